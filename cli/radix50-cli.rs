@@ -3,19 +3,21 @@
 // Copyright © 2023-2024 David Caldwell <david@porkrind.org>
 // License: MIT (see LICENSE.md file)
 
-#![feature(iter_intersperse)]
-
 use std::error::Error;
+use std::io::Read;
 
 use docopt::Docopt;
+use radix50::{Encoding, Endian, bytes::Packing, convert::{self, Direction, OnError, Options}};
 use serde::Deserialize;
 
-const USAGE: &'static str = r#"
+const USAGE: &str = r#"
 Usage:
   radix50 -h
-  radix50 [-h] decode  [--pdp10] [<word>...]
-  radix50 [-h] encode  [--pdp10] [--format=<format>] [<string>]
-  radix50 [-h] charset [--pdp10]
+  radix50 [-h] [-v...] decode  [--pdp10] [--extract-octal] [<word>...]
+  radix50 [-h] [-v...] decode  [--pdp10] [--parity=<parity>] [--packing=<packing>] --file=<file>
+  radix50 [-h] [-v...] decode  [--pdp10] [--parity=<parity>] [--packing=<packing>]
+  radix50 [-h] [-v...] encode  [--pdp10] [--format=<format>] [--parity=<parity>] [--packing=<packing>] [<string>]
+  radix50 [-h] [-v...] charset [--pdp10]
 
 Options:
   -h --help              Show this screen.
@@ -24,20 +26,47 @@ Options:
                          "raw" is a raw big endian binary byte stream.
   --pdp10                Use the PDP-10 radix-50 encoding instead
                          of the default PDP-11 encoding.
+  --extract-octal        Pull the words out of assembler listing lines.
+  --file=<file>          Decode <file> as a big endian binary stream
+                         (memory mapped, so big images are cheap).
+  --parity=<parity>      Check and clear the parity bit (the top bit) of
+                         each input byte, for paper tape images [default: none].
+                         <parity> can be: none, strip, even, odd, mark.
+                         "strip" clears the bit without checking it.
+  --packing=<packing>    Read or write raw 36 bit PDP-10 words packed into
+                         bytes, instead of 32 bit words (for encoding, this
+                         replaces --format). <packing> can be: core-dump,
+                         high-density, ansi-ascii, industry.
+  -v --verbose           Describe each processing step on stderr. Repeat
+                         (-vv) to also show timing.
 
 <word> is a word in decimal, hex, or octal (123, 0x7b, 0o173,
-and 0b1111011 are the same). The default PDP-11 encoding uses 16-bit
+and 0b1111011 are the same). Underscores between digits (0o177_777) and
+a trailing comma are ignored. The default PDP-11 encoding uses 16-bit
 words. PDP-10 encoding mode uses 32-bit words.
 
 If <string> or <word> is omitted, stdin is read as input.
 When decoding from stdin, stdin is read as a big endian binary stream.
 
+With --extract-octal, the input (the <word> arguments joined with spaces,
+or each line of stdin) is a MACRO-11 style listing line, such as:
+    23 000040 003223 075250         .RAD50  /ABCSY /
+The address is the first 6 digit octal number (it may only be preceded by
+an error flag and a line number), and the octal numbers after it are the
+words to decode. Anything else on the line is ignored.
+Relocation markers (' and G) after a word are ignored.
+
 The "charset" command will dump the radix-50 charset table.
 "#;
 #[derive(Debug, Deserialize)]
 struct Args {
     flag_format:      Format,
     flag_pdp10:       bool,
+    flag_extract_octal: bool,
+    flag_file:        Option<String>,
+    flag_parity:      Parity,
+    flag_packing:     Option<String>,
+    flag_verbose:     usize,
     cmd_decode:       bool,
     cmd_encode:       bool,
     cmd_charset:      bool,
@@ -48,109 +77,148 @@ struct Args {
 #[derive(Debug, Deserialize)]
 enum Format { Raw, Bin, Hex, Oct, Dec }
 
+#[derive(Debug, Deserialize)]
+enum Parity { None, Strip, Even, Odd, Mark }
+
+// Print to stderr if -v was given at least `level` times
+macro_rules! log {
+    ($verbosity:expr, $level:expr, $($arg:tt)*) => {
+        if $verbosity >= $level { eprintln!("radix50: {}", format!($($arg)*)) }
+    };
+}
+
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args: Args = Docopt::new(USAGE)
         .and_then(|d| d.deserialize())
         .unwrap_or_else(|e| e.exit());
 
+    let start = std::time::Instant::now();
+    let verbosity = args.flag_verbose;
+
+    let encoding = if args.flag_pdp10 { Encoding::Pdp10 } else { Encoding::Pdp11 };
+    log!(verbosity, 1, "encoding: {} ({} characters per {} bit word)", encoding, encoding.chars_per_word(), encoding.word_bytes() * 8);
+    let parity: convert::Parity = args.flag_parity.into();
+    if parity != convert::Parity::None { log!(verbosity, 1, "parity: {:?}", parity) }
+    let packing = args.flag_packing.as_deref().map(parse_packing).transpose()?;
+    if let Some(packing) = packing { log!(verbosity, 1, "packing: {:?}", packing) }
+
     if args.cmd_encode {
-        let to_encode = args.arg_string.map(|s| Ok(s)).unwrap_or_else(|| stdin_to_string())?;
-        match args.flag_pdp10 { true  => output_with_format(&radix50::pdp10::encode(&to_encode)?, args.flag_format)?,
-                                false => output_with_format(&radix50::pdp11::encode(&to_encode)?, args.flag_format)?}
+        if packing.is_none() { log!(verbosity, 1, "output format: {}", format!("{:?}", args.flag_format).to_lowercase()) }
+        let format = packing.map_or(args.flag_format.into(), convert::Format::Packed);
+        let options = Options { format, parity, ..Options::default() };
+        let input: Box<dyn Read> = match args.arg_string {
+            Some(ref s) => { log!(verbosity, 1, "input: command line ({} bytes)", s.len()); Box::new(s.as_bytes()) },
+            None        => { log!(verbosity, 1, "input: stdin");                                Box::new(std::io::stdin()) },
+        };
+        let count = convert::file_with_encoding(encoding, input, std::io::stdout(), options)?;
+        log!(verbosity, 1, "encoded {} words", count);
     }
 
 
     if args.cmd_decode {
-        match args.flag_pdp10 {
-            true  => println!("{}", radix50::pdp10::decode(&get_input(&args.arg_word)?)),
-            false => println!("{}", radix50::pdp11::decode(&get_input(&args.arg_word)?)),
-        };
+        if args.flag_extract_octal {
+            let lines = if args.arg_word.is_empty() { log!(verbosity, 1, "input: stdin (listing lines)");
+                                                      stdin_to_string()?.lines().map(str::to_string).collect() }
+                        else                        { log!(verbosity, 1, "input: command line (listing line)");
+                                                      vec![args.arg_word.join(" ")] };
+            let words = lines.iter().flat_map(|line| extract_octal(line)).collect::<Vec<_>>();
+            println!("{}", radix50::decode(encoding, parse_words(&words)?)?);
+            log!(verbosity, 1, "decoded {} words from {} lines", words.len(), lines.len());
+        } else if let (Some(ref path), convert::Parity::None, None) = (&args.flag_file, parity, packing) {
+            log!(verbosity, 1, "input: {} (raw big endian words, memory mapped)", path);
+            // Trailing bytes that don't make a whole word are ignored, like they are on stdin
+            let count = convert::decode_file(path, Endian::Big, encoding, OnError::Skip, std::io::stdout())?;
+            println!();
+            log!(verbosity, 1, "decoded {} words", count);
+        } else if args.arg_word.is_empty() {
+            // The parity bits have to be cleared (or the words unpacked), so the file can't be decoded straight from a
+            // memory map
+            let input: Box<dyn Read> = match args.flag_file {
+                Some(ref path) => { log!(verbosity, 1, "input: {} (raw words)", path); Box::new(std::fs::File::open(path)?) },
+                None           => { log!(verbosity, 1, "input: stdin (raw words)");    Box::new(std::io::stdin()) },
+            };
+            let format = packing.map_or(convert::Format::Raw(Endian::Big), convert::Format::Packed);
+            // Trailing bytes that don't make a whole word are ignored
+            let options = Options { direction: Direction::Decode, format, on_error: OnError::Skip, parity };
+            let count = convert::file_with_encoding(encoding, input, std::io::stdout(), options)?;
+            println!();
+            log!(verbosity, 1, "decoded {} words", count);
+        } else {
+            log!(verbosity, 1, "input: command line ({} words)", args.arg_word.len());
+            println!("{}", radix50::decode(encoding, parse_words(&args.arg_word)?)?);
+            log!(verbosity, 1, "decoded {} words", args.arg_word.len());
+        }
     }
 
 
     if args.cmd_charset {
         let header = format!("{:5} {:-3} {:>4} {:>4} {:>6}", "Char", "Dec", "Hex", "Oct", "Binary");
         println!("{}\n{:-<2$}", header, "", header.len());
-        for (i, c) in if args.flag_pdp10 { radix50::pdp10::RADIX50_DECODE }
-                                    else { radix50::pdp11::RADIX50_DECODE }.iter().enumerate() {
+        for (i, c) in encoding.charset() {
             println!("{:5} {:3} {:#04x} {:#04o} {:06b}",
-                if *c == ' ' { "space".to_string() } else { c.to_string() },
+                if c == ' ' { "space".to_string() } else { c.to_string() },
                 i, i, i, i);
         }
     }
 
 
+    log!(verbosity, 2, "took {:?}", start.elapsed());
     Ok(())
 }
 
-use std::{fmt::{Binary, LowerHex, Octal, Display}, mem::size_of};
-
-fn output_with_format<T>(encoded: &Vec<T>, format: Format) -> Result<(), Box<dyn Error>>
-where
-    T: Binary+LowerHex+Octal+Display+Copy, u64:From<T>
-{
-    use std::io::Write;
-    match format {
-        Format::Raw => {
-            let mut buffer: Vec<u8> = Vec::with_capacity(encoded.len() * size_of::<T>());
-            for w in encoded.iter() {
-                buffer.extend_from_slice(&(u64::from(*w)).to_be_bytes()[8-size_of::<T>()..]);
-            }
-            std::io::stdout().write(&buffer)?;
-        },
-        Format::Hex | Format::Oct | Format::Dec | Format::Bin => {
-            println!("{}", encoded.iter().map(|w| { match format {
-                                                        Format::Bin => format!("{:b}", w),
-                                                        Format::Hex => format!("{:x}", w),
-                                                        Format::Oct => format!("{:o}", w),
-                                                        Format::Dec => format!("{}",   w),
-                                                        _ => unreachable!(),
-                                                    }})
-                                         .intersperse(" ".to_string()).collect::<String>())
-        },
+impl From<Format> for convert::Format {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Raw => convert::Format::Raw(Endian::Big),
+            Format::Bin => convert::Format::Bin,
+            Format::Hex => convert::Format::Hex,
+            Format::Oct => convert::Format::Oct,
+            Format::Dec => convert::Format::Dec,
+        }
     }
+}
 
-    Ok(())
+impl From<Parity> for convert::Parity {
+    fn from(parity: Parity) -> Self {
+        match parity {
+            Parity::None  => convert::Parity::None,
+            Parity::Strip => convert::Parity::Strip,
+            Parity::Even  => convert::Parity::Even,
+            Parity::Odd   => convert::Parity::Odd,
+            Parity::Mark  => convert::Parity::Mark,
+        }
+    }
 }
 
-fn get_input<T>(words: &Vec<String>) -> Result<Vec<T>, Box<dyn Error>>
-where
-    T: std::convert::TryFrom<u64, Error=std::num::TryFromIntError>,
-{
-    if words.len() > 0 {
-        parse_words(words)
-    } else {
-        Ok(stdin_to_bytes()?.chunks_exact(size_of::<T>()).map(|a| {
-            a.iter().fold(0u64, |w, b| w << 8 | *b as u64)
-                .try_into().unwrap(/*Can't fail in chunk param is correct*/)
-        }).collect())
+fn parse_packing(packing: &str) -> Result<Packing, Box<dyn Error>> {
+    match packing {
+        "core-dump"    => Ok(Packing::CoreDump),
+        "high-density" => Ok(Packing::HighDensity),
+        "ansi-ascii"   => Ok(Packing::AnsiAscii),
+        "industry"     => Ok(Packing::IndustryCompatible),
+        _              => Err(format!("Unknown packing: {}", packing).into()),
     }
 }
 
-fn parse_words<T>(words: &Vec<String>) -> Result<Vec<T>, Box<dyn Error>>
-where
-    T: std::convert::TryFrom<u64, Error=std::num::TryFromIntError>,
-{
-    words.iter().map(|s| Ok(match s {
-        s if s.starts_with("0x") => u64::from_str_radix(&s[2..], 16),
-        s if s.starts_with("0o") => u64::from_str_radix(&s[2..],  8),
-        s if s.starts_with("0b") => u64::from_str_radix(&s[2..],  2),
-        s                        => u64::from_str_radix(s,       10),
-    }.map_err(|_| format!("Couldn't parse as integer: {}", s))?
-        .try_into().map_err(|_| format!("Couldn't convert {} to {}", s, std::any::type_name::<T>()))?))
+fn parse_words(words: &[String]) -> Result<Vec<u64>, Box<dyn Error>> {
+    words.iter().map(|s| Ok(radix50::parse::parse_word(s, 10).map_err(|_| format!("Couldn't parse as integer: {}", s))?))
         .collect()
 }
 
-fn stdin_to_bytes() -> Result<Vec<u8>, Box<dyn Error>> {
-    use std::io::Read;
-    let mut b = Vec::new();
-    std::io::stdin().read_to_end(&mut b)?;
-    Ok(b)
+// Find the words in a listing line: the octal numbers following the address (a 6 digit octal number that can only
+// be preceded by an error flag and a line number). They're returned in 0o form, ready for parse_words().
+fn extract_octal(line: &str) -> Vec<String> {
+    let is_octal = |s: &str| !s.is_empty() && s.bytes().all(|b| (b'0'..=b'7').contains(&b));
+    let tokens: Vec<&str> = line.split_whitespace().map(|token| token.trim_end_matches(['\'', 'G'])).collect();
+    let Some(address) = tokens.iter().take(3).position(|token| token.len() == 6 && is_octal(token)) else { return vec![] };
+    tokens[address+1..].iter()
+        .take_while(|token| is_octal(token))
+        .map(|token| format!("0o{}", token))
+        .collect()
 }
 
 fn stdin_to_string() -> Result<String, Box<dyn Error>> {
-    use std::io::Read;
     let mut s = String::new();
     std::io::stdin().read_to_string(&mut s)?;
     Ok(s)
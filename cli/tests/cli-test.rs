@@ -39,6 +39,7 @@ fn decode_args_pdp_11_bases() -> Result<(), Box<dyn std::error::Error>> {
     run(&["decode", "0o77111", "0o73311", "0o73301", "0o1445", "0o74740"], NONE, "THIS IS A TEST \n")?;
     run(&["decode", "0b111111001001001", "0b111011011001001",
                     "0b111011011000001", "0b1100100101", "0b111100111100000"], NONE,"THIS IS A TEST \n")?;
+    run(&["decode", "32_329,", "0x76c9,", "0o73_301,", "805,", "31200"],  NONE, "THIS IS A TEST \n")?;
     Ok(())
 }
 
@@ -60,6 +61,15 @@ fn decode_stdin() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn decode_file() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::temp_dir().join(format!("radix50-cli-decode-file-{}", std::process::id()));
+    std::fs::write(&path, [0x7e, 0x49, 0x76, 0xc9, 0x76, 0xc1, 0x03, 0x25, 0x79, 0xe0, 0x00])?;
+    let result = run(&["decode", "--file", path.to_str().unwrap()], NONE, "THIS IS A TEST \n");
+    std::fs::remove_file(&path)?;
+    result
+}
+
 #[test]
 fn encode_args_basic() -> Result<(), Box<dyn std::error::Error>> {
     run(&["encode", "THIS IS A TEST"], NONE, "32329 30409 30401 805 31200\n")?;
@@ -95,3 +105,88 @@ fn encode_stdin() -> Result<(), Box<dyn std::error::Error>> {
     run(&["encode", "--pdp10"], Some("THIS IS A TEST"), "3119342419 2970305215 3046400000\n")?;
     Ok(())
 }
+
+#[test]
+fn parity() -> Result<(), Box<dyn std::error::Error>> {
+    let mark: Vec<u8> = b"THIS IS A TEST".iter().map(|b| b | 0x80).collect();
+    run(&["encode", "--parity=mark"],   Some(mark.clone()), "32329 30409 30401 805 31200\n")?;
+    run(&["encode", "--parity", "strip"], Some(mark.clone()), "32329 30409 30401 805 31200\n")?;
+    run(&["decode", "--parity=odd"],    Some(&[0xfe, 0x49]), "THI\n")?;
+    Command::cargo_bin("radix50")?
+        .args(["encode", "--parity=even"])
+        .write_stdin(mark)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Parity { offset: 1 }"));
+
+    let path = std::env::temp_dir().join(format!("radix50-cli-decode-parity-{}", std::process::id()));
+    std::fs::write(&path, [0xfe, 0x49])?;
+    let result = run(&["decode", "--parity=odd", "--file", path.to_str().unwrap()], NONE, "THI\n");
+    std::fs::remove_file(&path)?;
+    result
+}
+
+#[test]
+fn packing() -> Result<(), Box<dyn std::error::Error>> {
+    let core_dump = [0x0b, 0x9e, 0xd6, 0x35, 0x03, 0x0b, 0x10, 0xb4, 0x2b, 0x0f, 0x0b, 0x59, 0x46, 0x00, 0x00];
+    run(&["encode", "--pdp10", "--packing=core-dump"], Some("THIS IS A TEST"), &core_dump[..])?;
+    run(&["decode", "--pdp10", "--packing=core-dump"], Some(&core_dump),       "THIS IS A TEST    \n")?;
+    let high_density = [0x0b, 0x9e, 0xd6, 0x35, 0x30, 0xb1, 0x0b, 0x42, 0xbf, 0x0b, 0x59, 0x46, 0x00, 0x00];
+    run(&["encode", "--pdp10", "--packing=high-density"], Some("THIS IS A TEST"), &high_density[..])?;
+    run(&["decode", "--pdp10", "--packing=high-density"], Some(&high_density),    "THIS IS A TEST    \n")?;
+    run(&["encode", "--pdp10", "--packing=industry"], Some("THIS IS A TEST"), &[0x0b, 0x9e, 0xd6, 0x35, 0x0b, 0x10, 0xb4, 0x2b, 0x0b, 0x59, 0x46, 0x00][..])?;
+    Command::cargo_bin("radix50")?
+        .args(["decode", "--packing=squished"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown packing: squished"));
+    Ok(())
+}
+
+#[test]
+fn verbose() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("radix50")?
+        .args(["encode", "-v", "THIS IS A TEST"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("32329 30409 30401 805 31200\n"))
+        .stderr(predicate::str::contains("encoding: pdp11")
+                .and(predicate::str::contains("input: command line (14 bytes)"))
+                .and(predicate::str::contains("encoded 5 words"))
+                .and(predicate::str::contains("took").not()));
+    Command::cargo_bin("radix50")?
+        .args(["decode", "-vv", "--pdp10"])
+        .write_stdin(&[0xb9, 0xed, 0x63, 0x53, 0xb1, 0x0b, 0x42, 0xbf, 0xb5, 0x94, 0x60, 0x00][..])
+        .assert()
+        .success()
+        .stdout(predicate::eq("THIS IS A TEST    \n"))
+        .stderr(predicate::str::contains("encoding: pdp10")
+                .and(predicate::str::contains("input: stdin"))
+                .and(predicate::str::contains("decoded 3 words"))
+                .and(predicate::str::contains("took")));
+    Ok(())
+}
+
+#[test]
+fn charset() -> Result<(), Box<dyn std::error::Error>> {
+    let pdp11 = String::from_utf8(Command::cargo_bin("radix50")?.args(["charset"]).output()?.stdout)?;
+    let pdp10 = String::from_utf8(Command::cargo_bin("radix50")?.args(["charset", "--pdp10"]).output()?.stdout)?;
+    assert_eq!(pdp11.lines().count(), 42);
+    assert_eq!(pdp11.lines().nth(2), Some("space   0 0x00 0o00 000000"));
+    assert_eq!(pdp11.lines().nth(3), Some("A       1 0x01 0o01 000001"));
+    assert_eq!(pdp10.lines().nth(3), Some("0       1 0x01 0o01 000001"));
+    assert_eq!(pdp10.lines().last(),  Some("%      39 0x27 0o47 100111"));
+    Ok(())
+}
+
+#[test]
+fn decode_extract_octal() -> Result<(), Box<dyn std::error::Error>> {
+    run(&["decode", "--extract-octal", "    23 000040 003223 075250         .RAD50  /ABCSY /"], NONE, "ABCSY \n")?;
+    run(&["decode", "--extract-octal", "23", "000040", "003223", "075250", ".RAD50", "/ABCSY", "/"], NONE, "ABCSY \n")?;
+    run(&["decode", "--extract-octal"], Some("U    23 000040' 003223 075250G  .RAD50 /ABCSY /  ; 1234\n"
+                                              .to_owned() + "     24 000044  077111 073311\n"
+                                              + "     25                  ; No words here 000050 123456\n"),
+        "ABCSY THIS I\n")?;
+    run(&["decode", "--extract-octal", "--pdp10"], Some("     1 000000' 26633472376  ; SYMBOL"), "SYMBOL\n")?;
+    Ok(())
+}
@@ -0,0 +1,272 @@
+// Non-blocking RADIX-50 stream adapters for tokio
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! [`AsyncRead`]/[`AsyncWrite`] adapters that encode and decode RADIX-50 on the fly.
+//!
+//! [`AsyncEncoder`] wraps a writer of raw words and takes text. [`AsyncDecoder`] wraps a reader of raw words and
+//! gives text. They work like [`Encoder`](crate::Encoder) and [`Codec::decode`], but a piece at a time and without
+//! blocking, so a service can sit between a socket and an emulator (say) without buffering whole records.
+//!
+//! The words are packed into bytes the same way as [`convert`](crate::convert)'s
+//! [`Format::Raw`](crate::convert::Format::Raw). Problems with the data (illegal characters, a partial word at the
+//! end) are [`io::ErrorKind::InvalidData`] errors wrapping an [`Error`].
+
+use std::io;
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{Codec, DefaultCodec, Endian, Error, bytes};
+
+/// An [`AsyncWrite`] that RADIX-50 encodes the text written to it and writes the words to `W`.
+///
+/// The text is space padded to a whole word when the encoder is shut down, so be sure to call
+/// [`shutdown()`](tokio::io::AsyncWriteExt::shutdown) when you're done.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Endian, async_io::AsyncEncoder};
+/// # use tokio::io::AsyncWriteExt;
+/// # async fn example() -> std::io::Result<()> {
+/// let mut encoder: AsyncEncoder<_> = AsyncEncoder::new(Vec::new(), Endian::Little);
+/// encoder.write_all(b"THIS IS").await?;
+/// encoder.write_all(b" A TEST").await?;
+/// encoder.shutdown().await?;
+/// assert_eq!(encoder.into_inner(), [0x49, 0x7e, 0xc9, 0x76, 0xc1, 0x76, 0x25, 0x03, 0xe0, 0x79]);
+/// # Ok(())
+/// # }
+/// # // In-memory readers and writers are always ready, so a no-op waker can drive it.
+/// # let mut future = std::pin::pin!(example());
+/// # let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+/// # while std::future::Future::poll(future.as_mut(), &mut cx).is_pending() {}
+/// ```
+pub struct AsyncEncoder<W, C: Codec = DefaultCodec> {
+    inner: W,
+    endian: Endian,
+    pending: u64,
+    pending_chars: usize,
+    pos: usize,
+    out: Vec<u8>,
+    padded: bool,
+    codec: PhantomData<fn() -> C>, // Doesn't hold a C, so it's Unpin whatever C is
+}
+
+impl<W: AsyncWrite + Unpin, C: Codec> AsyncEncoder<W, C> {
+    /// Wrap `inner`, which gets the words in `endian` byte order.
+    pub fn new(inner: W, endian: Endian) -> Self {
+        AsyncEncoder { inner, endian, pending: 0, pending_chars: 0, pos: 0, out: Vec::new(), padded: false, codec: PhantomData }
+    }
+
+    /// Get the wrapped writer back. Anything not yet flushed is lost.
+    pub fn into_inner(self) -> W { self.inner }
+
+    fn push(&mut self, code: u8) {
+        self.pending = self.pending * 40 + code as u64;
+        self.pending_chars += 1;
+        if self.pending_chars == C::CHARS {
+            bytes::write_word(self.pending, size_of::<C::Word>(), self.endian, &mut self.out);
+            self.pending = 0;
+            self.pending_chars = 0;
+        }
+    }
+
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.out.is_empty() {
+            let n = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.out))?;
+            if n == 0 { return Poll::Ready(Err(io::ErrorKind::WriteZero.into())) }
+            self.out.drain(..n);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin, C: Codec> AsyncWrite for AsyncEncoder<W, C> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        for (i, &b) in buf.iter().enumerate() {
+            // Every RADIX-50 character is ASCII, so the text can be taken a byte at a time
+            let Some(code) = b.is_ascii().then(|| C::char_to_code(b as char)).flatten() else {
+                // Report what was taken so far, and the error when the caller writes the rest
+                if i > 0 { return Poll::Ready(Ok(i)) }
+                let char = String::from_utf8_lossy(buf).chars().next().expect("there's at least 1 byte");
+                return Poll::Ready(Err(io::Error::new(io::ErrorKind::InvalidData, Error::IllegalChar { char, pos: this.pos + 1 })));
+            };
+            this.pos += 1;
+            this.push(code);
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if !this.padded {
+            let space = C::char_to_code(' ').expect("space is in every RADIX-50 character set");
+            while this.pending_chars != 0 { this.push(space) }
+            this.padded = true;
+        }
+        ready!(this.poll_drain(cx))?;
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+/// An [`AsyncRead`] that reads raw words from `R` and gives their decoded RADIX-50 text.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Endian, async_io::AsyncDecoder};
+/// # use tokio::io::AsyncReadExt;
+/// # async fn example() -> std::io::Result<()> {
+/// let words: &[u8] = &[0x7e, 0x49, 0x76, 0xc9, 0x76, 0xc1, 0x03, 0x25, 0x79, 0xe0];
+/// let mut text = String::new();
+/// AsyncDecoder::<_>::new(words, Endian::Big).read_to_string(&mut text).await?;
+/// assert_eq!(text, "THIS IS A TEST ");
+/// # Ok(())
+/// # }
+/// # // In-memory readers and writers are always ready, so a no-op waker can drive it.
+/// # let mut future = std::pin::pin!(example());
+/// # let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+/// # while std::future::Future::poll(future.as_mut(), &mut cx).is_pending() {}
+/// ```
+pub struct AsyncDecoder<R, C: Codec = DefaultCodec> {
+    inner: R,
+    endian: Endian,
+    raw: Vec<u8>,
+    text: Vec<u8>,
+    text_pos: usize,
+    eof: bool,
+    codec: PhantomData<fn() -> C>, // Doesn't hold a C, so it's Unpin whatever C is
+}
+
+impl<R: AsyncRead + Unpin, C: Codec> AsyncDecoder<R, C> {
+    /// Wrap `inner`, which has the words in `endian` byte order.
+    pub fn new(inner: R, endian: Endian) -> Self {
+        AsyncDecoder { inner, endian, raw: Vec::new(), text: Vec::new(), text_pos: 0, eof: false, codec: PhantomData }
+    }
+
+    /// Get the wrapped reader back. Anything read from it but not yet decoded is lost.
+    pub fn into_inner(self) -> R { self.inner }
+}
+
+impl<R: AsyncRead + Unpin, C: Codec> AsyncRead for AsyncDecoder<R, C> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let size = size_of::<C::Word>();
+        loop {
+            if this.text_pos < this.text.len() {
+                let n = buf.remaining().min(this.text.len() - this.text_pos);
+                buf.put_slice(&this.text[this.text_pos..this.text_pos + n]);
+                this.text_pos += n;
+                return Poll::Ready(Ok(()));
+            }
+            if this.eof {
+                // Report a partial word once, and then act like a normal end of file.
+                let trailing = core::mem::take(&mut this.raw).len();
+                if trailing > 0 {
+                    return Poll::Ready(Err(io::Error::new(io::ErrorKind::InvalidData, Error::TrailingBytes { len: trailing })));
+                }
+                return Poll::Ready(Ok(()));
+            }
+
+            let mut chunk = [0; 1024];
+            let mut read = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut read))?;
+            if read.filled().is_empty() { this.eof = true; continue }
+            this.raw.extend_from_slice(read.filled());
+
+            let whole = this.raw.len() / size * size;
+            let words = bytes::words(&this.raw[..whole], size, this.endian)
+                .map(|w| C::Word::try_from(w).ok().expect("a word's worth of bytes fits in a word"));
+            this.text = C::decode(words).into_bytes();
+            this.text_pos = 0;
+            this.raw.drain(..whole);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pdp10::Pdp10, pdp11::{self, Pdp11}};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // The in-memory readers and writers are always ready, so there's no need for a whole runtime.
+    fn block_on<F: core::future::Future>(future: F) -> F::Output {
+        let mut future = core::pin::pin!(future);
+        let mut cx = Context::from_waker(std::task::Waker::noop());
+        loop {
+            if let Poll::Ready(out) = future.as_mut().poll(&mut cx) { return out }
+        }
+    }
+
+    fn encode<C: Codec>(pieces: &[&str], endian: Endian) -> io::Result<Vec<u8>> {
+        block_on(async {
+            let mut encoder = AsyncEncoder::<_, C>::new(Vec::new(), endian);
+            for piece in pieces { encoder.write_all(piece.as_bytes()).await? }
+            encoder.shutdown().await?;
+            Ok(encoder.into_inner())
+        })
+    }
+
+    fn decode<C: Codec>(bytes: &[u8], endian: Endian) -> io::Result<String> {
+        block_on(async {
+            let mut text = String::new();
+            AsyncDecoder::<_, C>::new(bytes, endian).read_to_string(&mut text).await?;
+            Ok(text)
+        })
+    }
+
+    #[test]
+    fn round_trip() {
+        let words: Vec<u8> = pdp11::encode("THIS IS A TEST").unwrap().iter().flat_map(|w| w.to_be_bytes()).collect();
+        assert_eq!(encode::<Pdp11>(&["TH", "IS IS A", "", " TEST"], Endian::Big).unwrap(), words);
+        assert_eq!(decode::<Pdp11>(&words, Endian::Big).unwrap(), "THIS IS A TEST ");
+        assert_eq!(encode::<Pdp10>(&["THIS IS A TEST"], Endian::Little).unwrap(),
+                   [0x53, 0x63, 0xed, 0xb9, 0xbf, 0x42, 0x0b, 0xb1, 0x00, 0x60, 0x94, 0xb5]);
+        assert_eq!(decode::<Pdp10>(&[0x53, 0x63, 0xed, 0xb9, 0xbf, 0x42, 0x0b, 0xb1, 0x00, 0x60, 0x94, 0xb5], Endian::Little).unwrap(),
+                   "THIS IS A TEST    ");
+        let pdp = [0xed, 0xb9, 0x53, 0x63, 0x0b, 0xb1, 0xbf, 0x42, 0x94, 0xb5, 0x00, 0x60];
+        assert_eq!(encode::<Pdp10>(&["THIS IS A TEST"], Endian::Pdp).unwrap(), pdp);
+        assert_eq!(decode::<Pdp10>(&pdp, Endian::Pdp).unwrap(), "THIS IS A TEST    ");
+        assert_eq!(encode::<Pdp11>(&[], Endian::Big).unwrap(), []);
+        assert_eq!(decode::<Pdp11>(&[], Endian::Big).unwrap(), "");
+
+        // More than one read's worth
+        let text = "ABC".repeat(1000);
+        let words = encode::<Pdp11>(&[&text], Endian::Little).unwrap();
+        assert_eq!(decode::<Pdp11>(&words, Endian::Little).unwrap(), text);
+    }
+
+    #[test]
+    fn errors() {
+        let inner = |e: io::Error| e.into_inner().unwrap().downcast::<Error>().map(|e| *e).unwrap();
+        assert_eq!(inner(encode::<Pdp11>(&["AB", "C_"], Endian::Big).unwrap_err()), Error::IllegalChar { char: '_', pos: 4 });
+        assert_eq!(inner(encode::<Pdp11>(&["ABÉ"], Endian::Big).unwrap_err()), Error::IllegalChar { char: 'É', pos: 3 });
+        assert_eq!(inner(decode::<Pdp11>(&[0x7e, 0x49, 0x76], Endian::Big).unwrap_err()), Error::TrailingBytes { len: 1 });
+
+        // The good text before an illegal character is written, and the error comes on the next write
+        let mut encoder: AsyncEncoder<Vec<u8>, Pdp11> = AsyncEncoder::new(Vec::new(), Endian::Big);
+        let mut cx = Context::from_waker(std::task::Waker::noop());
+        let mut write = |buf: &[u8]| match Pin::new(&mut encoder).poll_write(&mut cx, buf) {
+            Poll::Ready(result) => result.map_err(inner),
+            Poll::Pending       => panic!("in-memory writers are always ready"),
+        };
+        assert_eq!(write(b"ABCD_E"), Ok(4));
+        assert_eq!(write(b"_E"), Err(Error::IllegalChar { char: '_', pos: 5 }));
+        assert_eq!(write(b"_E"), Err(Error::IllegalChar { char: '_', pos: 5 }));
+        assert_eq!(write(b"E"), Ok(1));
+        block_on(encoder.shutdown()).unwrap();
+        assert_eq!(encoder.into_inner(), pdp11::encode("ABCDE").unwrap().iter().flat_map(|w| w.to_be_bytes()).collect::<Vec<_>>());
+    }
+}
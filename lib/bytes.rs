@@ -0,0 +1,391 @@
+// Packing words into bytes
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Packing words into byte streams and unpacking them again.
+//!
+//! [`read_word`], [`write_word`], and [`words`] handle words of 1 to 8 bytes in any [`Endian`] byte order (16 bit
+//! PDP-11 words, or PDP-10 RADIX-50 words stored in 32 bits). 36 bit PDP-10 words don't fit in a whole number of
+//! bytes, so media images pack them in one of several ways (see [`Packing`]).
+//!
+//! Words are `u64`s, whatever their size.
+//!
+//! [`Counted`] reads and writes counted records: a character count followed by the RADIX-50 words holding that many
+//! characters.
+//!
+//! # Examples
+//! ```
+//! # use radix50::{Endian, bytes};
+//! let mut out = Vec::new();
+//! bytes::write_word(0x12345678, 4, Endian::Pdp, &mut out);
+//! assert_eq!(out, [0x34, 0x12, 0x78, 0x56]);
+//! assert_eq!(bytes::read_word(&out, Endian::Pdp), 0x12345678);
+//! assert_eq!(bytes::words(&out, 2, Endian::Little).collect::<Vec<_>>(), [0x1234, 0x5678]);
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::{Endian, Error};
+
+/// Read a word from `bytes` (all of them) in `endian` byte order.
+///
+/// # Panics
+/// If there are more than 8 bytes.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Endian, bytes::read_word};
+/// assert_eq!(read_word(&[0x12, 0x34], Endian::Big), 0x1234);
+/// assert_eq!(read_word(&[0x12, 0x34], Endian::Little), 0x3412);
+/// assert_eq!(read_word(&[0x12, 0x34, 0x56, 0x78], Endian::Pdp), 0x34127856);
+/// ```
+pub fn read_word(bytes: &[u8], endian: Endian) -> u64 {
+    assert!(bytes.len() <= 8, "words are at most 8 bytes");
+    match endian {
+        Endian::Big    => bytes.iter()       .fold(0u64, |w, b| w << 8 | *b as u64),
+        Endian::Little => bytes.iter().rev() .fold(0u64, |w, b| w << 8 | *b as u64),
+        Endian::Pdp    => bytes.chunks(2)   .fold(0u64, |w, pair| pair.iter().rev().fold(w, |w, b| w << 8 | *b as u64)),
+    }
+}
+
+/// Append the bottom `size` bytes of `word` to `out`, in `endian` byte order.
+///
+/// # Panics
+/// If `size` is more than 8.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Endian, bytes::write_word};
+/// let mut out = Vec::new();
+/// write_word(0x123456, 2, Endian::Big, &mut out);
+/// write_word(0x123456, 3, Endian::Little, &mut out);
+/// assert_eq!(out, [0x34, 0x56, 0x56, 0x34, 0x12]);
+/// ```
+pub fn write_word(word: u64, size: usize, endian: Endian, out: &mut Vec<u8>) {
+    assert!(size <= 8, "words are at most 8 bytes");
+    let be = &word.to_be_bytes()[8 - size..];
+    match endian {
+        Endian::Big    => out.extend_from_slice(be),
+        Endian::Little => out.extend(be.iter().rev()),
+        Endian::Pdp    => out.extend(be.chunks(2).flat_map(|pair| pair.iter().rev())),
+    }
+}
+
+/// Iterate over the whole `size` byte words in `bytes`. Any bytes left over at the end are ignored.
+///
+/// # Panics
+/// If `size` is 0 or more than 8.
+pub fn words(bytes: &[u8], size: usize, endian: Endian) -> impl Iterator<Item = u64> + '_ {
+    assert!((1..=8).contains(&size), "words are 1 to 8 bytes");
+    bytes.chunks_exact(size).map(move |b| read_word(b, endian))
+}
+
+/// How 36 bit PDP-10 words are packed into a byte stream.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum Packing {
+    /// Each word in 5 bytes: its top 32 bits (big endian) in the first 4, and its bottom 4 bits in the low half of
+    /// the 5th. This is how most PDP-10 images on bitsavers (and SIMH's tape images) are packed.
+    CoreDump,
+    /// Each pair of words in 9 bytes, as one big endian 72 bit number. A last odd word takes 5 bytes, with the
+    /// low half of the 5th byte unused.
+    HighDensity,
+    /// Each word in 5 bytes, as five 7 bit ASCII characters (one in the bottom 7 bits of each byte) with the
+    /// word's last bit in the top bit of the 5th byte. This is how TOPS-10 writes text files to ANSI labeled tapes.
+    AnsiAscii,
+    /// Each word in 4 bytes: its top 32 bits, big endian. The bottom 4 bits are lost (they're 0 when unpacking).
+    /// This is industry compatible mode (also called image mode), for tapes meant to be read on other machines.
+    IndustryCompatible,
+}
+
+impl Packing {
+    /// How many words pack into how many bytes (the smallest whole number of each).
+    pub fn group(self) -> (usize, usize) {
+        match self {
+            Packing::CoreDump           => (1, 5),
+            Packing::HighDensity        => (2, 9),
+            Packing::AnsiAscii          => (1, 5),
+            Packing::IndustryCompatible => (1, 4),
+        }
+    }
+
+    /// Pack `words` onto the end of `out`. Only the bottom 36 bits of each word are used.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::bytes::Packing;
+    /// let mut bytes = Vec::new();
+    /// Packing::CoreDump.pack(&[0o123456_701234], &mut bytes);
+    /// assert_eq!(bytes, [0x29, 0xcb, 0xb8, 0x29, 0x0c]);
+    ///
+    /// let mut bytes = Vec::new();
+    /// Packing::AnsiAscii.pack(&[0o406050_342213], &mut bytes); // "ABCDE", and bit 35 set
+    /// assert_eq!(bytes, b"ABCD\xc5");
+    ///
+    /// let mut bytes = Vec::new();
+    /// Packing::HighDensity.pack(&[0o123456_701234, 0o777777_000001, 0o1], &mut bytes);
+    /// assert_eq!(bytes, [0x29, 0xcb, 0xb8, 0x29, 0xcf, 0xff, 0xfc, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x10]);
+    /// ```
+    pub fn pack(self, words: &[u64], out: &mut Vec<u8>) {
+        let (group, _) = self.group();
+        for w in words.chunks(group) {
+            match (self, w) {
+                (Packing::CoreDump, &[w]) => out.extend([(w >> 28) as u8, (w >> 20) as u8, (w >> 12) as u8, (w >> 4) as u8, w as u8 & 0xf]),
+                (Packing::HighDensity, &[a, b]) => out.extend_from_slice(&(((a & WORD36) as u128) << 36 | (b & WORD36) as u128).to_be_bytes()[7..]),
+                (Packing::HighDensity, &[w]) => out.extend_from_slice(&((w & WORD36) << 4).to_be_bytes()[3..]),
+                (Packing::AnsiAscii, &[w]) => out.extend([(w >> 29) as u8 & 0x7f, (w >> 22) as u8 & 0x7f, (w >> 15) as u8 & 0x7f,
+                                                          (w >> 8) as u8 & 0x7f, (w >> 1) as u8 & 0x7f | (w as u8 & 1) << 7]),
+                (Packing::IndustryCompatible, &[w]) => out.extend_from_slice(&((w >> 4) as u32).to_be_bytes()),
+                _ => unreachable!("chunks are never bigger than a group"),
+            }
+        }
+    }
+
+    /// Unpack the words in `bytes` onto the end of `words`. Returns how many bytes were left over at the end (not
+    /// enough for another word).
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::bytes::Packing;
+    /// let mut words = Vec::new();
+    /// assert_eq!(Packing::CoreDump.unpack(&[0x29, 0xcb, 0xb8, 0x29, 0x0c, 0xff], &mut words), 1);
+    /// assert_eq!(words, [0o123456_701234]);
+    /// ```
+    pub fn unpack(self, bytes: &[u8], words: &mut Vec<u64>) -> usize {
+        let big_endian = |b: &[u8]| b.iter().fold(0u128, |w, b| w << 8 | *b as u128);
+        let (_, group) = self.group();
+        let chunks = bytes.chunks_exact(group);
+        let mut rest = chunks.remainder();
+        for b in chunks {
+            match self {
+                Packing::CoreDump    => words.push((big_endian(&b[..4]) << 4) as u64 | (b[4] & 0xf) as u64),
+                Packing::HighDensity => { let w = big_endian(b); words.extend([(w >> 36) as u64, w as u64 & WORD36]) },
+                Packing::AnsiAscii   => words.push(b.iter().fold(0u64, |w, b| w << 7 | (b & 0x7f) as u64) << 1 | (b[4] >> 7) as u64),
+                Packing::IndustryCompatible => words.push((big_endian(b) << 4) as u64),
+            }
+        }
+        if self == Packing::HighDensity && rest.len() >= 5 {
+            words.push((big_endian(&rest[..5]) >> 4) as u64);
+            rest = &rest[5..];
+        }
+        rest.len()
+    }
+}
+
+/// The layout of a counted record, which several on-disk structures use to store names: a character count, then
+/// the RADIX-50 words that hold that many characters (the count rounded up to a whole number of words). The unused
+/// characters at the end of the last word are padding (spaces, normally).
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "pdp11")] {
+/// # use radix50::{Endian, bytes::Counted, pdp11};
+/// let layout = Counted { count_size: 1, word_size: 2, chars_per_word: pdp11::CHARS_PER_WORD, endian: Endian::Little };
+/// let mut out = Vec::new();
+/// layout.write(4, &pdp11::encode("SWAP").unwrap().iter().map(|&w| w as u64).collect::<Vec<_>>(), &mut out)?;
+/// assert_eq!(out.len(), layout.len(4));
+/// let (chars, words) = layout.read(&out)?;
+/// assert_eq!(&pdp11::decode(words.iter().map(|&w| w as u16))[..chars], "SWAP");
+/// # }
+/// # Ok::<(), radix50::Error>(())
+/// ```
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct Counted {
+    /// The size of the count, in bytes.
+    pub count_size: usize,
+    /// The size of each word, in bytes.
+    pub word_size: usize,
+    /// How many characters each word holds.
+    pub chars_per_word: usize,
+    /// The byte order of the count and the words.
+    pub endian: Endian,
+}
+
+impl Counted {
+    /// The layout for an [`Encoding`](crate::Encoding), with a `count_size` byte count.
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    pub fn for_encoding(encoding: crate::Encoding, count_size: usize, endian: Endian) -> Counted {
+        Counted { count_size, word_size: encoding.word_bytes(), chars_per_word: encoding.chars_per_word(), endian }
+    }
+
+    /// How many words it takes to hold `chars` characters.
+    pub fn words(self, chars: usize) -> usize { chars.div_ceil(self.chars_per_word) }
+
+    /// How many bytes a record of `chars` characters takes, count included.
+    pub fn len(self, chars: usize) -> usize { self.count_size + self.words(chars) * self.word_size }
+
+    /// The largest count that fits in the count.
+    pub fn max_chars(self) -> usize {
+        usize::try_from(u64::MAX >> (64 - 8 * self.count_size)).unwrap_or(usize::MAX)
+    }
+
+    /// Read the record at the start of `bytes`. Returns the character count and the words (all of them, padding
+    /// included). The record is [`len`](Counted::len)`(count)` bytes long, and anything after it is ignored.
+    ///
+    /// It returns an [`Error::Truncated`] if `bytes` ends before the record does.
+    ///
+    /// # Panics
+    /// If the count or word size is 0 or more than 8, or `chars_per_word` is 0.
+    pub fn read(self, bytes: &[u8]) -> Result<(usize, Vec<u64>), Error> {
+        self.check();
+        let truncated = |needed| Error::Truncated { len: bytes.len(), needed };
+        let count = bytes.get(..self.count_size).ok_or(truncated(self.count_size))?;
+        let chars = usize::try_from(read_word(count, self.endian)).map_err(|_| truncated(usize::MAX))?;
+        let needed = self.words(chars).checked_mul(self.word_size).and_then(|n| n.checked_add(self.count_size)).unwrap_or(usize::MAX);
+        let data = bytes.get(self.count_size..needed).ok_or(truncated(needed))?;
+        Ok((chars, words(data, self.word_size, self.endian).collect()))
+    }
+
+    /// Append a record of `chars` characters held in `words` to `out`. If there are fewer words than the count
+    /// needs, the rest are written as 0 (spaces).
+    ///
+    /// It returns an [`Error::InputTooLong`] if `chars` doesn't fit in the count, or if there are more words than
+    /// the count needs (`len` is then the number of characters the words hold). `out` isn't changed if there's an
+    /// error.
+    ///
+    /// # Panics
+    /// If the count or word size is 0 or more than 8, or `chars_per_word` is 0.
+    pub fn write(self, chars: usize, words: &[u64], out: &mut Vec<u8>) -> Result<(), Error> {
+        self.check();
+        if chars > self.max_chars() { return Err(Error::InputTooLong { len: chars, max: self.max_chars() }) }
+        let needed = self.words(chars);
+        if words.len() > needed {
+            return Err(Error::InputTooLong { len: words.len() * self.chars_per_word, max: needed * self.chars_per_word })
+        }
+        write_word(chars as u64, self.count_size, self.endian, out);
+        for &w in words.iter().chain(core::iter::repeat_n(&0, needed - words.len())) {
+            write_word(w, self.word_size, self.endian, out);
+        }
+        Ok(())
+    }
+
+    /// Read the record at the start of `bytes` as a string, decoding it with `encoding` (see [`read`](Counted::read))
+    /// and dropping the padding.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{Encoding, Endian, bytes::Counted};
+    /// let layout = Counted::for_encoding(Encoding::Pdp11, 2, Endian::Little);
+    /// let mut out = Vec::new();
+    /// layout.write_str(Encoding::Pdp11, "HELLO", &mut out)?;
+    /// assert_eq!(out.len(), 6);
+    /// assert_eq!(layout.read_str(Encoding::Pdp11, &out)?, "HELLO");
+    /// # Ok::<(), radix50::Error>(())
+    /// ```
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    pub fn read_str(self, encoding: crate::Encoding, bytes: &[u8]) -> Result<alloc::string::String, Error> {
+        let (chars, words) = self.read(bytes)?;
+        Ok(crate::decode(encoding, words)?.chars().take(chars).collect())
+    }
+
+    /// Encode `s` with `encoding` and append it to `out` as a record (see [`write`](Counted::write)).
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    pub fn write_str(self, encoding: crate::Encoding, s: &str, out: &mut Vec<u8>) -> Result<(), Error> {
+        self.write(s.chars().count(), &crate::encode(encoding, s)?, out)
+    }
+
+    fn check(self) {
+        assert!((1..=8).contains(&self.count_size) && (1..=8).contains(&self.word_size), "counts and words are 1 to 8 bytes");
+        assert!(self.chars_per_word > 0, "words hold at least 1 character");
+    }
+}
+
+// The bits of a 36 bit word
+const WORD36: u64 = 0o777777_777777;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn endians() {
+        for endian in [Endian::Big, Endian::Little, Endian::Pdp] {
+            for size in 1..=8 {
+                let word = 0x0123_4567_89ab_cdef & (u64::MAX >> (64 - size * 8));
+                let mut out = Vec::new();
+                write_word(word, size, endian, &mut out);
+                assert_eq!(out.len(), size);
+                assert_eq!(read_word(&out, endian), word, "{:?} {}", endian, size);
+            }
+        }
+        let mut out = Vec::new();
+        write_word(0x0123_4567_89ab, 6, Endian::Pdp, &mut out);
+        assert_eq!(out, [0x23, 0x01, 0x67, 0x45, 0xab, 0x89]);
+        assert_eq!(words(&[1, 2, 3, 4, 5], 2, Endian::Pdp).collect::<Vec<_>>(), [0x0201, 0x0403]);
+        assert_eq!(read_word(&[], Endian::Big), 0);
+    }
+
+    #[test]
+    fn packings() {
+        let words36 = [0o406050_342213, 0o777777_777777, 0, 0o123456_701234];
+        for packing in [Packing::CoreDump, Packing::HighDensity, Packing::AnsiAscii, Packing::IndustryCompatible] {
+            let mut bytes = Vec::new();
+            packing.pack(&words36, &mut bytes);
+            let (group_words, group_bytes) = packing.group();
+            assert_eq!(bytes.len(), words36.len() / group_words * group_bytes, "{:?}", packing);
+            let mut unpacked = Vec::new();
+            assert_eq!(packing.unpack(&bytes, &mut unpacked), 0);
+            // Industry compatible mode drops the bottom 4 bits
+            let kept = if packing == Packing::IndustryCompatible { !0o17 } else { !0 };
+            assert_eq!(unpacked, words36.map(|w| w & kept), "{:?}", packing);
+        }
+
+        let mut high_density = Vec::new();
+        Packing::HighDensity.pack(&words36[..3], &mut high_density);
+        assert_eq!(high_density.len(), 14);
+        let mut unpacked = vec![];
+        assert_eq!(Packing::HighDensity.unpack(&high_density[..13], &mut unpacked), 4);
+        assert_eq!(unpacked, words36[..2]);
+
+        let mut ascii = Vec::new();
+        assert_eq!(Packing::AnsiAscii.unpack(b"HELLO WORLD", &mut ascii), 1);
+        assert_eq!(ascii.iter().flat_map(|w| (0..5).rev().map(move |n| (w >> (1 + n * 7) & 0x7f) as u8)).collect::<Vec<_>>(), b"HELLO WORL");
+        let mut industry = Vec::new();
+        Packing::IndustryCompatible.pack(&[0o123456_701234], &mut industry);
+        assert_eq!(industry, [0x29, 0xcb, 0xb8, 0x29]);
+    }
+
+    #[test]
+    fn counted() {
+        let layout = Counted { count_size: 1, word_size: 2, chars_per_word: 3, endian: Endian::Big };
+        assert_eq!((layout.words(0), layout.words(3), layout.words(4)), (0, 1, 2));
+        assert_eq!((layout.len(0), layout.len(4)), (1, 5));
+        assert_eq!(layout.max_chars(), 255);
+
+        let mut out = Vec::new();
+        layout.write(4, &[0x1234], &mut out).unwrap();
+        assert_eq!(out, [4, 0x12, 0x34, 0, 0]);
+        assert_eq!(layout.read(&[4, 0x12, 0x34, 0, 0, 0xff]), Ok((4, vec![0x1234, 0])));
+        assert_eq!(layout.read(&[4, 0x12, 0x34, 0]), Err(Error::Truncated { len: 4, needed: 5 }));
+        assert_eq!(layout.read(&[4, 0x12, 0x34, 0]).unwrap_err().to_string(), "Record is short: it needs 5 bytes but only 4 are left");
+        assert_eq!(layout.read(&[]), Err(Error::Truncated { len: 0, needed: 1 }));
+        assert_eq!(layout.read(&[0]), Ok((0, vec![])));
+
+        assert_eq!(layout.write(3, &[1, 2], &mut out), Err(Error::InputTooLong { len: 6, max: 3 }));
+        assert_eq!(layout.write(256, &[], &mut out), Err(Error::InputTooLong { len: 256, max: 255 }));
+        assert_eq!(out.len(), 5);
+
+        let wide = Counted { count_size: 8, ..layout };
+        assert_eq!(wide.max_chars(), usize::MAX);
+        assert_eq!(wide.read(&[0xff; 8]), Err(Error::Truncated { len: 8, needed: 8 + usize::MAX.div_ceil(3) * 2 }));
+    }
+
+    #[test]
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    fn counted_strings() {
+        use crate::Encoding;
+        for encoding in Encoding::ALL {
+            let layout = Counted::for_encoding(encoding, 2, Endian::Pdp);
+            for s in ["", "A", "ABCDEFG", "SY0.$"] {
+                let mut out = Vec::new();
+                layout.write_str(encoding, s, &mut out).unwrap();
+                assert_eq!(out.len(), layout.len(s.len()), "{} {:?}", encoding, s);
+                assert_eq!(layout.read_str(encoding, &out).as_deref(), Ok(s), "{}", encoding);
+            }
+            assert_eq!(layout.write_str(encoding, "a", &mut Vec::new()), Err(Error::IllegalChar { char: 'a', pos: 1 }));
+        }
+        let wide = Counted { count_size: 1, word_size: 4, chars_per_word: 3, endian: Endian::Little };
+        assert_eq!(wide.read_str(Encoding::Pdp11, &[1, 0, 0, 1, 0]), Err(Error::WordOutOfRange { word: 0x10000, index: 0 }));
+    }
+}
@@ -0,0 +1,179 @@
+// The 40 RADIX-50 code points as a type
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+use alloc::string::String;
+
+use crate::{Codec, Error, pdp11};
+
+/// One of the 40 RADIX-50 characters.
+///
+/// The numeric value of a character depends on the encoding (the PDP-10 puts the digits before the letters, the
+/// PDP-11 puts them after the punctuation), so a `Code` names the character rather than the number. Use
+/// [`value`](Code::value) to get the number for a particular [`Codec`]. The enum's own discriminants (and so
+/// `u8::from(code)`) are the PDP-11 values.
+///
+/// # Examples
+/// ```
+/// # #[cfg(all(feature = "pdp10", feature = "pdp11"))] {
+/// # use radix50::{Code, pdp10::Pdp10, pdp11::Pdp11};
+/// assert_eq!(Code::try_from('A'), Ok(Code::A));
+/// assert_eq!(Code::Digit7.to_char(), '7');
+/// assert_eq!(u8::from(Code::Dollar), 27);
+/// assert_eq!(Code::Dollar.value::<Pdp10>(), Some(38));
+/// assert_eq!(Code::from_value::<Pdp10>(38), Some(Code::Dollar));
+///
+/// assert_eq!(Code::encode_word::<Pdp11>(&[Code::S, Code::Y]), Ok(31400));
+/// # }
+/// ```
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,PartialOrd,Ord)]
+#[repr(u8)]
+pub enum Code {
+    /// `' '`, which is also the padding.
+    Space = 0,
+    /// `'A'`
+    A,
+    /// `'B'`
+    B,
+    /// `'C'`
+    C,
+    /// `'D'`
+    D,
+    /// `'E'`
+    E,
+    /// `'F'`
+    F,
+    /// `'G'`
+    G,
+    /// `'H'`
+    H,
+    /// `'I'`
+    I,
+    /// `'J'`
+    J,
+    /// `'K'`
+    K,
+    /// `'L'`
+    L,
+    /// `'M'`
+    M,
+    /// `'N'`
+    N,
+    /// `'O'`
+    O,
+    /// `'P'`
+    P,
+    /// `'Q'`
+    Q,
+    /// `'R'`
+    R,
+    /// `'S'`
+    S,
+    /// `'T'`
+    T,
+    /// `'U'`
+    U,
+    /// `'V'`
+    V,
+    /// `'W'`
+    W,
+    /// `'X'`
+    X,
+    /// `'Y'`
+    Y,
+    /// `'Z'`
+    Z,
+    /// `'$'`
+    Dollar,
+    /// `'.'`
+    Dot,
+    /// `'%'`
+    Percent,
+    /// `'0'`
+    Digit0,
+    /// `'1'`
+    Digit1,
+    /// `'2'`
+    Digit2,
+    /// `'3'`
+    Digit3,
+    /// `'4'`
+    Digit4,
+    /// `'5'`
+    Digit5,
+    /// `'6'`
+    Digit6,
+    /// `'7'`
+    Digit7,
+    /// `'8'`
+    Digit8,
+    /// `'9'`
+    Digit9,
+}
+
+impl Code {
+    /// All 40 codes, in PDP-11 order.
+    pub const ALL: [Code; 40] = {
+        use Code::*;
+        [Space, A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, Dollar, Dot, Percent,
+         Digit0, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9]
+    };
+
+    /// The character.
+    pub const fn to_char(self) -> char { pdp11::RADIX50_DECODE[self as usize] }
+
+    /// The code's numeric value in a particular encoding. This is only `None` for [`Codec`]s whose character set
+    /// isn't a permutation of the standard one.
+    pub fn value<C: Codec>(self) -> Option<u8> { C::char_to_code(self.to_char()) }
+
+    /// Look up a numeric value in a particular encoding (`None` if it's out of range).
+    pub fn from_value<C: Codec>(value: u8) -> Option<Code> { C::code_to_char(value).and_then(|c| Code::try_from(c).ok()) }
+
+    /// Encode up to [`CHARS`](Codec::CHARS) codes into a word. Missing codes are assumed to be [`Space`](Code::Space).
+    /// It returns an [`Error::InputTooLong`] if there are more codes than fit in a word.
+    pub fn encode_word<C: Codec>(codes: &[Code]) -> Result<C::Word, Error> {
+        if codes.len() > C::CHARS { return Err(Error::InputTooLong { len: codes.len(), max: C::CHARS }) }
+        C::encode_word(&codes.iter().map(|code| code.to_char()).collect::<String>())
+    }
+}
+
+impl TryFrom<char> for Code {
+    type Error = Error;
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        pdp11::char_to_code(c).map(|code| Code::ALL[code as usize]).ok_or(Error::IllegalChar { char: c, pos: 1 })
+    }
+}
+
+impl From<Code> for u8 { fn from(code: Code) -> Self { code as u8 } }
+impl From<Code> for char { fn from(code: Code) -> Self { code.to_char() } }
+
+#[cfg(all(test, feature = "pdp10"))]
+mod tests {
+    use super::*;
+    use crate::{pdp10::{self, Pdp10}, pdp11::Pdp11};
+
+    #[test]
+    fn values() {
+        for (i, code) in Code::ALL.iter().enumerate() {
+            assert_eq!(u8::from(*code) as usize, i);
+            assert_eq!(code.value::<Pdp11>(), Some(i as u8));
+            assert_eq!(Code::from_value::<Pdp11>(i as u8), Some(*code));
+            assert_eq!(pdp10::code_to_char(code.value::<Pdp10>().unwrap()), Some(code.to_char()));
+            assert_eq!(Code::try_from(code.to_char()), Ok(*code));
+        }
+        assert_eq!(Code::Digit0.value::<Pdp10>(), Some(1));
+        assert_eq!(Code::A.value::<Pdp10>(), Some(11));
+        assert_eq!(Code::from_value::<Pdp11>(40), None);
+        assert_eq!(Code::try_from('a'), Err(Error::IllegalChar { char: 'a', pos: 1 }));
+    }
+
+    #[test]
+    fn words() {
+        use Code::*;
+        assert_eq!(Code::encode_word::<Pdp11>(&[A, B, C]), Ok(1683));
+        assert_eq!(Code::encode_word::<Pdp11>(&[]), Ok(0));
+        assert_eq!(Code::encode_word::<Pdp11>(&[A, B, C, D]), Err(Error::InputTooLong { len: 4, max: 3 }));
+        assert_eq!(Code::encode_word::<Pdp10>(&[F, O, O, B, A, R]), pdp10::encode_word("FOOBAR"));
+    }
+}
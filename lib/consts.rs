@@ -0,0 +1,81 @@
+// Pre-encoded RADIX-50 words for well known names
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Pre-encoded [PDP-11 RADIX-50][crate::pdp11::RADIX50_DECODE] words for common device names and file
+//! extensions.
+//!
+//! These are computed at compile time so systems code can compare against well-known words without encoding
+//! anything at runtime.
+//!
+//! # Examples
+//! ```
+//! # use radix50::{consts, pdp11};
+//! let words = [0o075250, 0o075273]; // "SY " "SYS"
+//! assert_eq!(words, [consts::SY, consts::SYS]);
+//! assert_eq!(pdp11::decode_word(consts::SAV), "SAV");
+//! ```
+
+use crate::pdp11::encode_word_const as word;
+
+/// An all-space word. This is `0` in both the PDP-10 and PDP-11 encodings.
+pub const BLANK: u16 = 0;
+
+/// Device name `SY` (the system device)
+pub const SY:  u16 = word("SY");
+/// Device name `DK` (the default storage device)
+pub const DK:  u16 = word("DK");
+/// Device name `TT` (the console terminal)
+pub const TT:  u16 = word("TT");
+/// Device name `LP` (the line printer)
+pub const LP:  u16 = word("LP");
+/// Device name `MT` (magtape)
+pub const MT:  u16 = word("MT");
+/// Device name `DT` (DECtape)
+pub const DT:  u16 = word("DT");
+
+/// File extension `SAV` (RT-11 executable image)
+pub const SAV: u16 = word("SAV");
+/// File extension `SYS` (system file/device handler)
+pub const SYS: u16 = word("SYS");
+/// File extension `OBJ` (object module)
+pub const OBJ: u16 = word("OBJ");
+/// File extension `TSK` (RSX-11 task image)
+pub const TSK: u16 = word("TSK");
+/// File extension `MAC` (MACRO-11 source)
+pub const MAC: u16 = word("MAC");
+/// File extension `LST` (assembler listing)
+pub const LST: u16 = word("LST");
+/// File extension `MAP` (linker map)
+pub const MAP: u16 = word("MAP");
+/// File extension `STB` (symbol definition file)
+pub const STB: u16 = word("STB");
+/// File extension `REL` (relocatable image)
+pub const REL: u16 = word("REL");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdp11;
+
+    #[test]
+    fn consts_match_runtime_encoding() {
+        assert_eq!(pdp11::encode_word("").unwrap(), BLANK);
+        #[cfg(feature = "pdp10")]
+        assert_eq!(crate::pdp10::encode_word("").unwrap(), BLANK as u32);
+        for (w, s) in [(SY, "SY"), (DK, "DK"), (TT, "TT"), (LP, "LP"), (MT, "MT"), (DT, "DT"),
+                       (SAV, "SAV"), (SYS, "SYS"), (OBJ, "OBJ"), (TSK, "TSK"), (MAC, "MAC"),
+                       (LST, "LST"), (MAP, "MAP"), (STB, "STB"), (REL, "REL")] {
+            assert_eq!(pdp11::encode_word(s).unwrap(), w, "{}", s);
+        }
+    }
+
+    #[test]
+    fn known_values() {
+        // Section 2.6 of "Getting DOS On The Air" https://archive.org/details/bitsavers_decpdp11dotingDOSontheAirAug71_3085688/page/n37/mode/2up
+        assert_eq!(LP, 0o46600);
+        assert_eq!(MT, 0o52140);
+        assert_eq!(DT, 0o16040);
+    }
+}
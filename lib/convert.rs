@@ -0,0 +1,664 @@
+// End to end RADIX-50 conversion of byte streams
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! End to end conversion between text and RADIX-50 word streams.
+//!
+//! [`file()`] does the whole job (reading, encoding or decoding, packing words into bytes, and writing) in one
+//! call so that front ends (like the cli) don't have to re-assemble it from the lower level functions.
+//!
+//! [`words_from_reader()`] and [`words32_from_reader()`] are the lower level piece that turns a byte stream into a
+//! stream of words. PDP-10 media usually packs 36 bit words into bytes, which [`Format::Packed`] reads and writes
+//! (see the [`bytes`](crate::bytes) module for the details).
+//!
+//! Paper tape images often have a parity bit in the top bit of each byte. [`Options::parity`] (or
+//! [`strip_parity()`] for other readers) checks and clears it before the bytes are converted.
+//!
+//! With the `mmap` feature, `decode_file()` decodes a raw word file straight from a memory map instead of reading
+//! the whole thing in first.
+
+use std::io::{self, Read, Write};
+use std::mem::size_of;
+#[cfg(feature = "mmap")]
+use std::path::Path;
+
+use crate::{Codec, Encoding, Endian, Error, bytes, pdp10::Pdp10, pdp11::Pdp11};
+pub use crate::bytes::Packing;
+
+/// Which way to convert.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum Direction {
+    /// Text in, words out
+    #[default]
+    Encode,
+    /// Words in, text out
+    Decode,
+}
+
+/// How the words are represented in the byte stream.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Format {
+    /// Raw binary words (each one the size of the codec's word) in the given byte order.
+    Raw(Endian),
+    /// Raw 36 bit PDP-10 words, packed into bytes (see [`Packing`]). A PDP-10 RADIX-50 word only uses the bottom
+    /// 32 bits: the top 4 are flags (in a symbol table), so they're ignored when decoding and 0 when encoding.
+    Packed(Packing),
+    /// Whitespace separated binary numbers (no `0b` prefix)
+    Bin,
+    /// Whitespace separated octal numbers (no `0o` prefix)
+    Oct,
+    /// Whitespace separated decimal numbers
+    Dec,
+    /// Whitespace separated hexadecimal numbers (no `0x` prefix)
+    Hex,
+}
+
+impl Default for Format {
+    fn default() -> Self { Format::Raw(Endian::Big) }
+}
+
+/// What to do with input that can't be converted.
+///
+/// When encoding, this applies to each character that isn't in the RADIX-50 character set. When decoding, it
+/// applies to each word that can't be read (a number that doesn't parse, or trailing bytes that don't make up a
+/// whole word).
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum OnError {
+    /// Stop and return an error.
+    #[default]
+    Fail,
+    /// Leave the bad character or word out.
+    Skip,
+    /// Encode this character instead of each bad character, or decode a bad word as a word's worth of this
+    /// character.
+    Replace(char),
+}
+
+/// The parity bit (bit 7) of each byte of an 8-level paper tape image.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum Parity {
+    /// There's no parity bit: use the bytes as they are.
+    #[default]
+    None,
+    /// Clear bit 7 without checking it.
+    Strip,
+    /// Each byte has an even number of 1 bits. Bit 7 is cleared after it's checked.
+    Even,
+    /// Each byte has an odd number of 1 bits. Bit 7 is cleared after it's checked.
+    Odd,
+    /// Bit 7 of each byte is set (the way most ASCII terminals punched tape). It's cleared after it's checked.
+    Mark,
+}
+
+impl Parity {
+    /// Check the parity of `byte` and clear its parity bit. Returns `None` if the parity is wrong.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::convert::Parity;
+    /// assert_eq!(Parity::Odd.check(0xc1), Some(b'A'));
+    /// assert_eq!(Parity::Even.check(0xc1), None);
+    /// assert_eq!(Parity::Even.check(0x41), Some(b'A'));
+    /// assert_eq!(Parity::Mark.check(b'A'), None);
+    /// assert_eq!(Parity::None.check(0xc1), Some(0xc1));
+    /// ```
+    pub fn check(self, byte: u8) -> Option<u8> {
+        let ok = match self {
+            Parity::None  => return Some(byte),
+            Parity::Strip => true,
+            Parity::Even  => byte.count_ones().is_multiple_of(2),
+            Parity::Odd   => !byte.count_ones().is_multiple_of(2),
+            Parity::Mark  => byte & 0x80 != 0,
+        };
+        ok.then_some(byte & 0x7f)
+    }
+}
+
+/// Options for [`file()`].
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub struct Options {
+    pub direction: Direction,
+    /// The format of the output when encoding, or of the input when decoding.
+    pub format: Format,
+    pub on_error: OnError,
+    /// The parity of the input bytes (whichever way the conversion goes).
+    pub parity: Parity,
+}
+
+/// Convert `input` to `output` according to `options`, using the codec `C`.
+///
+/// When encoding, `input` is read as UTF-8 text and `output` gets the encoded words in
+/// [`options.format`][Options::format] (text formats are space separated and end with a newline). When decoding,
+/// `input` is read in [`options.format`][Options::format] and `output` gets the decoded text.
+///
+/// Returns the number of words written (when encoding) or read (when decoding).
+///
+/// Illegal characters and malformed words are reported as [`io::ErrorKind::InvalidData`] errors (wrapping the
+/// [`Error`](crate::Error) when there is one) unless [`options.on_error`][Options::on_error] says otherwise. A
+/// byte with the wrong [parity](Options::parity) is always an error.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Endian, convert::{self, Direction, Format, OnError, Options}, pdp10::Pdp10, pdp11::Pdp11};
+/// let mut out = Vec::new();
+/// convert::file::<Pdp11>("THIS IS A TEST".as_bytes(), &mut out, Options::default()).unwrap();
+/// assert_eq!(out, [0x7e, 0x49, 0x76, 0xc9, 0x76, 0xc1, 0x03, 0x25, 0x79, 0xe0]);
+///
+/// let mut out = Vec::new();
+/// let options = Options { format: Format::Oct, ..Options::default() };
+/// convert::file::<Pdp10>("THIS IS A TEST".as_bytes(), &mut out, options).unwrap();
+/// assert_eq!(out, b"27173261523 26102641277 26545060000\n");
+///
+/// let mut out = Vec::new();
+/// let options = Options { direction: Direction::Decode, format: Format::Raw(Endian::Little), on_error: OnError::Replace('?'), ..Options::default() };
+/// convert::file::<Pdp11>(&[0x49, 0x7e, 0xc9, 0x76, 0x01][..], &mut out, options).unwrap();
+/// assert_eq!(out, b"THIS I???");
+/// ```
+pub fn file<C: Codec>(input: impl Read, mut output: impl Write, options: Options) -> io::Result<usize> {
+    let mut input = strip_parity(input, options.parity);
+    match options.direction {
+        Direction::Encode => {
+            let mut text = String::new();
+            input.read_to_string(&mut text)?;
+            let words = encode_with_policy::<C>(&text, options.on_error)?;
+            write_words::<C>(&mut output, &words, options.format)?;
+            Ok(words.len())
+        },
+        Direction::Decode => match options.format {
+            // Raw words are streamed, so big images don't have to fit in memory
+            Format::Raw(endian) => decode_words::<C>(raw_words(input, endian), output, options.on_error),
+            Format::Packed(packing) => decode_words::<C>(packed_words(input, packing), output, options.on_error),
+            format => {
+                let mut bytes = Vec::new();
+                input.read_to_end(&mut bytes)?;
+                decode_words::<C>(read_words::<C>(&bytes, format)?, output, options.on_error)
+            },
+        },
+    }
+}
+
+fn decode_words<C: Codec>(words: impl IntoIterator<Item = io::Result<C::Word>>, mut output: impl Write, on_error: OnError) -> io::Result<usize> {
+    let mut count = 0;
+    for word in words {
+        match (word, on_error) {
+            (Ok(w), _)                => { output.write_all(C::decode_word(w).as_bytes())?; count += 1 },
+            // Only bad data is subject to on_error. Failing to read is always an error.
+            (Err(e), _) if e.kind() != io::ErrorKind::InvalidData || is_parity_error(&e) => Err(e)?,
+            (Err(e), OnError::Fail)   => Err(e)?,
+            (Err(_), OnError::Skip)   => {},
+            (Err(_), OnError::Replace(c)) => output.write_all(c.to_string().repeat(C::CHARS).as_bytes())?,
+        }
+    }
+    Ok(count)
+}
+
+/// Check and clear the parity bit of each byte read from `reader` (see [`Parity::check`]).
+///
+/// A byte with the wrong parity fails the read with an [`io::ErrorKind::InvalidData`] error wrapping an
+/// [`Error::Parity`], after the bytes before it have been read.
+///
+/// # Examples
+/// ```
+/// # use std::io::Read;
+/// # use radix50::{Error, convert::{Parity, strip_parity}};
+/// let mut text = String::new();
+/// strip_parity(&[0xd4, 0xc5, 0xd3, 0xd4][..], Parity::Mark).read_to_string(&mut text).unwrap();
+/// assert_eq!(text, "TEST");
+///
+/// let mut bytes = Vec::new();
+/// let error = strip_parity(&[0xd4, 0x45][..], Parity::Mark).read_to_end(&mut bytes).unwrap_err();
+/// assert_eq!(error.into_inner().unwrap().downcast_ref::<Error>(), Some(&Error::Parity { offset: 1 }));
+/// assert_eq!(bytes, b"T");
+/// ```
+pub fn strip_parity<R: Read>(reader: R, parity: Parity) -> impl Read {
+    ParityReader { reader, parity, offset: 0, bad: false }
+}
+
+struct ParityReader<R> {
+    reader: R,
+    parity: Parity,
+    // How many bytes have been handed out
+    offset: usize,
+    // The byte at `offset` had bad parity
+    bad: bool,
+}
+
+impl<R: Read> Read for ParityReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.bad { return Err(invalid_data(Error::Parity { offset: self.offset })) }
+        let len = self.reader.read(buf)?;
+        for (n, byte) in buf[..len].iter_mut().enumerate() {
+            let Some(b) = self.parity.check(*byte) else {
+                // Hand out the good bytes before it first. The error comes with the next read.
+                self.bad = true;
+                self.offset += n;
+                return if n > 0 { Ok(n) } else { Err(invalid_data(Error::Parity { offset: self.offset })) };
+            };
+            *byte = b;
+        }
+        self.offset += len;
+        Ok(len)
+    }
+}
+
+fn is_parity_error(e: &io::Error) -> bool {
+    matches!(e.get_ref().and_then(|e| e.downcast_ref::<Error>()), Some(Error::Parity { .. }))
+}
+
+/// Read raw 16 bit words (in `endian` byte order) from `reader`.
+///
+/// The reader is buffered internally, so it's fine to pass an unbuffered one (like a `File`). If the stream ends
+/// partway through a word, the last item is an [`io::ErrorKind::InvalidData`] error wrapping an
+/// [`Error::TrailingBytes`]. The iterator ends after the first error.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Endian, Error, convert::words_from_reader};
+/// let bytes: &[u8] = &[0x49, 0x7e, 0xc9, 0x76, 0x01];
+/// let mut words = words_from_reader(bytes, Endian::Little);
+/// assert_eq!(words.next().unwrap().unwrap(), 0x7e49);
+/// assert_eq!(words.next().unwrap().unwrap(), 0x76c9);
+/// let trailing = words.next().unwrap().unwrap_err();
+/// assert_eq!(trailing.into_inner().unwrap().downcast_ref::<Error>(), Some(&Error::TrailingBytes { len: 1 }));
+/// assert!(words.next().is_none());
+/// ```
+pub fn words_from_reader<R: Read>(reader: R, endian: Endian) -> impl Iterator<Item = io::Result<u16>> {
+    raw_words(reader, endian)
+}
+
+/// Read raw 32 bit words (in `endian` byte order) from `reader`. This is the 32 bit version of
+/// [`words_from_reader()`].
+///
+/// # Examples
+/// ```
+/// # use radix50::{Endian, convert::words32_from_reader};
+/// let bytes: &[u8] = &[0xb9, 0xed, 0x63, 0x53, 0xb1, 0x0b, 0x42, 0xbf];
+/// let words: Vec<u32> = words32_from_reader(bytes, Endian::Big).collect::<Result<_, _>>().unwrap();
+/// assert_eq!(words, [3119342419, 2970305215]);
+/// ```
+pub fn words32_from_reader<R: Read>(reader: R, endian: Endian) -> impl Iterator<Item = io::Result<u32>> {
+    raw_words(reader, endian)
+}
+
+fn raw_words<W: TryFrom<u64>>(reader: impl Read, endian: Endian) -> impl Iterator<Item = io::Result<W>> {
+    let size = size_of::<W>();
+    let mut reader = io::BufReader::new(reader);
+    let mut done = false;
+    std::iter::from_fn(move || {
+        if done { return None }
+        let mut buf = [0; 8];
+        let len = match read_full(&mut reader, &mut buf[..size]) {
+            Ok(len) => len,
+            Err(e)  => { done = true; return Some(Err(e)) },
+        };
+        if len < size { done = true }
+        match len {
+            0                => None,
+            len if len < size => Some(Err(invalid_data(Error::TrailingBytes { len }))),
+            _                => Some(Ok(W::try_from(bytes::read_word(&buf[..size], endian)).ok().expect("a word's worth of bytes fits in a word"))),
+        }
+    })
+}
+
+// Fill `buf`, unless the stream ends first. Returns how many bytes were read.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]) {
+            Ok(0)  => break,
+            Ok(n)  => len += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(len)
+}
+
+fn packed_words<W: TryFrom<u64>>(reader: impl Read, packing: Packing) -> impl Iterator<Item = io::Result<W>> {
+    let (_, size) = packing.group();
+    let mut reader = io::BufReader::new(reader);
+    let mut words = Vec::new();
+    let mut trailing = 0;
+    let mut index = 0;
+    let mut count = 0;
+    let mut done = false;
+    std::iter::from_fn(move || {
+        if index == words.len() && trailing == 0 {
+            if done { return None }
+            words.clear();
+            index = 0;
+            let mut buf = [0; 9];
+            let len = match read_full(&mut reader, &mut buf[..size]) {
+                Ok(len) => len,
+                Err(e)  => { done = true; return Some(Err(e)) },
+            };
+            if len < size { done = true }
+            trailing = packing.unpack(&buf[..len], &mut words);
+        }
+        match words.get(index) {
+            Some(&word) => {
+                // The flags aren't part of the RADIX-50 word
+                let word = word & 0xffff_ffff;
+                index += 1;
+                count += 1;
+                Some(W::try_from(word).map_err(|_| invalid_data(Error::WordOutOfRange { word, index: count - 1 })))
+            },
+            None if trailing > 0 => { done = true; let len = core::mem::take(&mut trailing); Some(Err(invalid_data(Error::TrailingBytes { len }))) },
+            None => None,
+        }
+    })
+}
+
+/// Like [`file()`], but with the codec picked at runtime.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Encoding, convert::{self, Format, Options}};
+/// let mut out = Vec::new();
+/// let options = Options { format: Format::Hex, ..Options::default() };
+/// convert::file_with_encoding(Encoding::Pdp11, "THIS IS A TEST".as_bytes(), &mut out, options).unwrap();
+/// assert_eq!(out, b"7e49 76c9 76c1 325 79e0\n");
+/// ```
+pub fn file_with_encoding(encoding: Encoding, input: impl Read, output: impl Write, options: Options) -> io::Result<usize> {
+    match encoding {
+        Encoding::Pdp10 => file::<Pdp10>(input, output, options),
+        Encoding::Pdp11 => file::<Pdp11>(input, output, options),
+    }
+}
+
+/// Decode the raw words in the file at `path` to `output`, without reading the whole file into memory.
+///
+/// The file is memory mapped and decoded a block at a time, so even huge disk images only cost a small buffer.
+/// Trailing bytes that don't make up a whole word are handled according to `on_error`, the same way
+/// [`file()`] handles them.
+///
+/// Returns the number of words decoded.
+///
+/// Needs the `mmap` feature.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Encoding, Endian, convert::{self, OnError}};
+/// let path = std::env::temp_dir().join(format!("radix50-decode-file-doctest-{}", std::process::id()));
+/// std::fs::write(&path, [0x7e, 0x49, 0x76, 0xc9, 0x76, 0xc1, 0x03, 0x25, 0x79, 0xe0]).unwrap();
+/// let mut out = Vec::new();
+/// assert_eq!(convert::decode_file(&path, Endian::Big, Encoding::Pdp11, OnError::Fail, &mut out).unwrap(), 5);
+/// assert_eq!(out, b"THIS IS A TEST ");
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[cfg(feature = "mmap")]
+pub fn decode_file(path: impl AsRef<Path>, endian: Endian, encoding: Encoding, on_error: OnError, output: impl Write) -> io::Result<usize> {
+    let file = std::fs::File::open(path)?;
+    // Safety: Mapping is only unsafe because another process could change the file while we're reading it. We
+    // only ever read the bytes, and a changed file decodes to garbage text, just like a corrupt one would.
+    #[allow(unsafe_code)]
+    let map = unsafe { memmap2::Mmap::map(&file)? };
+    match encoding {
+        Encoding::Pdp10 => decode_raw::<Pdp10>(&map, endian, on_error, output),
+        Encoding::Pdp11 => decode_raw::<Pdp11>(&map, endian, on_error, output),
+    }
+}
+
+// Decode a block of words at a time so the output doesn't get one tiny write per word.
+#[cfg(feature = "mmap")]
+fn decode_raw<C: Codec>(bytes: &[u8], endian: Endian, on_error: OnError, output: impl Write) -> io::Result<usize> {
+    const BLOCK_WORDS: usize = 4096;
+    let size = size_of::<C::Word>();
+    let mut output = io::BufWriter::new(output);
+    let mut text = String::with_capacity(BLOCK_WORDS * C::CHARS);
+    let (count, trailing) = (bytes.len() / size, bytes.len() % size);
+    for block in bytes[..count * size].chunks(BLOCK_WORDS * size) {
+        text.clear();
+        // A word's worth of bytes always fits in a word.
+        let block = bytes::words(block, size, endian).filter_map(|w| C::Word::try_from(w).ok());
+        C::decode_fmt(block, &mut text).expect("writing to a String can't fail");
+        output.write_all(text.as_bytes())?;
+    }
+    if trailing > 0 {
+        match on_error {
+            OnError::Fail       => Err(invalid_data(Error::TrailingBytes { len: trailing }))?,
+            OnError::Skip       => {},
+            OnError::Replace(c) => output.write_all(c.to_string().repeat(C::CHARS).as_bytes())?,
+        }
+    }
+    output.flush()?;
+    Ok(count)
+}
+
+fn invalid_data(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+fn encode_with_policy<C: Codec>(text: &str, on_error: OnError) -> io::Result<Vec<C::Word>> {
+    let legal = |c: char| c.is_ascii() && C::ENCODE[c as usize].is_some();
+    let text: String = match on_error {
+        OnError::Fail       => return C::encode(text).map_err(invalid_data),
+        OnError::Skip       => text.chars().filter(|c| legal(*c)).collect(),
+        OnError::Replace(r) => text.chars().map(|c| if legal(c) { c } else { r }).collect(),
+    };
+    C::encode(&text).map_err(invalid_data)
+}
+
+fn write_words<C: Codec>(output: &mut impl Write, words: &[C::Word], format: Format) -> io::Result<()> {
+    let size = size_of::<C::Word>();
+    match format {
+        Format::Raw(endian) => {
+            let mut buffer: Vec<u8> = Vec::with_capacity(std::mem::size_of_val(words));
+            for w in words.iter() { bytes::write_word((*w).into(), size, endian, &mut buffer) }
+            output.write_all(&buffer)
+        },
+        Format::Packed(packing) => {
+            let mut buffer = Vec::new();
+            packing.pack(&words.iter().map(|w| (*w).into()).collect::<Vec<u64>>(), &mut buffer);
+            output.write_all(&buffer)
+        },
+        Format::Bin | Format::Oct | Format::Dec | Format::Hex => {
+            let text = words.iter().map(|w| { let w: u64 = (*w).into(); match format {
+                                                  Format::Bin => format!("{:b}", w),
+                                                  Format::Oct => format!("{:o}", w),
+                                                  Format::Dec => format!("{}",   w),
+                                                  Format::Hex => format!("{:x}", w),
+                                                  Format::Raw(_) | Format::Packed(_) => unreachable!(),
+                                              }})
+                                .collect::<Vec<String>>().join(" ");
+            writeln!(output, "{}", text)
+        },
+    }
+}
+
+// The text formats
+fn read_words<C: Codec>(bytes: &[u8], format: Format) -> io::Result<Vec<io::Result<C::Word>>> {
+    let to_word = |w: u64| C::Word::try_from(w).map_err(|_| invalid_data(format!("{} doesn't fit in a {}", w, std::any::type_name::<C::Word>())));
+    let radix = match format { Format::Bin => 2, Format::Oct => 8, Format::Dec => 10, Format::Hex => 16, Format::Raw(_) | Format::Packed(_) => unreachable!("raw words are streamed") };
+    let text = std::str::from_utf8(bytes).map_err(invalid_data)?;
+    Ok(text.split_whitespace()
+           .map(|s| u64::from_str_radix(s, radix).map_err(|_| invalid_data(format!("Couldn't parse as integer: {}", s))).and_then(to_word))
+           .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert<C: Codec>(input: &[u8], options: Options) -> io::Result<(usize, Vec<u8>)> {
+        let mut out = Vec::new();
+        let count = file::<C>(input, &mut out, options)?;
+        Ok((count, out))
+    }
+
+    const PDP11_RAW: [u8; 10] = [0x7e, 0x49, 0x76, 0xc9, 0x76, 0xc1, 0x03, 0x25, 0x79, 0xe0];
+    const PDP10_RAW: [u8; 12] = [0xb9, 0xed, 0x63, 0x53, 0xb1, 0x0b, 0x42, 0xbf, 0xb5, 0x94, 0x60, 0x00];
+
+    #[test]
+    fn encode_formats() {
+        let enc = |format| Options { format, ..Options::default() };
+        assert_eq!(convert::<Pdp11>(b"THIS IS A TEST", enc(Format::Raw(Endian::Big))).unwrap(), (5, PDP11_RAW.to_vec()));
+        assert_eq!(convert::<Pdp10>(b"THIS IS A TEST", enc(Format::Raw(Endian::Big))).unwrap(), (3, PDP10_RAW.to_vec()));
+        assert_eq!(convert::<Pdp11>(b"THIS IS A TEST", enc(Format::Raw(Endian::Little))).unwrap().1,
+                   [0x49, 0x7e, 0xc9, 0x76, 0xc1, 0x76, 0x25, 0x03, 0xe0, 0x79]);
+        assert_eq!(convert::<Pdp10>(b"THIS IS A TEST", enc(Format::Raw(Endian::Little))).unwrap().1,
+                   [0x53, 0x63, 0xed, 0xb9, 0xbf, 0x42, 0x0b, 0xb1, 0x00, 0x60, 0x94, 0xb5]);
+        assert_eq!(convert::<Pdp10>(b"THIS IS A TEST", enc(Format::Raw(Endian::Pdp))).unwrap().1,
+                   [0xed, 0xb9, 0x53, 0x63, 0x0b, 0xb1, 0xbf, 0x42, 0x94, 0xb5, 0x00, 0x60]);
+        assert_eq!(convert::<Pdp11>(b"THIS IS A TEST", enc(Format::Raw(Endian::Pdp))).unwrap(),
+                   convert::<Pdp11>(b"THIS IS A TEST", enc(Format::Raw(Endian::Little))).unwrap());
+        assert_eq!(convert::<Pdp11>(b"THIS IS A TEST", enc(Format::Dec)).unwrap().1, b"32329 30409 30401 805 31200\n");
+        assert_eq!(convert::<Pdp11>(b"THIS IS A TEST", enc(Format::Hex)).unwrap().1, b"7e49 76c9 76c1 325 79e0\n");
+        assert_eq!(convert::<Pdp11>(b"THIS IS A TEST", enc(Format::Oct)).unwrap().1, b"77111 73311 73301 1445 74740\n");
+        assert_eq!(convert::<Pdp11>(b"THIS IS A TEST", enc(Format::Bin)).unwrap().1,
+                   b"111111001001001 111011011001001 111011011000001 1100100101 111100111100000\n");
+        assert_eq!(convert::<Pdp11>(b"", enc(Format::Dec)).unwrap(), (0, b"\n".to_vec()));
+    }
+
+    #[test]
+    fn decode_formats() {
+        let dec = |format| Options { direction: Direction::Decode, format, ..Options::default() };
+        assert_eq!(convert::<Pdp11>(&PDP11_RAW, dec(Format::Raw(Endian::Big))).unwrap(), (5, b"THIS IS A TEST ".to_vec()));
+        assert_eq!(convert::<Pdp10>(&PDP10_RAW, dec(Format::Raw(Endian::Big))).unwrap(), (3, b"THIS IS A TEST    ".to_vec()));
+        assert_eq!(convert::<Pdp10>(&[0xed, 0xb9, 0x53, 0x63, 0x0b, 0xb1, 0xbf, 0x42], dec(Format::Raw(Endian::Pdp))).unwrap(),
+                   (2, b"THIS IS A TE".to_vec()));
+        assert_eq!(convert::<Pdp11>(b"32329 30409\n30401  805 31200\n", dec(Format::Dec)).unwrap().1, b"THIS IS A TEST ");
+        assert_eq!(convert::<Pdp10>(b"b9ed6353 b10b42bf b5946000", dec(Format::Hex)).unwrap().1, b"THIS IS A TEST    ");
+        assert_eq!(convert::<Pdp11>(b"77111 73311 73301 1445 74740", dec(Format::Oct)).unwrap().1, b"THIS IS A TEST ");
+    }
+
+    #[test]
+    fn error_policies() {
+        let enc = |on_error| Options { format: Format::Dec, on_error, ..Options::default() };
+        assert_eq!(convert::<Pdp11>(b"THIS_IS\n", enc(OnError::Fail)).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(convert::<Pdp11>(b"THIS_IS\n", enc(OnError::Skip)).unwrap().1, convert::<Pdp11>(b"THISIS", enc(OnError::Fail)).unwrap().1);
+        assert_eq!(convert::<Pdp11>(b"THIS_IS\n", enc(OnError::Replace(' '))).unwrap().1, convert::<Pdp11>(b"THIS IS ", enc(OnError::Fail)).unwrap().1);
+        assert!(convert::<Pdp11>(b"THIS_IS\n", enc(OnError::Replace('_'))).is_err());
+
+        let dec = |format, on_error| Options { direction: Direction::Decode, format, on_error, ..Options::default() };
+        assert_eq!(convert::<Pdp11>(&PDP11_RAW[..9], dec(Format::Raw(Endian::Big), OnError::Fail)).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(convert::<Pdp11>(&PDP11_RAW[..9], dec(Format::Raw(Endian::Big), OnError::Fail)).unwrap_err().into_inner().unwrap().downcast_ref::<Error>(),
+                   Some(&Error::TrailingBytes { len: 1 }));
+        assert_eq!(convert::<Pdp11>(&PDP11_RAW[..9], dec(Format::Raw(Endian::Big), OnError::Skip)).unwrap(), (4, b"THIS IS A TE".to_vec()));
+        assert_eq!(convert::<Pdp10>(&PDP10_RAW[..9], dec(Format::Raw(Endian::Big), OnError::Replace('*'))).unwrap(), (2, b"THIS IS A TE******".to_vec()));
+        assert_eq!(convert::<Pdp11>(b"32329 x 70000 30409", dec(Format::Dec, OnError::Skip)).unwrap(), (2, b"THIS I".to_vec()));
+        assert_eq!(convert::<Pdp11>(b"32329 x 70000 30409", dec(Format::Dec, OnError::Replace('?'))).unwrap(), (2, b"THI??????S I".to_vec()));
+        assert_eq!(convert::<Pdp11>(b"32329 x", dec(Format::Dec, OnError::Fail)).unwrap_err().to_string(), "Couldn't parse as integer: x");
+    }
+
+    #[test]
+    fn packed() {
+        let words: Vec<u64> = PDP10_RAW.chunks(4).map(|b| b.iter().fold(0, |w, b| w << 8 | *b as u64)).collect();
+        let mut core_dump = Vec::new();
+        Packing::CoreDump.pack(&words, &mut core_dump);
+        assert_eq!(core_dump.len(), 15);
+        let mut high_density = Vec::new();
+        Packing::HighDensity.pack(&words, &mut high_density);
+        let enc = Options { format: Format::Packed(Packing::CoreDump), ..Options::default() };
+        assert_eq!(convert::<Pdp10>(b"THIS IS A TEST", enc).unwrap(), (3, core_dump.clone()));
+
+        let dec = |on_error| Options { direction: Direction::Decode, format: Format::Packed(Packing::CoreDump), on_error, ..Options::default() };
+        assert_eq!(convert::<Pdp10>(&core_dump, dec(OnError::Fail)).unwrap(), (3, b"THIS IS A TEST    ".to_vec()));
+        // Symbol flags in the top 4 bits (and junk in the unused half of the 5th byte) are ignored
+        let mut flagged = core_dump.clone();
+        flagged[0] |= 0xf0;
+        flagged[4] |= 0xf0;
+        assert_eq!(convert::<Pdp10>(&flagged, dec(OnError::Fail)).unwrap(), (3, b"THIS IS A TEST    ".to_vec()));
+        assert_eq!(convert::<Pdp10>(&core_dump[..13], dec(OnError::Replace('?'))).unwrap(), (2, b"THIS IS A TE??????".to_vec()));
+        let error = convert::<Pdp10>(&core_dump[..13], dec(OnError::Fail)).unwrap_err();
+        assert_eq!(error.into_inner().unwrap().downcast_ref::<Error>(), Some(&Error::TrailingBytes { len: 3 }));
+        let hd = Options { direction: Direction::Decode, format: Format::Packed(Packing::HighDensity), ..Options::default() };
+        assert_eq!(convert::<Pdp10>(&high_density, hd).unwrap(), (3, b"THIS IS A TEST    ".to_vec()));
+        let error = convert::<Pdp10>(&[&high_density[..], &[1, 2]].concat(), hd).unwrap_err();
+        assert_eq!(error.into_inner().unwrap().downcast_ref::<Error>(), Some(&Error::TrailingBytes { len: 2 }));
+        let enc = Options { format: Format::Packed(Packing::HighDensity), ..Options::default() };
+        assert_eq!(convert::<Pdp10>(b"THIS IS A TEST", enc).unwrap(), (3, high_density));
+
+        // A PDP-11 word has to fit in 16 bits
+        let error = convert::<Pdp11>(&core_dump, dec(OnError::Fail)).unwrap_err();
+        assert_eq!(error.into_inner().unwrap().downcast_ref::<Error>(), Some(&Error::WordOutOfRange { word: 3119342419, index: 0 }));
+        assert_eq!(convert::<Pdp11>(&[0, 0, 0x07, 0xe4, 0x09], dec(OnError::Fail)).unwrap(), (1, b"THI".to_vec()));
+    }
+
+    #[test]
+    fn parity() {
+        let mark: Vec<u8> = b"THIS IS A TEST".iter().map(|b| b | 0x80).collect();
+        let even: Vec<u8> = b"THIS IS A TEST".iter().map(|b| if b.count_ones() % 2 == 1 { b | 0x80 } else { *b }).collect();
+        let enc = |parity| Options { format: Format::Raw(Endian::Big), parity, ..Options::default() };
+        assert_eq!(convert::<Pdp11>(&mark, enc(Parity::Mark)).unwrap(), (5, PDP11_RAW.to_vec()));
+        assert_eq!(convert::<Pdp11>(&mark, enc(Parity::Strip)).unwrap(), (5, PDP11_RAW.to_vec()));
+        assert_eq!(convert::<Pdp11>(&even, enc(Parity::Even)).unwrap(), (5, PDP11_RAW.to_vec()));
+        assert_eq!(convert::<Pdp11>(&mark, enc(Parity::None)).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let error = convert::<Pdp11>(&even, enc(Parity::Odd)).unwrap_err();
+        assert_eq!(error.into_inner().unwrap().downcast_ref::<Error>(), Some(&Error::Parity { offset: 0 }));
+
+        // Decoding checks the raw words' bytes, and skipping bad words doesn't skip bad parity
+        let dec = |parity, on_error| Options { direction: Direction::Decode, on_error, parity, ..Options::default() };
+        let words: [u8; 4] = [0x7e, 0x49, 0x76, 0xc9];
+        let odd: Vec<u8> = words.iter().map(|b| if b.count_ones() % 2 == 0 { b ^ 0x80 } else { *b }).collect();
+        assert_eq!(convert::<Pdp11>(&words.map(|b| b & 0x7f), dec(Parity::None, OnError::Fail)).unwrap().1,
+                   convert::<Pdp11>(&odd, dec(Parity::Odd, OnError::Fail)).unwrap().1);
+        let error = convert::<Pdp11>(&odd, dec(Parity::Even, OnError::Skip)).unwrap_err();
+        assert_eq!(error.into_inner().unwrap().downcast_ref::<Error>(), Some(&Error::Parity { offset: 0 }));
+        let mut bad = odd.clone();
+        bad[3] ^= 0x80;
+        let error = convert::<Pdp11>(&bad, dec(Parity::Odd, OnError::Skip)).unwrap_err();
+        assert_eq!(error.into_inner().unwrap().downcast_ref::<Error>(), Some(&Error::Parity { offset: 3 }));
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn decode_mapped_file() {
+        let path = std::env::temp_dir().join(format!("radix50-decode-mapped-file-{}", std::process::id()));
+        let decode = |bytes: &[u8], endian, encoding, on_error| {
+            std::fs::write(&path, bytes).unwrap();
+            let mut out = Vec::new();
+            decode_file(&path, endian, encoding, on_error, &mut out).map(|count| (count, out))
+        };
+        assert_eq!(decode(&PDP11_RAW, Endian::Big, Encoding::Pdp11, OnError::Fail).unwrap(), (5, b"THIS IS A TEST ".to_vec()));
+        assert_eq!(decode(&PDP10_RAW, Endian::Big, Encoding::Pdp10, OnError::Fail).unwrap(), (3, b"THIS IS A TEST    ".to_vec()));
+        assert_eq!(decode(&[0x49, 0x7e, 0xc9], Endian::Little, Encoding::Pdp11, OnError::Replace('?')).unwrap(), (1, b"THI???".to_vec()));
+        assert_eq!(decode(&PDP11_RAW[..9], Endian::Big, Encoding::Pdp11, OnError::Skip).unwrap(), (4, b"THIS IS A TE".to_vec()));
+        assert_eq!(decode(&PDP11_RAW[..9], Endian::Big, Encoding::Pdp11, OnError::Fail).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(decode(&[], Endian::Big, Encoding::Pdp11, OnError::Fail).unwrap(), (0, vec![]));
+
+        // Bigger than a block, so it takes more than one write
+        let words = Pdp11::encode(&"ABC".repeat(10000)).unwrap();
+        let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_be_bytes()).collect();
+        assert_eq!(decode(&bytes, Endian::Big, Encoding::Pdp11, OnError::Fail).unwrap(), (10000, "ABC".repeat(10000).into_bytes()));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    // Hands out one byte per read, with an interruption before each one, and then fails instead of ending.
+    struct Trickle<'a> { bytes: &'a [u8], interrupt: bool }
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.interrupt = !self.interrupt;
+            if self.interrupt { return Err(io::ErrorKind::Interrupted.into()) }
+            let Some((first, rest)) = self.bytes.split_first() else { return Err(io::Error::other("unplugged")) };
+            buf[0] = *first;
+            self.bytes = rest;
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn word_readers() {
+        let words: Vec<_> = words_from_reader(&PDP11_RAW[..], Endian::Big).map(Result::unwrap).collect();
+        assert_eq!(words, [32329, 30409, 30401, 805, 31200]);
+        let words: Vec<_> = words32_from_reader(&PDP10_RAW[..], Endian::Big).map(Result::unwrap).collect();
+        assert_eq!(words, [3119342419, 2970305215, 3046400000]);
+        assert_eq!(words_from_reader(&[][..], Endian::Little).count(), 0);
+
+        let mut words = words32_from_reader(&PDP10_RAW[..7], Endian::Little);
+        assert_eq!(words.next().unwrap().unwrap(), 0x5363edb9);
+        assert_eq!(words.next().unwrap().unwrap_err().into_inner().unwrap().downcast_ref::<Error>(), Some(&Error::TrailingBytes { len: 3 }));
+        assert!(words.next().is_none());
+
+        let mut words = words_from_reader(Trickle { bytes: &PDP11_RAW[..4], interrupt: false }, Endian::Big);
+        assert_eq!(words.next().unwrap().unwrap(), 32329);
+        assert_eq!(words.next().unwrap().unwrap(), 30409);
+        assert_eq!(words.next().unwrap().unwrap_err().to_string(), "unplugged");
+        assert!(words.next().is_none());
+
+        // A read error isn't bad data, so it isn't skipped
+        let dec = Options { direction: Direction::Decode, on_error: OnError::Skip, ..Options::default() };
+        assert_eq!(file::<Pdp11>(Trickle { bytes: &PDP11_RAW[..4], interrupt: false }, io::sink(), dec).unwrap_err().to_string(), "unplugged");
+    }
+}
@@ -0,0 +1,115 @@
+// RADIX-50 codec with a character table chosen at runtime
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+use alloc::{string::String, vec::Vec};
+use core::borrow::Borrow;
+use core::marker::PhantomData;
+
+use crate::{Codec, DefaultCodec, Error};
+
+/// A RADIX-50 codec using a character table supplied at runtime.
+///
+/// Some historical systems ordered the punctuation codes a little differently than the standard tables. This
+/// lets you encode and decode with such a table without having to write a new [`Codec`] type.
+///
+/// The word layout (word size and characters per word) comes from the `C` type parameter, which defaults to
+/// [`Pdp11`](crate::pdp11::Pdp11) (3 characters per 16 bit word). Use [`Pdp10`](crate::pdp10::Pdp10) for 6 characters per 32 bit word.
+///
+/// # Examples
+/// ```
+/// # #[cfg(all(feature = "pdp10", feature = "pdp11"))] {
+/// # use radix50::{CustomCodec, Error, pdp10::Pdp10};
+/// let mut table = radix50::pdp11::RADIX50_DECODE;
+/// table.swap(27, 28); // '.' before '$'
+///
+/// let codec: CustomCodec = CustomCodec::new(table).unwrap();
+/// assert_eq!(codec.encode("A.$").unwrap(), radix50::pdp11::encode("A$.").unwrap());
+/// assert_eq!(codec.decode(&codec.encode("THIS.IS$A.TEST").unwrap()), "THIS.IS$A.TEST ");
+///
+/// let codec = CustomCodec::<Pdp10>::new(table).unwrap();
+/// assert_eq!(codec.encode("THIS.IS$A.TEST").unwrap().len(), 3);
+///
+/// table[3] = 'A';
+/// assert_eq!(CustomCodec::<Pdp10>::new(table), Err(Error::DuplicateChar { char: 'A', pos: 4 }));
+/// # }
+/// ```
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct CustomCodec<C: Codec = DefaultCodec> {
+    encode: [Option<u8>; 128],
+    legal: u128,
+    decode: [char; 40],
+    layout: PhantomData<C>,
+}
+
+impl<C: Codec> CustomCodec<C> {
+    /// Create a codec from a decode table (the character for each of the 40 RADIX-50 codes).
+    ///
+    /// It will return an [`Error::NonAsciiChar`] if a character in the table isn't ASCII, or an
+    /// [`Error::DuplicateChar`] if a character appears more than once.
+    pub fn new(table: [char; 40]) -> Result<Self, Error> {
+        for (i, &c) in table.iter().enumerate() {
+            if !c.is_ascii() {
+                return Err(Error::NonAsciiChar { char: c, pos: i + 1 });
+            }
+            if table[..i].contains(&c) {
+                return Err(Error::DuplicateChar { char: c, pos: i + 1 });
+            }
+        }
+        let encode = crate::invert(&table);
+        Ok(CustomCodec { encode, legal: crate::legal_mask(&encode), decode: table, layout: PhantomData })
+    }
+
+    /// The character table the codec was created with.
+    pub fn charset(&self) -> &[char; 40] { &self.decode }
+
+    /// Encode a string, space padding it to a multiple of [`C::CHARS`](Codec::CHARS) characters. See
+    /// [`pdp11::encode`](crate::pdp11::encode).
+    pub fn encode(&self, s: &str) -> Result<Vec<C::Word>, Error> { crate::encode_with::<C>(&self.encode, self.legal, s) }
+
+    /// Encode [`C::CHARS`](Codec::CHARS) characters into a word. See [`pdp11::encode_word`](crate::pdp11::encode_word).
+    pub fn encode_word(&self, s: &str) -> Result<C::Word, Error> { crate::encode_word_with::<C>(&self.encode, s) }
+
+    /// Decode a sequence of words into a string. See [`pdp11::decode`](crate::pdp11::decode).
+    pub fn decode<I>(&self, words: I) -> String where I: IntoIterator, I::Item: Borrow<C::Word> { crate::decode_with::<C, I>(&self.decode, words) }
+
+    /// Decode a word into a [`C::CHARS`](Codec::CHARS) character string. See [`pdp11::decode_word`](crate::pdp11::decode_word).
+    pub fn decode_word(&self, w: C::Word) -> String { crate::decode_word_with::<C>(&self.decode, w) }
+}
+
+#[cfg(all(test, feature = "pdp11"))]
+mod tests {
+    use super::*;
+    #[cfg(feature = "pdp10")]
+    use crate::pdp10;
+    use crate::pdp11::{self, Pdp11};
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn matches_builtin_codecs() {
+        let pdp11_codec: CustomCodec = CustomCodec::new(pdp11::RADIX50_DECODE).unwrap();
+        let pdp10_codec = CustomCodec::<pdp10::Pdp10>::new(pdp10::RADIX50_DECODE).unwrap();
+        assert_eq!(pdp11_codec.encode("THIS IS A TEST"), pdp11::encode("THIS IS A TEST"));
+        assert_eq!(pdp10_codec.encode("THIS IS A TEST"), pdp10::encode("THIS IS A TEST"));
+        assert_eq!(pdp11_codec.encode("THIS IS A TES_"), pdp11::encode("THIS IS A TES_"));
+        assert_eq!(pdp10_codec.encode_word("ABCDEF"), pdp10::encode_word("ABCDEF"));
+        assert_eq!(pdp11_codec.decode([32329, 30409, 30401, 805, 31200]), "THIS IS A TEST ");
+        assert_eq!(pdp10_codec.decode_word(1157975016), "ABCDEF");
+    }
+
+    #[test]
+    fn validation() {
+        let mut table = pdp11::RADIX50_DECODE;
+        table[29] = '_';
+        let codec: CustomCodec = CustomCodec::new(table).unwrap();
+        assert_eq!(codec.decode_word(pdp11::encode_word("A%B").unwrap()), "A_B");
+        assert_eq!(codec.encode("A%B"), Err(Error::IllegalChar { char: '%', pos: 2 }));
+        assert_eq!(codec.charset(), &table);
+
+        table[39] = 'é';
+        assert_eq!(CustomCodec::<Pdp11>::new(table), Err(Error::NonAsciiChar { char: 'é', pos: 40 }));
+        table[39] = ' ';
+        assert_eq!(CustomCodec::<Pdp11>::new(table), Err(Error::DuplicateChar { char: ' ', pos: 40 }));
+    }
+}
@@ -0,0 +1,178 @@
+// DDT's in-core symbol table
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Reading the symbol tables that LINK-10 loads into memory for DDT.
+//!
+//! The table is a run of 2 word entries: a [`symbol10`] name (whose flags say what [kind](SymbolKind) of symbol it
+//! is) and a value. Each program's local symbols come first, then an entry with the program's name. Programs
+//! find the table through `.JBSYM` (address 0o116), which holds the table's negative length in its left half and
+//! its address in its right half (see [`pointer()`]).
+//!
+//! Given the words of the table (from a crash dump, say), [`Table::parse`] decodes it and sorts the symbols out by
+//! program.
+//!
+//! [`symbol10`]: crate::symbol10
+//!
+//! # Examples
+//! ```
+//! # use radix50::{ddt::{self, Table}, symbol10};
+//! # let mut dump = vec![0; 0o1010];
+//! # dump[0o116] = 0o777774_001000;
+//! # dump[0o1000..0o1004].copy_from_slice(&[symbol10::encode_symbol("LOOP", 2)?, 0o142, symbol10::encode_symbol("MAIN", 0)?, 0]);
+//! let (address, len) = ddt::pointer(dump[0o116]).unwrap();
+//! let table = Table::parse(&dump[address as usize..][..len]);
+//! for program in &table.programs {
+//!     println!("{}:", program.name);
+//!     for sym in table.locals(program) {
+//!         println!("  {:6} {:06o}", sym.name, sym.value);
+//!     }
+//! }
+//! # assert_eq!(table.get("LOOP").unwrap().value, 0o142);
+//! # Ok::<(), radix50::Error>(())
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::ops::Range;
+
+use crate::rel10::{self, Symbol, SymbolKind};
+
+/// Split a `.JBSYM` style pointer into the table's address and its length in words. Returns `None` if the
+/// pointer is 0 (there's no table).
+///
+/// # Examples
+/// ```
+/// # use radix50::ddt::pointer;
+/// assert_eq!(pointer(0o777000_010000), Some((0o10000, 0o1000)));
+/// assert_eq!(pointer(0), None);
+/// ```
+pub fn pointer(w: u64) -> Option<(u32, usize)> {
+    (w != 0).then_some(((w & 0o777777) as u32, ((w >> 18).wrapping_neg() & 0o777777) as usize))
+}
+
+/// A program in a [`Table`].
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+pub struct Program {
+    pub name: String,
+    /// Where its local symbols are in [`Table::symbols`].
+    pub symbols: Range<usize>,
+}
+
+/// A decoded symbol table.
+#[derive(Debug,Clone,PartialEq,Eq,Default)]
+pub struct Table {
+    /// Every entry in the table, in order (including the program names).
+    pub symbols: Vec<Symbol>,
+    /// The programs, in order.
+    pub programs: Vec<Program>,
+}
+
+impl Table {
+    /// Decode a symbol table from its words. Any odd word at the end is ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{ddt::Table, symbol10};
+    /// let words = [symbol10::encode_symbol("X", 0o2)?, 1, symbol10::encode_symbol("Y", 0o12)?, 2, symbol10::encode_symbol("P1", 0)?, 0,
+    ///              symbol10::encode_symbol("P2", 0)?, 0,
+    ///              symbol10::encode_symbol("START", 0o1)?, 0o140];
+    /// let table = Table::parse(&words);
+    /// assert_eq!(table.programs.iter().map(|p| (p.name.as_str(), p.symbols.clone())).collect::<Vec<_>>(),
+    ///            [("P1", 0..2), ("P2", 3..3)]);
+    /// assert_eq!(table.get("START").unwrap().value, 0o140);
+    /// # Ok::<(), radix50::Error>(())
+    /// ```
+    pub fn parse(words: &[u64]) -> Table {
+        let symbols: Vec<Symbol> = rel10::symbols(words).collect();
+        let mut programs = Vec::new();
+        let mut start = 0;
+        for (n, sym) in symbols.iter().enumerate() {
+            if sym.kind() == SymbolKind::ProgramName {
+                programs.push(Program { name: sym.name.clone(), symbols: start..n });
+                start = n + 1;
+            }
+        }
+        Table { symbols, programs }
+    }
+
+    /// The local symbols of `program`.
+    pub fn locals(&self, program: &Program) -> &[Symbol] { &self.symbols[program.symbols.clone()] }
+
+    /// Look up a program by name.
+    pub fn program(&self, name: &str) -> Option<&Program> { self.programs.iter().find(|p| p.name == name) }
+
+    /// Look up a symbol definition by name, the way DDT does when there's no open program: a global definition if
+    /// there is one, otherwise the first local definition.
+    pub fn get(&self, name: &str) -> Option<&Symbol> {
+        let defined = |kind| self.symbols.iter().find(|s| s.name == name && s.kind() == kind);
+        defined(SymbolKind::GlobalDefinition).or_else(|| defined(SymbolKind::LocalDefinition))
+    }
+
+    /// Find the symbol DDT would print for `address`: the closest definition at or before it, and how far past it
+    /// `address` is (`LOOP+3`). Returns `None` if there's no definition at or below `address`.
+    ///
+    /// Like DDT, it skips suppressed symbols. When several symbols have the same value, a global one wins, and then
+    /// the first one in the table. For a saved program, get the table with [`Exe::symbol_table`].
+    ///
+    /// [`Exe::symbol_table`]: crate::exe10::Exe::symbol_table
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{ddt::Table, symbol10};
+    /// let words = [symbol10::encode_symbol("LOOP", 0o2)?, 0o142, symbol10::encode_symbol("L1", 0o2)?, 0o150,
+    ///              symbol10::encode_symbol("MAIN", 0)?, 0, symbol10::encode_symbol("START", 0o1)?, 0o150];
+    /// let table = Table::parse(&words);
+    /// assert_eq!(table.symbolicate(0o145), Some(("LOOP", 3)));
+    /// assert_eq!(table.symbolicate(0o150), Some(("START", 0)));
+    /// assert_eq!(table.symbolicate(0o100), None);
+    /// # Ok::<(), radix50::Error>(())
+    /// ```
+    pub fn symbolicate(&self, address: u64) -> Option<(&str, u64)> {
+        let sym = self.symbols.iter().rev()
+                              .filter(|s| matches!(s.kind(), SymbolKind::GlobalDefinition | SymbolKind::LocalDefinition))
+                              .filter(|s| !s.is_suppressed() && s.value <= address)
+                              .max_by_key(|s| (s.value, s.kind() == SymbolKind::GlobalDefinition))?;
+        Some((&sym.name, address - sym.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol10;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn programs() {
+        let entries = [("A", 2, 1), ("B", 2, 2), ("PROG1", 0, 0), ("A", 2, 3), ("B", 1, 4), ("PROG2", 0, 0), ("C", 1, 5)];
+        let mut words: Vec<u64> = entries.iter().flat_map(|&(name, flags, value)| [symbol10::encode_symbol(name, flags).unwrap(), value])
+                                         .collect();
+        words.push(0o777);
+        let table = Table::parse(&words);
+        assert_eq!(table.symbols.len(), 7);
+        let prog2 = table.program("PROG2").unwrap();
+        assert_eq!(table.locals(prog2).iter().map(|s| s.value).collect::<Vec<_>>(), [3, 4]);
+        assert_eq!(table.program("PROG3"), None);
+        assert_eq!(table.get("A").unwrap().value, 1);
+        assert_eq!(table.get("B").unwrap().value, 4);
+        assert_eq!(table.get("C").unwrap().value, 5);
+        assert_eq!(table.get("PROG1"), None);
+        assert_eq!(Table::parse(&[]), Table::default());
+    }
+
+    #[test]
+    fn symbolicate() {
+        let entries = [("A", 2, 0o100), ("HIDDEN", 0o12, 0o104), ("B", 2, 0o200), ("PROG1", 0, 0o1000), ("BLK", 3, 0o300),
+                       ("REQ", 0o14, 0o400), ("C", 2, 0o200), ("G", 1, 0o200)];
+        let words: Vec<u64> = entries.iter().flat_map(|&(name, flags, value)| [symbol10::encode_symbol(name, flags).unwrap(), value])
+                                     .collect();
+        let table = Table::parse(&words);
+        assert_eq!(table.symbolicate(0o77), None);
+        assert_eq!(table.symbolicate(0o100), Some(("A", 0)));
+        assert_eq!(table.symbolicate(0o105), Some(("A", 5)));
+        assert_eq!(table.symbolicate(0o200), Some(("G", 0)));
+        assert_eq!(table.symbolicate(0o777777), Some(("G", 0o777577)));
+        assert_eq!(Table::default().symbolicate(0), None);
+    }
+}
@@ -0,0 +1,241 @@
+// TC11 DECtape images
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Listing the files on TC11 DECtape images (SIMH `.dta`/`.dt` files).
+//!
+//! A PDP-11 DECtape has 578 blocks of 256 words. SIMH stores an image either as 16 bit little endian words (512
+//! bytes a block) or, for its 18 bit format, as 32 bit little endian words with the data in the low 16 bits.
+//! [`Image`] reads either.
+//!
+//! RT-11 DECtapes use the same directory layout as RT-11 disks, so with the `std` feature
+//! [`Image::rt11_volume`] hands the tape to [`rt11::Volume`](crate::rt11::Volume). DOS-11 DECtapes keep a master
+//! file directory (MFD) at block 0o100 that lists each [`Uic`]'s user file directory (UFD); the UFD entries are
+//! laid out like [XXDP's](crate::xxdp). [`dos11_files`] lists them.
+//!
+//! # Examples
+//! ```
+//! # use radix50::dectape::{self, Image};
+//! # let mut bytes = vec![0u8; dectape::BLOCKS * 512];
+//! # let word = |bytes: &mut [u8], block: usize, n: usize, w: u16| bytes[block * 512 + n * 2..][..2].copy_from_slice(&w.to_le_bytes());
+//! # word(&mut bytes, 0o100, 0, 0o101);
+//! # for (n, w) in [0o404, 0o102, 9].into_iter().enumerate() { word(&mut bytes, 0o101, n + 1, w) }
+//! # for (n, w) in [7457, 20840, 30462, 12045, 0, 230, 20, 249].into_iter().enumerate() { word(&mut bytes, 0o102, n + 1, w) }
+//! let image = Image::new(&bytes);
+//! for file in dectape::dos11_files(&image)? {
+//!     println!("{} {:10} {:5}", file.uic, file.entry.filename(), file.entry.length);
+//! }
+//! # assert_eq!(dectape::dos11_files(&image)?[0].entry.filename(), "DZQMA.SAV");
+//! # Ok::<(), dectape::DirError>(())
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io::{self, Cursor};
+
+use crate::{dos11::Uic, xxdp};
+#[cfg(feature = "std")]
+use crate::rt11;
+
+/// How many blocks are on a DECtape.
+pub const BLOCKS: usize = 578;
+
+/// How many words are in a block.
+pub const BLOCK_WORDS: usize = 256;
+
+/// The first block of a DOS-11 DECtape's master file directory.
+pub const MFD_BLOCK: u16 = 0o100;
+
+/// How an image stores its words.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum Format {
+    /// 2 bytes a word.
+    Words16,
+    /// 4 bytes a word (SIMH's 18 bit format).
+    Words18,
+}
+
+impl Format {
+    /// How many bytes each word takes.
+    pub fn word_bytes(self) -> usize {
+        match self {
+            Format::Words16 => 2,
+            Format::Words18 => 4,
+        }
+    }
+}
+
+/// A DECtape image.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Image<'a> {
+    bytes: &'a [u8],
+    pub format: Format,
+}
+
+impl<'a> Image<'a> {
+    /// Use `bytes` as an image, guessing its format from its size: a full tape in the 18 bit format is
+    /// [`Format::Words18`], anything else is [`Format::Words16`].
+    pub fn new(bytes: &'a [u8]) -> Image<'a> {
+        let format = if bytes.len() == BLOCKS * BLOCK_WORDS * Format::Words18.word_bytes() { Format::Words18 } else { Format::Words16 };
+        Image { bytes, format }
+    }
+
+    /// Use `bytes` as an image in the given format.
+    pub fn with_format(bytes: &'a [u8], format: Format) -> Image<'a> { Image { bytes, format } }
+
+    /// How many whole blocks the image has.
+    pub fn blocks(&self) -> usize { self.bytes.len() / (BLOCK_WORDS * self.format.word_bytes()) }
+
+    /// Read a block's words. Returns `None` if it's past the end of the image.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::dectape::{Image, Format};
+    /// let mut bytes = vec![0u8; 2 * 1024];
+    /// bytes[1024..1028].copy_from_slice(&[0x34, 0x12, 0x03, 0]);
+    /// let image = Image::with_format(&bytes, Format::Words18);
+    /// assert_eq!(image.block(1).unwrap()[0], 0x1234);
+    /// assert_eq!(image.block(2), None);
+    /// ```
+    pub fn block(&self, n: u16) -> Option<[u16; BLOCK_WORDS]> {
+        let size = self.format.word_bytes();
+        let bytes = self.bytes.get(n as usize * BLOCK_WORDS * size..)?.get(..BLOCK_WORDS * size)?;
+        let mut words = [0; BLOCK_WORDS];
+        for (w, b) in words.iter_mut().zip(bytes.chunks_exact(size)) { *w = u16::from_le_bytes([b[0], b[1]]) }
+        Some(words)
+    }
+
+    /// The image as 16 bit words (512 bytes a block), the way an RT-11 disk image would be.
+    #[cfg(feature = "std")]
+    pub fn to_words16(&self) -> Vec<u8> {
+        match self.format {
+            Format::Words16 => self.bytes.to_vec(),
+            Format::Words18 => self.bytes.chunks_exact(4).flat_map(|b| [b[0], b[1]]).collect(),
+        }
+    }
+
+    /// Read the RT-11 directory from the tape (see [`rt11::Volume::open`]).
+    #[cfg(feature = "std")]
+    pub fn rt11_volume(&self) -> io::Result<rt11::Volume<Cursor<Vec<u8>>>> { rt11::Volume::open(Cursor::new(self.to_words16())) }
+}
+
+/// Errors from reading a DOS-11 DECtape directory.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+#[non_exhaustive]
+pub enum DirError {
+    /// A directory block link points past the end of the image.
+    BadBlock { block: u16 },
+    /// The chain of directory blocks loops back on itself at `block`.
+    Loop { block: u16 },
+}
+
+impl core::error::Error for DirError {
+}
+
+impl fmt::Display for DirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DirError::BadBlock { block } => write!(f, "Directory block {:o} is past the end of the tape", block),
+            DirError::Loop { block }     => write!(f, "Directory chain loops at block {:o}", block),
+        }
+    }
+}
+
+/// A file in a DOS-11 DECtape directory.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct DosFile {
+    /// The owner (whose UFD the file is in).
+    pub uic: Uic,
+    pub entry: xxdp::Entry,
+}
+
+/// List the files in a DOS-11 DECtape's directories, UFD by UFD.
+pub fn dos11_files(image: &Image<'_>) -> Result<Vec<DosFile>, DirError> {
+    let mut seen = Vec::new();
+    let mut read = |block: u16| {
+        if seen.contains(&block) { return Err(DirError::Loop { block }) }
+        seen.push(block);
+        image.block(block).ok_or(DirError::BadBlock { block })
+    };
+    let mut ufds = Vec::new();
+    let mut mfd = read(MFD_BLOCK)?[0];
+    while mfd != 0 {
+        let block = read(mfd)?;
+        ufds.extend(block[1..].chunks_exact(4).filter(|e| e[0] != 0).map(|e| (Uic::from(e[0]), e[1])));
+        mfd = block[0];
+    }
+    let mut files = Vec::new();
+    for (uic, mut ufd) in ufds {
+        while ufd != 0 {
+            let block = read(ufd)?;
+            files.extend(xxdp::entries(&block).map(|entry| DosFile { uic, entry }));
+            ufd = xxdp::next_block(&block).unwrap_or(0);
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rt11;
+    use alloc::{string::{String, ToString}, vec};
+
+    fn put(bytes: &mut [u8], block: usize, at: usize, words: &[u16]) {
+        for (n, w) in words.iter().enumerate() { bytes[(block * BLOCK_WORDS + at + n) * 2..][..2].copy_from_slice(&w.to_le_bytes()) }
+    }
+
+    fn dos11_tape() -> Vec<u8> {
+        let mut bytes = vec![0; BLOCKS * BLOCK_WORDS * 2];
+        put(&mut bytes, 0o100, 0, &[0o101]);
+        put(&mut bytes, 0o101, 0, &[0, 0o1001, 0o102, 9, 0, 0, 0, 0, 0, 0o1002, 0o104, 9, 0]);
+        let entry = |name| { let mut e = rt11::encode_filename(name).unwrap().to_vec(); e.extend([9001, 0, 0o200, 1, 0o200]); e };
+        put(&mut bytes, 0o102, 0, &[0o103]);
+        put(&mut bytes, 0o102, 1, &entry("A.MAC"));
+        put(&mut bytes, 0o103, 1 + 27 * 9, &entry("B.OBJ"));
+        put(&mut bytes, 0o104, 1, &entry("C"));
+        bytes
+    }
+
+    #[test]
+    fn dos11() {
+        let bytes = dos11_tape();
+        let files = dos11_files(&Image::new(&bytes)).unwrap();
+        assert_eq!(files.iter().map(|f| (f.uic.to_string(), f.entry.filename())).collect::<Vec<(String, String)>>(),
+                   [("[2,1]".into(), "A.MAC".into()), ("[2,1]".into(), "B.OBJ".into()), ("[2,2]".into(), "C".into())]);
+        assert_eq!(files[0].entry.date(), Some((1979, 1)));
+
+        // The same tape in the 18 bit format
+        let wide: Vec<u8> = bytes.chunks_exact(2).flat_map(|b| [b[0], b[1], 0, 0]).collect();
+        let image = Image::new(&wide);
+        assert_eq!(image.format, Format::Words18);
+        assert_eq!(image.blocks(), BLOCKS);
+        assert_eq!(dos11_files(&image).unwrap(), files);
+
+        let mut looped = bytes.clone();
+        put(&mut looped, 0o103, 0, &[0o102]);
+        assert_eq!(dos11_files(&Image::new(&looped)), Err(DirError::Loop { block: 0o102 }));
+        let mut past_end = bytes;
+        put(&mut past_end, 0o104, 0, &[1000]);
+        assert_eq!(dos11_files(&Image::new(&past_end)), Err(DirError::BadBlock { block: 1000 }));
+        assert_eq!(dos11_files(&Image::new(&[])), Err(DirError::BadBlock { block: MFD_BLOCK }));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn rt11_volume() {
+        let mut bytes = vec![0; BLOCKS * BLOCK_WORDS * 2];
+        let mut segment = vec![1, 0, 1, 0, 8, rt11::status::PERMANENT];
+        segment.extend(rt11::encode_filename("SWAP.SYS").unwrap());
+        segment.extend([3, 0, 0, rt11::status::END_OF_SEGMENT]);
+        put(&mut bytes, 6, 0, &segment);
+        put(&mut bytes, 8, 0, &[0o123456]);
+        let wide: Vec<u8> = bytes.chunks_exact(2).flat_map(|b| [b[0], b[1], 0, 0]).collect();
+        let mut volume = Image::new(&wide).rt11_volume().unwrap();
+        let file = *volume.files().next().unwrap();
+        assert_eq!((file.filename().as_str(), file.start, file.length), ("SWAP.SYS", 8, 3));
+        assert_eq!(volume.read(&file).unwrap()[..2], [0o56, 0o247]);
+    }
+}
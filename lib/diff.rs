@@ -0,0 +1,178 @@
+// Comparing symbol tables
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Comparing the symbols defined in two builds of a program: which were added, which were removed, and which moved
+//! to a different value.
+//!
+//! [`diff`] works on anything that implements [`Symbols`]: PDP-11 object and symbol table files (read with
+//! [`stb::SymbolTable::parse`], which takes both), PDP-10 `.REL` modules, and DDT symbol tables. The two sides don't
+//! have to be the same kind.
+//!
+//! [`stb::SymbolTable::parse`]: crate::stb::SymbolTable::parse
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "pdp11")] {
+//! # use radix50::{diff, obj11::{self, GsdEntry, GsdKind, flags}, stb::SymbolTable};
+//! # let obj = |syms: &[(&str, u16)]| {
+//! #     let mut bytes = Vec::new();
+//! #     let entries: Vec<GsdEntry> = syms.iter().map(|&(name, value)| GsdEntry::new(name, GsdKind::GlobalSymbol, flags::GLOBAL_DEFINITION, value).unwrap()).collect();
+//! #     obj11::write_gsd(&entries, &mut bytes);
+//! #     bytes
+//! # };
+//! # let (old_file, new_file) = (obj(&[("START", 0o1000), ("OLD", 0o1100)]), obj(&[("START", 0o1000), ("NEW", 0o1200)]));
+//! let old = SymbolTable::parse(&old_file)?;
+//! let new = SymbolTable::parse(&new_file)?;
+//! for change in diff::diff(&old, &new) {
+//!     println!("{}", change);
+//! }
+//! # assert_eq!(diff::diff(&old, &new).iter().map(|c| c.to_string()).collect::<Vec<_>>(), ["+ NEW 1200", "- OLD 1100"]);
+//! # }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::fmt;
+
+/// A symbol table that [`diff`] can compare.
+pub trait Symbols {
+    /// The defined symbols, as names and values.
+    fn defined_symbols(&self) -> Vec<(String, u64)>;
+}
+
+#[cfg(feature = "pdp11")]
+impl Symbols for crate::stb::SymbolTable {
+    fn defined_symbols(&self) -> Vec<(String, u64)> {
+        use alloc::string::ToString;
+        self.symbols.iter().map(|s| (s.name.to_string(), s.value as u64)).collect()
+    }
+}
+
+/// Global and local definitions (not program names, block names, or requests).
+#[cfg(feature = "pdp10")]
+impl Symbols for crate::rel10::Module {
+    fn defined_symbols(&self) -> Vec<(String, u64)> { definitions(&self.symbols) }
+}
+
+/// Global and local definitions (not program names, block names, or requests).
+#[cfg(feature = "pdp10")]
+impl Symbols for crate::ddt::Table {
+    fn defined_symbols(&self) -> Vec<(String, u64)> { definitions(&self.symbols) }
+}
+
+#[cfg(feature = "pdp10")]
+fn definitions(symbols: &[crate::rel10::Symbol]) -> Vec<(String, u64)> {
+    use crate::rel10::SymbolKind;
+    symbols.iter().filter(|s| matches!(s.kind(), SymbolKind::GlobalDefinition | SymbolKind::LocalDefinition))
+                  .map(|s| (s.name.clone(), s.value)).collect()
+}
+
+/// A difference between two symbol tables.
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+pub enum Change {
+    /// The symbol is only in the new table.
+    Added { name: String, value: u64 },
+    /// The symbol is only in the old table.
+    Removed { name: String, value: u64 },
+    /// The symbol is in both tables, with different values.
+    Moved { name: String, old: u64, new: u64 },
+}
+
+impl Change {
+    /// The symbol's name.
+    pub fn name(&self) -> &str {
+        match self {
+            Change::Added { name, .. } | Change::Removed { name, .. } | Change::Moved { name, .. } => name,
+        }
+    }
+}
+
+/// Formats the change like a line of a diff, with octal values: `+ NAME 1200`, `- NAME 1100`, or
+/// `~ NAME 1000 -> 1004`.
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Added { name, value }    => write!(f, "+ {} {:o}", name, value),
+            Change::Removed { name, value }  => write!(f, "- {} {:o}", name, value),
+            Change::Moved { name, old, new } => write!(f, "~ {} {:o} -> {:o}", name, old, new),
+        }
+    }
+}
+
+/// Compare the symbols defined in two tables. Returns the changes, sorted by name.
+///
+/// Symbols are matched up by name. If a table defines a name more than once (local symbols in different modules,
+/// say), only its first definition is compared.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "pdp10")] {
+/// # use radix50::{diff::{diff, Change}, ddt::Table, symbol10};
+/// let table = |syms: &[(&str, u64)]| Table::parse(&syms.iter().flat_map(|&(name, value)| [symbol10::encode_symbol(name, 0o1).unwrap(), value])
+///                                                               .collect::<Vec<_>>());
+/// let old = table(&[("START", 0o140), ("LOOP", 0o142), ("GONE", 0o150)]);
+/// let new = table(&[("START", 0o140), ("LOOP", 0o143), ("ADDED", 0o151)]);
+/// assert_eq!(diff(&old, &new), [Change::Added { name: "ADDED".into(), value: 0o151 },
+///                               Change::Removed { name: "GONE".into(), value: 0o150 },
+///                               Change::Moved { name: "LOOP".into(), old: 0o142, new: 0o143 }]);
+/// # }
+/// ```
+pub fn diff<O: Symbols + ?Sized, N: Symbols + ?Sized>(old: &O, new: &N) -> Vec<Change> {
+    let by_name = |symbols: Vec<(String, u64)>| {
+        let mut map = BTreeMap::new();
+        for (name, value) in symbols { map.entry(name).or_insert(value); }
+        map
+    };
+    let (old, mut new) = (by_name(old.defined_symbols()), by_name(new.defined_symbols()));
+    let mut changes = Vec::new();
+    for (name, old) in old {
+        match new.remove(&name) {
+            None                    => changes.push(Change::Removed { name, value: old }),
+            Some(new) if new != old => changes.push(Change::Moved { name, old, new }),
+            Some(_)                 => {},
+        }
+    }
+    changes.extend(new.into_iter().map(|(name, value)| Change::Added { name, value }));
+    changes.sort_by(|a, b| a.name().cmp(b.name()));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec};
+
+    struct Raw(Vec<(&'static str, u64)>);
+
+    impl Symbols for Raw {
+        fn defined_symbols(&self) -> Vec<(String, u64)> { self.0.iter().map(|&(name, value)| (name.to_string(), value)).collect() }
+    }
+
+    #[test]
+    fn changes() {
+        let old = Raw(vec![("B", 2), ("A", 1), ("C", 3), ("C", 4), ("D", 5)]);
+        let new = Raw(vec![("D", 6), ("C", 3), ("E", 7), ("A", 1)]);
+        let changes = diff(&old, &new);
+        assert_eq!(changes, [Change::Removed { name: "B".into(), value: 2 }, Change::Moved { name: "D".into(), old: 5, new: 6 },
+                             Change::Added { name: "E".into(), value: 7 }]);
+        assert_eq!(changes.iter().map(|c| c.to_string()).collect::<Vec<_>>(), ["- B 2", "~ D 5 -> 6", "+ E 7"]);
+        assert_eq!(diff(&old, &old), []);
+        assert_eq!(diff(&Raw(vec![]), &Raw(vec![("A", 0o10)])), [Change::Added { name: "A".into(), value: 0o10 }]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    fn kinds() {
+        use crate::{rel10, symbol10, stb};
+        let module = rel10::Module { name: Some("MAIN".into()), entries: vec![],
+                                     symbols: [("MAIN", 0, 0), ("START", 1, 0o140), ("LOOP", 2, 0o142), ("EXT", 0o14, 0o200), ("BLK", 3, 0)]
+                                              .iter().flat_map(|&(name, flags, value)| rel10::symbols(&[symbol10::encode_symbol(name, flags).unwrap(), value]).collect::<Vec<_>>())
+                                              .collect() };
+        assert_eq!(module.defined_symbols(), [("START".into(), 0o140), ("LOOP".into(), 0o142)]);
+        let stb = stb::SymbolTable { symbols: vec![stb::Symbol { name: "START".parse().unwrap(), value: 0o140, flags: 0o10 }],
+                                     ..Default::default() };
+        assert_eq!(diff(&stb, &module), [Change::Added { name: "LOOP".into(), value: 0o142 }]);
+    }
+}
@@ -0,0 +1,86 @@
+// Display raw words as RADIX-50 text without building a String
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+use core::fmt;
+
+#[cfg(feature = "pdp10")]
+use crate::pdp10;
+#[cfg(feature = "pdp11")]
+use crate::pdp11;
+
+/// A wrapper that [`Display`](fmt::Display)s a raw word as its decoded RADIX-50 text.
+///
+/// It decodes straight into the formatter (on the stack), so words can be dropped into `format!` or `println!`
+/// without allocating an intermediate `String`. Width, fill, and alignment work the way they do for `str`.
+///
+/// `Rad50Display(word)` wraps a PDP-11 (`u16`) word and `Rad50Display::pdp10(word)` wraps a PDP-10 (`u32`) word.
+/// The characters are the same ones [`pdp11::decode_word_chars`] and [`pdp10::decode_word_chars`] return.
+///
+/// # Examples
+/// ```
+/// # #[cfg(all(feature = "pdp10", feature = "pdp11"))] {
+/// # use radix50::Rad50Display;
+/// assert_eq!(format!("{}", Rad50Display(1683_u16)), "ABC");
+/// assert_eq!(format!("[{:>5}]", Rad50Display(31400_u16)), "[  SY ]");
+/// assert_eq!(format!("[{:*<8}]", Rad50Display::pdp10(1704000000)), "[FOO   **]");
+/// assert_eq!(format!("{:.3}", Rad50Display::pdp10(1157975016)), "ABC");
+/// # }
+/// ```
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct Rad50Display<W = u16>(pub W);
+
+#[cfg(feature = "pdp11")]
+impl Rad50Display<u16> {
+    /// Wrap a PDP-11 word. This is the same as `Rad50Display(word)`.
+    pub const fn pdp11(word: u16) -> Self { Rad50Display(word) }
+}
+
+#[cfg(feature = "pdp10")]
+impl Rad50Display<u32> {
+    /// Wrap a PDP-10 word.
+    pub const fn pdp10(word: u32) -> Self { Rad50Display(word) }
+}
+
+#[cfg(feature = "pdp11")]
+impl fmt::Display for Rad50Display<u16> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { pad_chars(&pdp11::decode_word_chars(self.0), f) }
+}
+
+#[cfg(feature = "pdp10")]
+impl fmt::Display for Rad50Display<u32> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { pad_chars(&pdp10::decode_word_chars(self.0), f) }
+}
+
+fn pad_chars(chars: &[char], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    // The decode tables are all ASCII, so each char is a single byte.
+    let mut buf = [0_u8; 6];
+    for (b, c) in buf.iter_mut().zip(chars) { *b = *c as u8 }
+    f.pad(core::str::from_utf8(&buf[..chars.len()]).expect("decode tables are ASCII"))
+}
+
+#[cfg(all(test, feature = "pdp11"))]
+mod tests {
+    use super::*;
+    #[cfg(feature = "pdp10")]
+    use crate::testdata;
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn matches_decode() {
+        for golden in testdata::CORPUS {
+            assert_eq!(golden.pdp11.iter().map(|&w| Rad50Display(w).to_string()).collect::<String>(), pdp11::decode(golden.pdp11));
+            assert_eq!(golden.pdp10.iter().map(|&w| Rad50Display::pdp10(w).to_string()).collect::<String>(), pdp10::decode(golden.pdp10));
+        }
+        assert_eq!(Rad50Display(u16::MAX).to_string(), String::from_iter(pdp11::decode_word_chars(u16::MAX)));
+        assert_eq!(Rad50Display::pdp10(u32::MAX).to_string(), String::from_iter(pdp10::decode_word_chars(u32::MAX)));
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!(format!("[{:^7}]", Rad50Display::pdp11(1683)), "[  ABC  ]");
+        assert_eq!(format!("[{:2}]", Rad50Display(1683_u16)), "[ABC]");
+        assert_eq!(format!("{:?}", Rad50Display(1683_u16)), "Rad50Display(1683)");
+    }
+}
@@ -0,0 +1,255 @@
+// DOS/BATCH-11 file names and UICs
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! DOS/BATCH-11 file names, user identification codes (UICs), and magtapes.
+//!
+//! DOS-11 file names follow the same 6.3 rules as [RT-11's](crate::rt11): 2 [PDP-11 RADIX-50][pdp11] words for the
+//! name and 1 for the extension. Each user's files live in a user file directory (UFD) that's looked up in the
+//! master file directory (MFD) by [`Uic`], a `[group,member]` pair that's packed into one word and written in
+//! octal.
+//!
+//! DOS-11 magtapes (also written by RSTS/E and read by RT-11's FLX) start each file with a [`TapeHeader`] record.
+//! [`tape_files`] lists the files on a [SIMH tape image](crate::tap).
+//!
+//! [pdp11]: crate::pdp11::RADIX50_DECODE
+//!
+//! # Examples
+//! ```
+//! # use radix50::dos11::{self, Uic};
+//! let uic: Uic = "[1,4]".parse().unwrap();
+//! assert_eq!(u16::from(uic), 0o404);
+//! assert_eq!(dos11::decode_filename(&dos11::encode_filename("PIP.LDA").unwrap()), "PIP.LDA");
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{Error, parse::ParseError, rt11, tap::{self, TapError}};
+
+/// Encode a DOS-11 file name (`NAME.EXT`) into 3 words: 2 for the name and 1 for the extension.
+///
+/// The rules are the same as for [`rt11::encode_filename`]: the extension is optional, only letters and digits are
+/// allowed, and lower case letters are upper cased.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,dos11::encode_filename};
+/// assert_eq!(encode_filename("PIP.LDA"), Ok([25976, 0, 19361]));
+/// assert_eq!(encode_filename("MONLIB.CIL.X"), Err(Error::IllegalChar { char: '.', pos: 11 }));
+/// ```
+pub fn encode_filename(name: &str) -> Result<[u16; 3], Error> { rt11::encode_filename(name) }
+
+/// Decode 3 words into a DOS-11 file name, trimming the padding from each part (see [`rt11::decode_filename`]).
+///
+/// # Examples
+/// ```
+/// # use radix50::dos11::decode_filename;
+/// assert_eq!(decode_filename(&[25976, 0, 19361]), "PIP.LDA");
+/// ```
+pub fn decode_filename(words: &[u16; 3]) -> String { rt11::decode_filename(words) }
+
+/// A DOS-11 user identification code: a group and a member number, stored as one word (group in the high byte).
+///
+/// It displays as `[group,member]` in octal, and parses from the same (the brackets are optional). Parsing returns
+/// a [`ParseError::InvalidDigit`] for anything out of place, a [`ParseError::Empty`] if either number is missing,
+/// and a [`ParseError::Overflow`] if either is bigger than 0o377.
+///
+/// # Examples
+/// ```
+/// # use radix50::{dos11::Uic, parse::ParseError};
+/// let uic = Uic { group: 0o200, member: 0o1 };
+/// assert_eq!(uic.to_string(), "[200,1]");
+/// assert_eq!("200,1".parse(), Ok(uic));
+/// assert_eq!(Uic::from(0o100001), uic);
+///
+/// assert_eq!("[1,8]".parse::<Uic>(), Err(ParseError::InvalidDigit { char: '8', pos: 4 }));
+/// assert_eq!("[1]".parse::<Uic>(), Err(ParseError::Empty));
+/// assert_eq!("[400,1]".parse::<Uic>(), Err(ParseError::Overflow));
+/// ```
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,Default,PartialOrd,Ord)]
+pub struct Uic {
+    /// The group number (the high byte).
+    pub group: u8,
+    /// The member number within the group (the low byte).
+    pub member: u8,
+}
+
+impl From<u16> for Uic { fn from(w: u16) -> Self { Uic { group: (w >> 8) as u8, member: w as u8 } } }
+impl From<Uic> for u16 { fn from(uic: Uic) -> Self { (uic.group as u16) << 8 | uic.member as u16 } }
+
+impl fmt::Display for Uic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "[{:o},{:o}]", self.group, self.member) }
+}
+
+impl FromStr for Uic {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [group, member] = parse_pair(s, 8)?;
+        Ok(Uic { group, member })
+    }
+}
+
+/// Decode a DOS-11 date word, `(year-1970)*1000 + day of the year`, into a `(year, day of the year)` pair. Returns
+/// `None` if there's no date (or if the day isn't between 1 and 366).
+///
+/// # Examples
+/// ```
+/// # use radix50::dos11::decode_date;
+/// assert_eq!(decode_date(17365), Some((1987, 365)));
+/// assert_eq!(decode_date(0), None);
+/// ```
+pub fn decode_date(w: u16) -> Option<(u16, u16)> {
+    let day = w % 1000;
+    (1..=366).contains(&day).then_some((1970 + w / 1000, day))
+}
+
+/// The header record at the start of each file on a DOS-11 magtape: the file name, owner, protection, and
+/// creation date.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct TapeHeader {
+    /// The file name: 2 words of name and 1 of extension.
+    pub name: [u16; 3],
+    /// The owner.
+    pub uic: Uic,
+    /// The protection code.
+    pub protection: u16,
+    /// The raw creation date (see [`decode_date`]).
+    pub date_word: u16,
+}
+
+impl TapeHeader {
+    /// How many bytes of the header record are used.
+    pub const SIZE: usize = 14;
+
+    /// Parse a header record. Returns `None` if it's shorter than [`TapeHeader::SIZE`], or if the name is blank.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::dos11::{TapeHeader, Uic};
+    /// let header = TapeHeader::from_bytes(&[0x78, 0x65, 0, 0, 0xa1, 0x4b, 4, 1, 0xe9, 0, 0x6b, 0x20, 0, 0]).unwrap();
+    /// assert_eq!(header.filename(), "PIP.LDA");
+    /// assert_eq!(header.uic, Uic { group: 1, member: 4 });
+    /// assert_eq!(header.date(), Some((1978, 299)));
+    /// ```
+    pub fn from_bytes(record: &[u8]) -> Option<TapeHeader> {
+        let w: Vec<u16> = record.get(..TapeHeader::SIZE)?.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])).collect();
+        if w[..3] == [0; 3] { return None }
+        Some(TapeHeader { name: [w[0], w[1], w[2]], uic: Uic::from(w[3]), protection: w[4], date_word: w[5] })
+    }
+
+    /// The file name as `NAME.EXT`, with the padding trimmed (see [`decode_filename`]).
+    pub fn filename(&self) -> String { decode_filename(&self.name) }
+
+    /// The creation date as a `(year, day of the year)` pair (see [`decode_date`]).
+    pub fn date(&self) -> Option<(u16, u16)> { decode_date(self.date_word) }
+}
+
+/// A file on a DOS-11 magtape.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct TapeFile<'a> {
+    pub header: TapeHeader,
+    /// The file's data records.
+    pub records: Vec<&'a [u8]>,
+}
+
+impl<'a> TapeFile<'a> {
+    /// Make a file from the records between two tape marks, the first of which should be the header. Returns
+    /// `None` if it isn't one (see [`TapeHeader::from_bytes`]). This is for tapes that have been split into records
+    /// some other way than [`tape_files`].
+    pub fn from_records(records: &[&'a [u8]]) -> Option<TapeFile<'a>> {
+        let (header, data) = records.split_first()?;
+        Some(TapeFile { header: TapeHeader::from_bytes(header)?, records: data.to_vec() })
+    }
+}
+
+/// List the files on a DOS-11 magtape, from a [SIMH `.TAP` image](crate::tap). Tape files that don't start with a
+/// header record are skipped.
+///
+/// # Examples
+/// ```
+/// # use radix50::{dos11, tap};
+/// let mut image = Vec::new();
+/// tap::write_file([&[0x78, 0x65, 0, 0, 0xa1, 0x4b, 4, 1, 0xe9, 0, 0x6b, 0x20, 0, 0][..], &[0; 512]], &mut image);
+/// tap::write_tape_mark(&mut image);
+/// for file in dos11::tape_files(&image)? {
+///     let (year, day) = file.header.date().unwrap_or_default();
+///     println!("{:10} {} {year}/{day} {} blocks", file.header.filename(), file.header.uic, file.records.len());
+/// }
+/// # assert_eq!(dos11::tape_files(&image)?[0].header.filename(), "PIP.LDA");
+/// # Ok::<(), tap::TapError>(())
+/// ```
+pub fn tape_files(image: &[u8]) -> Result<Vec<TapeFile<'_>>, TapError> {
+    Ok(tap::files(image)?.iter().filter_map(|records| TapeFile::from_records(records)).collect())
+}
+
+// Parse a `[a,b]` pair of byte sized numbers in `radix`. The brackets are optional.
+pub(crate) fn parse_pair(s: &str, radix: u32) -> Result<[u8; 2], ParseError> {
+    let bracketed = s.starts_with('[');
+    let mut pair = [None::<u8>; 2];
+    let mut n = 0;
+    for (i, c) in s.chars().enumerate() {
+        let pos = i + 1;
+        match c {
+            '[' if i == 0                                => {},
+            ']' if bracketed && pos == s.chars().count() => {},
+            ',' if n == 0                                => n = 1,
+            _ => {
+                let digit = c.to_digit(radix).ok_or(ParseError::InvalidDigit { char: c, pos })?;
+                pair[n] = Some(u8::try_from(pair[n].unwrap_or(0) as u32 * radix + digit).map_err(|_| ParseError::Overflow)?);
+            },
+        }
+    }
+    if bracketed && !s.ends_with(']') { return Err(ParseError::Empty) }
+    match pair {
+        [Some(a), Some(b)] => Ok([a, b]),
+        _                  => Err(ParseError::Empty),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn uic() {
+        for w in [0, 0o401, 0o177777, 0o1000] {
+            let uic = Uic::from(w);
+            assert_eq!(u16::from(uic), w);
+            assert_eq!(uic.to_string().parse(), Ok(uic));
+        }
+        assert_eq!("[377,377]".parse(), Ok(Uic { group: 0o377, member: 0o377 }));
+        assert_eq!("[1,2".parse::<Uic>(), Err(ParseError::Empty));
+        assert_eq!("[,2]".parse::<Uic>(), Err(ParseError::Empty));
+        assert_eq!("".parse::<Uic>(), Err(ParseError::Empty));
+        assert_eq!("1,2]".parse::<Uic>(), Err(ParseError::InvalidDigit { char: ']', pos: 4 }));
+        assert_eq!("[1,2,3]".parse::<Uic>(), Err(ParseError::InvalidDigit { char: ',', pos: 5 }));
+        assert_eq!("[1, 2]".parse::<Uic>(), Err(ParseError::InvalidDigit { char: ' ', pos: 4 }));
+    }
+
+    #[test]
+    fn tape() {
+        fn header(name: &str, uic: u16, date: u16) -> Vec<u8> {
+            let mut r: Vec<u8> = encode_filename(name).unwrap().iter().flat_map(|w| w.to_le_bytes()).collect();
+            r.extend([uic, 0o233, date, 0].iter().flat_map(|w| w.to_le_bytes()));
+            r
+        }
+        let mut image = Vec::new();
+        tap::write_file([header("A.MAC", 0o401, 9001), vec![1; 512], vec![2; 512]], &mut image);
+        tap::write_file([vec![0; 14]], &mut image); // not a header
+        tap::write_file([header("B", 0o20003, 0)], &mut image);
+        tap::write_tape_mark(&mut image);
+        tap::write_file([header("UNSEEN.MAC", 0, 0)], &mut image);
+
+        let files = tape_files(&image).unwrap();
+        assert_eq!(files.iter().map(|f| (f.header.filename(), f.header.uic.to_string(), f.header.date(), f.records.len()))
+                        .collect::<Vec<_>>(),
+                   [("A.MAC".into(), "[1,1]".into(), Some((1979, 1)), 2), ("B".into(), "[40,3]".into(), None, 0)]);
+        assert_eq!(files[0].header.protection, 0o233);
+        assert_eq!(TapeHeader::from_bytes(&header("A", 0, 0)[..13]), None);
+        assert_eq!(TapeFile::from_records(&[]), None);
+    }
+}
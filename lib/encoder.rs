@@ -0,0 +1,155 @@
+// Incremental RADIX-50 encoder that can be fed from iterators
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+use crate::{Codec, DefaultCodec, Error};
+
+/// Encodes characters as they arrive, so encoded words can be built straight from an iterator pipeline.
+///
+/// It implements [`FromIterator<char>`] and [`Extend<char>`]. Encoding errors don't interrupt the pipeline: the
+/// first one is remembered and returned by [`finish`](Encoder::finish), which makes the end of the pipeline
+/// `?`-friendly. The words are exactly what [`Codec::encode`] would produce for the same characters.
+///
+/// # Examples
+/// ```
+/// # #[cfg(all(feature = "pdp10", feature = "pdp11"))] {
+/// # use radix50::{Encoder, Error, pdp10::Pdp10};
+/// let words = "this is a test".chars().map(|c| c.to_ascii_uppercase()).collect::<Encoder>().finish();
+/// assert_eq!(words, Ok(vec![32329, 30409, 30401, 805, 31200]));
+///
+/// let mut encoder = Encoder::<Pdp10>::new();
+/// encoder.extend("FOO".chars());
+/// encoder.extend(['B', 'A', 'R', '_']);
+/// assert_eq!(encoder.finish(), Err(Error::IllegalChar { char: '_', pos: 7 }));
+/// # }
+/// ```
+pub struct Encoder<C: Codec = DefaultCodec> {
+    words: Vec<C::Word>,
+    pending: u64,
+    pending_chars: usize,
+    pos: usize,
+    error: Option<Error>,
+    codec: PhantomData<C>,
+}
+
+impl<C: Codec> Encoder<C> {
+    /// Create an empty encoder.
+    pub fn new() -> Self {
+        Encoder { words: vec![], pending: 0, pending_chars: 0, pos: 0, error: None, codec: PhantomData }
+    }
+
+    /// Encode one character. If it isn't in the character set then it's skipped (and the first such character is
+    /// reported by [`finish`](Encoder::finish)).
+    pub fn push(&mut self, c: char) {
+        self.pos += 1;
+        let Some(code) = C::char_to_code(c) else {
+            self.error.get_or_insert(Error::IllegalChar { char: c, pos: self.pos });
+            return
+        };
+        self.pending = self.pending * 40 + code as u64;
+        self.pending_chars += 1;
+        if self.pending_chars == C::CHARS {
+            self.flush();
+        }
+    }
+
+    /// The number of characters pushed so far.
+    pub fn len(&self) -> usize { self.pos }
+
+    /// Whether no characters have been pushed.
+    pub fn is_empty(&self) -> bool { self.pos == 0 }
+
+    /// Space pad the last word and return the words, or the first error. Like [`Codec::encode`], it's an
+    /// [`Error::IllegalChar`] if the padding is needed and the codec has no space.
+    pub fn finish(mut self) -> Result<Vec<C::Word>, Error> {
+        if let Some(error) = self.error { return Err(error) }
+        if self.pending_chars > 0 {
+            // Not necessarily code 0, so pad with the codec's own space
+            for _ in self.pending_chars..C::CHARS { self.push(' ') }
+            if let Some(error) = self.error { return Err(error) }
+        }
+        Ok(self.words)
+    }
+
+    fn flush(&mut self) {
+        self.words.push(C::Word::try_from(self.pending).unwrap_or_else(|_| panic!("{} RADIX-50 characters don't fit in a {}",
+                                                                                    C::CHARS, core::any::type_name::<C::Word>())));
+        self.pending = 0;
+        self.pending_chars = 0;
+    }
+}
+
+impl<C: Codec> Default for Encoder<C> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<C: Codec> Extend<char> for Encoder<C> {
+    fn extend<T: IntoIterator<Item = char>>(&mut self, iter: T) {
+        for c in iter { self.push(c) }
+    }
+}
+
+impl<C: Codec> FromIterator<char> for Encoder<C> {
+    fn from_iter<T: IntoIterator<Item = char>>(iter: T) -> Self {
+        let mut encoder = Self::new();
+        encoder.extend(iter);
+        encoder
+    }
+}
+
+#[cfg(all(test, feature = "pdp11"))]
+mod tests {
+    use super::*;
+    use crate::pdp11::Pdp11;
+    #[cfg(feature = "pdp10")]
+    use crate::{pdp10::{self, Pdp10}, pdp11, testdata};
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn matches_encode() {
+        for golden in testdata::CORPUS {
+            assert_eq!(golden.text.chars().collect::<Encoder<Pdp10>>().finish().unwrap(), golden.pdp10);
+            assert_eq!(golden.text.chars().collect::<Encoder<Pdp11>>().finish().unwrap(), golden.pdp11);
+        }
+        for s in ["", "A", "AB", "ABC", "ABCD", "A_B", "AB%_", "ÀBC"] {
+            assert_eq!(s.chars().collect::<Encoder<Pdp10>>().finish(), pdp10::encode(s));
+            assert_eq!(s.chars().collect::<Encoder<Pdp11>>().finish(), pdp11::encode(s));
+        }
+    }
+
+    #[test]
+    fn padding() {
+        // Space isn't 0 here, so the padding has to be the codec's own space code
+        struct Swapped;
+        impl Codec for Swapped {
+            type Word = u16;
+            const CHARS: usize = 3;
+            const DECODE: [char; 40] = { let mut t = crate::pdp11::RADIX50_DECODE; t.swap(0, 1); t };
+        }
+        // And here there's no space to pad with at all
+        struct NoSpace;
+        impl Codec for NoSpace {
+            type Word = u16;
+            const CHARS: usize = 3;
+            const DECODE: [char; 40] = { let mut t = crate::pdp11::RADIX50_DECODE; t[0] = '_'; t };
+        }
+        for s in ["", "A", "AB", "ABC", "ABCD", "B A"] {
+            assert_eq!(s.chars().collect::<Encoder<Swapped>>().finish(), Swapped::encode(s));
+            assert_eq!(s.chars().collect::<Encoder<NoSpace>>().finish(), NoSpace::encode(s.trim()));
+        }
+        assert_eq!("AB".chars().collect::<Encoder<Swapped>>().finish(), Ok(vec![81])); // A=0, B=2, space=1
+        assert_eq!("AB".chars().collect::<Encoder<NoSpace>>().finish(), Err(Error::IllegalChar { char: ' ', pos: 3 }));
+    }
+
+    #[test]
+    fn first_error() {
+        let encoder: Encoder = "A_B!".chars().collect();
+        assert_eq!(encoder.len(), 4);
+        assert_eq!(encoder.finish(), Err(Error::IllegalChar { char: '_', pos: 2 }));
+        assert!(Encoder::<Pdp11>::default().is_empty());
+    }
+}
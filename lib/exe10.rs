@@ -0,0 +1,209 @@
+// TOPS-10/TOPS-20 .EXE files
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Reading the symbol tables of TOPS-10 and TOPS-20 saved programs (`.EXE` files).
+//!
+//! An `.EXE` file starts with a directory that maps the file's 512 word pages onto the program's address space.
+//! If the program was saved with its symbols, the job data area word `.JBSYM` (address 0o116) points at them: its
+//! left half is the negative length and its right half the address of a table of 2 word entries, a [`symbol10`]
+//! name and a value, the same layout as a [`.REL` symbol block](crate::rel10::symbols).
+//!
+//! Words are `u64`s (only the low 36 bits are used).
+//!
+//! [`symbol10`]: crate::symbol10
+//!
+//! # Examples
+//! ```
+//! # use radix50::{symbol10, exe10::Exe};
+//! # let mut words = vec![0; 3 * 512];
+//! # words[..5].copy_from_slice(&[0o1776000003, 0o1, 0o1000000000, 0o1777000001, 0]);
+//! # words[512 + 0o116] = 0o777776_001000;
+//! # words[1024..1026].copy_from_slice(&[symbol10::encode_symbol("START", 1).unwrap(), 0o140]);
+//! let exe = Exe::parse(&words)?;
+//! for sym in exe.symbols()? {
+//!     println!("{:6} {:?} {:012o}", sym.name, sym.kind(), sym.value);
+//! }
+//! # assert_eq!(exe.symbols()?[0].name, "START");
+//! # Ok::<(), radix50::exe10::ExeError>(())
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{ddt, rel10::{self, Symbol}};
+
+/// How many words are in a page.
+pub const PAGE_WORDS: usize = 512;
+
+/// The address of `.JBSYM`, the job data area word that points to the symbol table.
+pub const JBSYM: u32 = 0o116;
+
+// Block types in the header
+const DIRECTORY: u64 = 0o1776;
+const END:       u64 = 0o1777;
+
+/// One directory entry: a run of pages in the file mapped into the address space.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct PageMap {
+    /// The access bits (high segment, sharable, writable, etc.): the top 9 bits of the entry's first word.
+    pub flags: u16,
+    /// The first page in the file, or 0 if the pages aren't stored in the file (they're allocated as zeros).
+    pub file_page: u32,
+    /// The first page in the address space.
+    pub process_page: u32,
+    /// How many pages there are.
+    pub count: u32,
+}
+
+/// Errors from reading `.EXE` files.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+#[non_exhaustive]
+pub enum ExeError {
+    /// The file doesn't start with a directory.
+    NotExe,
+    /// The block at word `offset` of the header runs past the end of the first page, or has no length.
+    BadHeader { offset: usize },
+    /// The file is too short for a page its directory maps.
+    Truncated { page: u32 },
+    /// The program didn't save a symbol table (`.JBSYM` is 0).
+    NoSymbols,
+    /// An address isn't in the program's address space.
+    Unmapped { address: u32 },
+}
+
+impl core::error::Error for ExeError {
+}
+
+impl fmt::Display for ExeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExeError::NotExe               => write!(f, "Not an .EXE file"),
+            ExeError::BadHeader { offset } => write!(f, "Bad header block at word {}", offset),
+            ExeError::Truncated { page }   => write!(f, "File page {:o} is missing", page),
+            ExeError::NoSymbols            => write!(f, "No symbol table"),
+            ExeError::Unmapped { address } => write!(f, "Address {:o} isn't mapped", address),
+        }
+    }
+}
+
+/// An `.EXE` file and its directory.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Exe<'a> {
+    words: &'a [u64],
+    /// The directory entries, in file order.
+    pub pages: Vec<PageMap>,
+}
+
+impl<'a> Exe<'a> {
+    /// Read the directory from the header blocks at the start of `words` (the whole file). Header blocks other
+    /// than the directory (the entry vector, for example) are skipped.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::exe10::{Exe, ExeError, PageMap};
+    /// let words = [0o1776000003, 0o400000_000001, 0o003000_000400, 0o1777000001];
+    /// let exe = Exe::parse(&words).unwrap();
+    /// assert_eq!(exe.pages, [PageMap { flags: 0o400, file_page: 1, process_page: 0o400, count: 4 }]);
+    /// assert_eq!(Exe::parse(&[0; 512]), Err(ExeError::NotExe));
+    /// ```
+    pub fn parse(words: &'a [u64]) -> Result<Exe<'a>, ExeError> {
+        if words.first().map(|w| w >> 18 & 0o777777) != Some(DIRECTORY) { return Err(ExeError::NotExe) }
+        let header = &words[..words.len().min(PAGE_WORDS)];
+        let mut pages = Vec::new();
+        let mut at = 0;
+        while let Some(&w) = header.get(at) {
+            let (typ, len) = (w >> 18 & 0o777777, (w & 0o777777) as usize);
+            if len == 0 || at + len > header.len() { return Err(ExeError::BadHeader { offset: at }) }
+            match typ {
+                END       => break,
+                DIRECTORY => pages.extend(header[at + 1..at + len].chunks_exact(2).map(|pair| PageMap {
+                    flags: (pair[0] >> 27 & 0o777) as u16,
+                    file_page: (pair[0] & 0o777777777) as u32,
+                    process_page: (pair[1] & 0o777777777) as u32,
+                    count: (pair[1] >> 27 & 0o777) as u32 + 1,
+                })),
+                _         => {},
+            }
+            at += len;
+        }
+        Ok(Exe { words, pages })
+    }
+
+    /// Read the word at `address` in the program's address space.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::exe10::{Exe, ExeError};
+    /// let mut words = vec![0; 1024];
+    /// words[..4].copy_from_slice(&[0o1776000003, 0o1, 0o1000000000, 0o1777000001]);
+    /// words[512 + 0o20] = 0o254000_001000;
+    /// let exe = Exe::parse(&words).unwrap();
+    /// assert_eq!(exe.read(0o20), Ok(0o254000_001000));
+    /// assert_eq!(exe.read(0o1000), Err(ExeError::Truncated { page: 2 }));
+    /// assert_eq!(exe.read(0o2000), Err(ExeError::Unmapped { address: 0o2000 }));
+    /// ```
+    pub fn read(&self, address: u32) -> Result<u64, ExeError> {
+        let page = address / PAGE_WORDS as u32;
+        let map = self.pages.iter().find(|m| (m.process_page..m.process_page + m.count).contains(&page))
+                                   .ok_or(ExeError::Unmapped { address })?;
+        if map.file_page == 0 { return Ok(0) }
+        let file_page = map.file_page + (page - map.process_page);
+        let at = file_page as usize * PAGE_WORDS + address as usize % PAGE_WORDS;
+        self.words.get(at).copied().ok_or(ExeError::Truncated { page: file_page })
+    }
+
+    /// Read the raw symbol table that `.JBSYM` points to. [`ddt::Table::parse`] can decode it.
+    pub fn symbol_table(&self) -> Result<Vec<u64>, ExeError> {
+        let (start, len) = ddt::pointer(self.read(JBSYM)?).ok_or(ExeError::NoSymbols)?;
+        (start..start + len as u32).map(|address| self.read(address)).collect()
+    }
+
+    /// Read and decode the symbol table that `.JBSYM` points to (see [`rel10::symbols`]).
+    pub fn symbols(&self) -> Result<Vec<Symbol>, ExeError> {
+        Ok(rel10::symbols(&self.symbol_table()?).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rel10::SymbolKind, symbol10};
+    use alloc::vec;
+
+    // Pages 0-1 of memory are in file pages 1-2, page 2 is allocated as zeros, and the symbol table straddles
+    // pages 0 and 1.
+    fn exe() -> Vec<u64> {
+        let mut words = vec![0; 3 * PAGE_WORDS];
+        words[..9].copy_from_slice(&[0o1776000005, 0o1, 0o1000000000, 0, 0o2,
+                                     0o1775000003, 1, 0o140, // entry vector
+                                     0o1777000001]);
+        words[PAGE_WORDS + JBSYM as usize] = 0o777772_000776;
+        let syms = [("PROG", 0, 0), ("LOOP", 2, 0o142), ("START", 1, 0o140)];
+        for (n, (name, flags, value)) in syms.into_iter().enumerate() {
+            let at = PAGE_WORDS + 0o776 + n * 2;
+            words[at..at + 2].copy_from_slice(&[symbol10::encode_symbol(name, flags).unwrap(), value]);
+        }
+        words
+    }
+
+    #[test]
+    fn symbols() {
+        let words = exe();
+        let exe = Exe::parse(&words).unwrap();
+        assert_eq!(exe.pages, [PageMap { flags: 0, file_page: 1, process_page: 0, count: 2 },
+                               PageMap { flags: 0, file_page: 0, process_page: 2, count: 1 }]);
+        assert_eq!(exe.read(0o2001), Ok(0));
+        let syms = exe.symbols().unwrap();
+        assert_eq!(syms.iter().map(|s| (s.name.as_str(), s.kind(), s.value)).collect::<Vec<_>>(),
+                   [("PROG", SymbolKind::ProgramName, 0), ("LOOP", SymbolKind::LocalDefinition, 0o142),
+                    ("START", SymbolKind::GlobalDefinition, 0o140)]);
+
+        let mut words = words;
+        words[PAGE_WORDS + JBSYM as usize] = 0;
+        assert_eq!(Exe::parse(&words).unwrap().symbols(), Err(ExeError::NoSymbols));
+        words[5] = 0o1775000000;
+        assert_eq!(Exe::parse(&words), Err(ExeError::BadHeader { offset: 5 }));
+    }
+}
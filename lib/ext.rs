@@ -0,0 +1,85 @@
+// Extension traits so strings and word slices can be converted with method calls
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{Codec, Error};
+#[cfg(feature = "pdp10")]
+use crate::pdp10::Pdp10;
+#[cfg(feature = "pdp11")]
+use crate::pdp11::Pdp11;
+
+/// Encode RADIX-50 with a method call on a string.
+///
+/// # Examples
+/// ```
+/// # #[cfg(all(feature = "pdp10", feature = "pdp11"))] {
+/// use radix50::ToRadix50;
+/// assert_eq!("THIS IS A TEST".to_radix50_pdp11(), Ok(vec![32329, 30409, 30401, 805, 31200]));
+/// assert_eq!("THIS IS A TEST".to_string().to_radix50_pdp10(), Ok(vec![3119342419, 2970305215, 3046400000]));
+/// assert!("this".to_radix50_pdp11().is_err());
+/// # }
+/// ```
+pub trait ToRadix50 {
+    /// Encode with any [`Codec`].
+    fn to_radix50<C: Codec>(&self) -> Result<Vec<C::Word>, Error>;
+    /// Encode with the [PDP-10 encoding](crate::pdp10::encode).
+    #[cfg(feature = "pdp10")]
+    fn to_radix50_pdp10(&self) -> Result<Vec<u32>, Error> { self.to_radix50::<Pdp10>() }
+    /// Encode with the [PDP-11 encoding](crate::pdp11::encode).
+    #[cfg(feature = "pdp11")]
+    fn to_radix50_pdp11(&self) -> Result<Vec<u16>, Error> { self.to_radix50::<Pdp11>() }
+}
+
+impl ToRadix50 for str {
+    fn to_radix50<C: Codec>(&self) -> Result<Vec<C::Word>, Error> { C::encode(self) }
+}
+
+/// Decode RADIX-50 with a method call on a slice (or `Vec` or array) of words.
+///
+/// The shortcut methods only exist for the matching word type: `from_radix50_pdp11` on `[u16]` and
+/// `from_radix50_pdp10` on `[u32]`.
+///
+/// # Examples
+/// ```
+/// # #[cfg(all(feature = "pdp10", feature = "pdp11"))] {
+/// use radix50::FromRadix50;
+/// assert_eq!([32329, 30409, 30401, 805, 31200].from_radix50_pdp11(), "THIS IS A TEST ");
+/// assert_eq!(vec![3119342419, 2970305215, 3046400000].from_radix50_pdp10(), "THIS IS A TEST    ");
+/// # }
+/// ```
+#[allow(clippy::wrong_self_convention)] // `from_radix50` reads naturally as "decode from RADIX-50"
+pub trait FromRadix50<W> {
+    /// Decode with any [`Codec`] that uses this word type.
+    fn from_radix50<C: Codec<Word = W>>(&self) -> String;
+    /// Decode with the [PDP-10 encoding](crate::pdp10::decode).
+    #[cfg(feature = "pdp10")]
+    fn from_radix50_pdp10(&self) -> String where Pdp10: Codec<Word = W> { self.from_radix50::<Pdp10>() }
+    /// Decode with the [PDP-11 encoding](crate::pdp11::decode).
+    #[cfg(feature = "pdp11")]
+    fn from_radix50_pdp11(&self) -> String where Pdp11: Codec<Word = W> { self.from_radix50::<Pdp11>() }
+}
+
+impl<W: Copy> FromRadix50<W> for [W] {
+    fn from_radix50<C: Codec<Word = W>>(&self) -> String { C::decode(self) }
+}
+
+#[cfg(all(test, feature = "pdp10", feature = "pdp11"))]
+mod tests {
+    use super::*;
+    use crate::{pdp10, pdp11, testdata};
+
+    #[test]
+    fn round_trip() {
+        for golden in testdata::CORPUS {
+            assert_eq!(golden.text.to_radix50_pdp10().unwrap(), golden.pdp10);
+            assert_eq!(golden.text.to_radix50_pdp11().unwrap(), golden.pdp11);
+            assert_eq!(golden.pdp10.from_radix50_pdp10(), pdp10::decode(golden.pdp10));
+            assert_eq!(golden.pdp11.from_radix50_pdp11(), pdp11::decode(golden.pdp11));
+            assert_eq!(golden.pdp11.from_radix50::<Pdp11>(), golden.text.to_radix50::<Pdp11>().unwrap().from_radix50_pdp11());
+        }
+        assert_eq!("A_".to_radix50_pdp10(), Err(Error::IllegalChar { char: '_', pos: 2 }));
+    }
+}
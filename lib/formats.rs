@@ -0,0 +1,215 @@
+// Recognizing file formats
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Recognizing the file formats this crate reads, so a tool can hand a file to the right parser without being
+//! told what it is.
+//!
+//! [`detect`] only looks at signatures (headers, checksums, and record framing), so it's quick, but it can be
+//! fooled: a file it recognizes can still fail to parse.
+//!
+//! # Examples
+//! ```
+//! # use radix50::{formats::{self, Kind}, obj11::{self, GsdEntry, GsdKind}, stb::SymbolTable};
+//! # let mut bytes = Vec::new();
+//! # obj11::write_gsd(&[GsdEntry::new("MAIN", GsdKind::ModuleName, 0, 0).unwrap()], &mut bytes);
+//! match formats::detect(&bytes) {
+//!     Some(Kind::Obj | Kind::Stb) => println!("{:?}", SymbolTable::parse(&bytes)?),
+//!     Some(kind)                  => println!("A {:?} file", kind),
+//!     None                        => println!("Unknown"),
+//! }
+//! # assert_eq!(formats::detect(&bytes), Some(Kind::Obj));
+//! # Ok::<(), radix50::obj11::ObjError>(())
+//! ```
+
+use crate::{bytes::Packing, obj11::{self, RecordType}, rel10::block_type, rt11, tap};
+
+/// The kinds of file [`detect`] recognizes.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+#[non_exhaustive]
+pub enum Kind {
+    /// A PDP-11 object module or library in formatted binary (the way RT-11 and paper tapes store them). See
+    /// [`obj11`](crate::obj11).
+    Obj,
+    /// A PDP-11 object module in RSX-11 variable length records. Task builder symbol table files are stored this
+    /// way (and so are RSX-11 object files). See [`stb`](crate::stb).
+    Stb,
+    /// An RSX-11 task image. See [`tsk`](crate::tsk).
+    Tsk,
+    /// A PDP-10 relocatable binary (`.REL`) file, core-dump packed. See [`rel10`](crate::rel10).
+    Rel,
+    /// A TOPS-10/TOPS-20 saved program (`.EXE`) file, core-dump packed. See [`exe10`](crate::exe10).
+    Exe,
+    /// A SIMH tape image. See [`tap`](crate::tap).
+    Tap,
+    /// An RT-11 disk (or DECtape) image. See [`rt11`](crate::rt11).
+    Rt11,
+}
+
+/// Recognize the format of a file from its contents. Returns `None` if it doesn't look like anything.
+///
+/// # Examples
+/// ```
+/// # use radix50::{formats::{self, Kind}, tap};
+/// let mut image = Vec::new();
+/// tap::write_file([b"HDR1"], &mut image);
+/// assert_eq!(formats::detect(&image), Some(Kind::Tap));
+/// assert_eq!(formats::detect(b"Just some text"), None);
+/// ```
+pub fn detect(bytes: &[u8]) -> Option<Kind> {
+    // The signatures at the start of the file go first. A disk image is recognized by what's in block 1 or 6, which
+    // a big enough file of another kind could have by chance.
+    [(Kind::Tap, is_tap as fn(&[u8]) -> bool), (Kind::Exe, is_exe), (Kind::Rel, is_rel), (Kind::Obj, is_obj),
+     (Kind::Stb, is_stb), (Kind::Tsk, is_tsk), (Kind::Rt11, is_rt11)]
+        .into_iter().find(|(_, is)| is(bytes)).map(|(kind, _)| kind)
+}
+
+// A whole good record (with matching lengths before and after) to start with
+fn is_tap(bytes: &[u8]) -> bool {
+    matches!(tap::items(bytes).next(), Some(Ok(tap::Item::Record(_))))
+}
+
+// The system ID in the home block, or a first directory segment with a sensible header whose entries all have
+// exactly one kind (tentative, empty, or permanent), up to an end of segment marker
+fn is_rt11(bytes: &[u8]) -> bool {
+    use rt11::status::{EMPTY, END_OF_SEGMENT, PERMANENT, TENTATIVE};
+    const SYSTEM_ID: usize = rt11::BLOCK_SIZE + 0o760;
+    const SEGMENT: usize = 6 * rt11::BLOCK_SIZE;
+    if bytes.get(SYSTEM_ID..SYSTEM_ID + 8) == Some(b"DECRT11A") { return true }
+    let Some(segment) = bytes.get(SEGMENT..SEGMENT + 2 * rt11::BLOCK_SIZE) else { return false };
+    let word = |n: usize| u16::from_le_bytes([segment[n * 2], segment[n * 2 + 1]]);
+    let [total, next, highest, extra_bytes, start] = [0, 1, 2, 3, 4].map(word);
+    let header = (1..=31).contains(&total) && next <= total && (1..=total).contains(&highest)
+                 && extra_bytes.is_multiple_of(2) && extra_bytes < 0o100 && start >= 6 + 2 * total;
+    if !header { return false }
+    for status in (5..segment.len() / 2).step_by(7 + extra_bytes as usize / 2).map(word) {
+        if status & END_OF_SEGMENT != 0 { return true }
+        if (status & (TENTATIVE | EMPTY | PERMANENT)).count_ones() != 1 { return false }
+    }
+    false
+}
+
+// The block type of an `.EXE` file's directory
+const EXE_DIRECTORY: u64 = 0o1776;
+
+fn first_word36(bytes: &[u8]) -> Option<u64> {
+    let mut words = alloc::vec::Vec::new();
+    Packing::CoreDump.unpack(bytes.get(..5)?, &mut words);
+    words.first().copied()
+}
+
+// The directory header block
+fn is_exe(bytes: &[u8]) -> bool {
+    first_word36(bytes).is_some_and(|w| w >> 18 == EXE_DIRECTORY && (w & 0o777777) > 1)
+}
+
+// A name or entry block, which is how the compilers and MACRO-10 start a module
+fn is_rel(bytes: &[u8]) -> bool {
+    first_word36(bytes).is_some_and(|w| matches!((w >> 18) as u32, block_type::NAME | block_type::ENTRY)
+                                        && (1..=0o777).contains(&(w & 0o777777)))
+}
+
+// A GSD record (or a library header) with a good checksum
+fn is_obj(bytes: &[u8]) -> bool {
+    match obj11::records(bytes).next() {
+        Some(Ok(&[lo, hi, ref rest @ ..])) => match RecordType::from(u16::from_le_bytes([lo, hi])) {
+            RecordType::Gsd       => rest.len().is_multiple_of(8),
+            RecordType::LibHeader => true,
+            _                     => false,
+        },
+        _ => false,
+    }
+}
+
+// A first variable length record that's a whole number of GSD entries
+fn is_stb(bytes: &[u8]) -> bool {
+    matches!(obj11::variable_records(bytes).next(), Some(Ok(&[1, 0, ref rest @ ..])) if !rest.is_empty() && rest.len().is_multiple_of(8))
+}
+
+// A label block with a valid task and partition name, and a header after the label blocks
+fn is_tsk(bytes: &[u8]) -> bool {
+    use crate::tsk::{BLOCK_SIZE, Label};
+    if bytes.len() < 2 * BLOCK_SIZE || !bytes.len().is_multiple_of(BLOCK_SIZE) { return false }
+    let label = Label::parse(bytes[..BLOCK_SIZE].try_into().unwrap());
+    let rad50 = |words: [u16; 2]| words != [0, 0] && words.iter().all(|&w| w < 0o175000);
+    rad50(label.task.name.words()) && rad50(label.partition.words())
+        && label.label_blocks >= 1 && label.header_block >= label.label_blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{obj11::{GsdEntry, GsdKind}, symbol10, Symbol6};
+    use alloc::{vec, vec::Vec};
+
+    fn gsd() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        obj11::write_gsd(&[GsdEntry::new("MAIN", GsdKind::ModuleName, 0, 0).unwrap(),
+                           GsdEntry::new("START", GsdKind::GlobalSymbol, obj11::flags::GLOBAL_DEFINITION, 0o1000).unwrap()], &mut bytes);
+        bytes
+    }
+
+    fn core_dump(words: &[u64]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        Packing::CoreDump.pack(words, &mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn kinds() {
+        let obj = gsd();
+        assert_eq!(detect(&obj), Some(Kind::Obj));
+        let mut stb = Vec::new();
+        for record in obj11::records(&obj).map(Result::unwrap) {
+            stb.extend((record.len() as u16).to_le_bytes());
+            stb.extend(record);
+            if record.len() % 2 == 1 { stb.push(0) }
+        }
+        assert_eq!(detect(&stb), Some(Kind::Stb));
+
+        let mut tape = Vec::new();
+        tap::write_file([&obj], &mut tape);
+        assert_eq!(detect(&tape), Some(Kind::Tap));
+
+        let mut disk = vec![0; 10 * rt11::BLOCK_SIZE];
+        assert_eq!(detect(&disk), None);
+        disk[rt11::BLOCK_SIZE + 0o760..][..12].copy_from_slice(b"DECRT11A    ");
+        assert_eq!(detect(&disk), Some(Kind::Rt11));
+        let mut disk = vec![0; 10 * rt11::BLOCK_SIZE];
+        disk[6 * rt11::BLOCK_SIZE..][..10].copy_from_slice(&[4, 0, 0, 0, 1, 0, 0, 0, 14, 0]);
+        assert_eq!(detect(&disk), None); // A plausible header, but no entries
+        let status = |s: u16| s.to_le_bytes();
+        disk[6 * rt11::BLOCK_SIZE + 10..][..2].copy_from_slice(&status(rt11::status::EMPTY));
+        disk[6 * rt11::BLOCK_SIZE + 24..][..2].copy_from_slice(&status(rt11::status::END_OF_SEGMENT));
+        assert_eq!(detect(&disk), Some(Kind::Rt11));
+        disk[6 * rt11::BLOCK_SIZE + 10..][..2].copy_from_slice(&status(rt11::status::EMPTY | rt11::status::PERMANENT));
+        assert_eq!(detect(&disk), None);
+
+        // A big object file can have something that looks like a directory segment in block 6
+        let mut big_obj = gsd();
+        big_obj.resize(10 * rt11::BLOCK_SIZE, 0);
+        big_obj[6 * rt11::BLOCK_SIZE..].copy_from_slice(&disk[6 * rt11::BLOCK_SIZE..]);
+        big_obj[6 * rt11::BLOCK_SIZE + 10..][..2].copy_from_slice(&status(rt11::status::PERMANENT));
+        assert_eq!(detect(&big_obj[..]), Some(Kind::Obj));
+        big_obj[..gsd().len()].fill(0);
+        assert_eq!(detect(&big_obj), Some(Kind::Rt11));
+
+        assert_eq!(detect(&core_dump(&[0o1776_000003, 1, 0o1000_000000, 0o1777_000001])), Some(Kind::Exe));
+        let name = symbol10::encode_symbol("MAIN", 0).unwrap();
+        assert_eq!(detect(&core_dump(&[0o6_000001, 0, name])), Some(Kind::Rel));
+        assert_eq!(detect(&core_dump(&[0o4_000000])), None);
+
+        let mut task = vec![0; 4 * 512];
+        let name = |s: &str| Symbol6::encode(s).unwrap().words().map(u16::to_le_bytes).concat();
+        task[0..4].copy_from_slice(&name("PIP"));
+        task[4..8].copy_from_slice(&name("GEN"));
+        task[0o452..0o456].copy_from_slice(&[2, 0, 2, 0]);
+        assert_eq!(detect(&task), Some(Kind::Tsk));
+        task[4..8].copy_from_slice(&[0xff, 0xff, 0, 0]);
+        assert_eq!(detect(&task), None);
+        assert_eq!(detect(&task[..512]), None);
+
+        assert_eq!(detect(&[]), None);
+    }
+}
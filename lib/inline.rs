@@ -0,0 +1,85 @@
+// A small fixed capacity string, so short decodes don't allocate
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+/// Decoded text stored inline, in a buffer of `N` bytes.
+///
+/// This is what the `_inline` decode functions return (like [`pdp11::decode_word_inline`](crate::pdp11::decode_word_inline)
+/// and [`Codec::decode_inline`](crate::Codec::decode_inline)). It's `Copy` and never allocates, so symbol heavy
+/// code can decode millions of words without touching the heap. It derefs to [`str`].
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "pdp11")] {
+/// # use radix50::{InlineStr, pdp11};
+/// let s: InlineStr<3> = pdp11::decode_word_inline(1683);
+/// assert_eq!(s, "ABC");
+/// assert_eq!(s.len(), 3);
+/// assert_eq!(format!("[{:>5}]", s), "[  ABC]");
+/// # }
+/// ```
+#[derive(Clone,Copy)]
+pub struct InlineStr<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> InlineStr<N> {
+    /// The text, as a `&str`.
+    pub fn as_str(&self) -> &str { core::str::from_utf8(&self.buf[..self.len]).expect("only built from a str") }
+
+    // Decode into a fresh buffer with `fill`, which returns the part of the buffer it used.
+    pub(crate) fn fill<E>(fill: impl FnOnce(&mut [u8]) -> Result<&str, E>) -> Result<Self, E> {
+        let mut buf = [0; N];
+        let len = fill(&mut buf)?.len();
+        Ok(InlineStr { buf, len })
+    }
+}
+
+impl<const N: usize> Deref for InlineStr<N> {
+    type Target = str;
+    fn deref(&self) -> &str { self.as_str() }
+}
+
+impl<const N: usize> AsRef<str> for InlineStr<N> { fn as_ref(&self) -> &str { self.as_str() } }
+impl<const N: usize> Borrow<str> for InlineStr<N> { fn borrow(&self) -> &str { self.as_str() } }
+
+impl<const N: usize> fmt::Display for InlineStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.pad(self.as_str()) }
+}
+
+impl<const N: usize> fmt::Debug for InlineStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { fmt::Debug::fmt(self.as_str(), f) }
+}
+
+// Not derived because the unused part of the buffer shouldn't count.
+impl<const N: usize> PartialEq       for InlineStr<N> { fn eq(&self, other: &Self) -> bool { self.as_str() == other.as_str() } }
+impl<const N: usize> Eq              for InlineStr<N> {}
+impl<const N: usize> PartialOrd      for InlineStr<N> { fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> { Some(self.cmp(other)) } }
+impl<const N: usize> Ord             for InlineStr<N> { fn cmp(&self, other: &Self) -> core::cmp::Ordering { self.as_str().cmp(other.as_str()) } }
+impl<const N: usize> Hash            for InlineStr<N> { fn hash<H: Hasher>(&self, state: &mut H) { self.as_str().hash(state) } }
+impl<const N: usize> PartialEq<str>  for InlineStr<N> { fn eq(&self, other: &str)  -> bool { self.as_str() == other } }
+impl<const N: usize> PartialEq<&str> for InlineStr<N> { fn eq(&self, other: &&str) -> bool { self.as_str() == *other } }
+
+#[cfg(all(test, feature = "pdp10", feature = "pdp11"))]
+mod tests {
+    use crate::{Codec, Error, pdp10, pdp11::{self, Pdp11}, testdata};
+
+    #[test]
+    fn matches_decode() {
+        for golden in testdata::CORPUS {
+            for &w in golden.pdp11 { assert_eq!(pdp11::decode_word_inline(w), *pdp11::decode_word(w)) }
+            for &w in golden.pdp10 { assert_eq!(pdp10::decode_word_inline(w), *pdp10::decode_word(w)) }
+        }
+        assert_eq!(Pdp11::decode_inline::<6, _>([1683, 31400]).unwrap(), "ABCSY ");
+        assert_eq!(Pdp11::decode_inline::<5, _>([1683, 31400]), Err(Error::InputTooLong { len: 6, max: 5 }));
+        assert_eq!(format!("{:?}", pdp11::decode_word_inline(1683)), r#""ABC""#);
+        assert!(pdp11::decode_word_inline(1683) < pdp11::decode_word_inline(31400));
+    }
+}
@@ -0,0 +1,136 @@
+// PDP-11 absolute loader tapes
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Reading PDP-11 absolute loader images (`.LDA` files and paper tapes).
+//!
+//! An absolute loader tape is a series of formatted binary records (the same framing as [object
+//! files](crate::obj11)). Each record's data is a load address followed by the bytes to load there. A record with
+//! no bytes ends the tape: its address is where to start the program, or an odd number to just halt.
+//!
+//! Once it's loaded, [`search`](crate::search) can find any RADIX-50 tables in the program.
+//!
+//! # Examples
+//! ```
+//! # use radix50::{lda, obj11::ObjError};
+//! # let tape = [0, 0, 1, 0, 10, 0, 0, 2, 0x12, 0x84, 0xa8, 0x15, 0xa0, 0, 1, 0, 6, 0, 0, 2, 0xf7, 0, 0];
+//! for block in lda::blocks(&tape) {
+//!     let block = block?;
+//!     println!("{:06o}: {} bytes", block.address, block.data.len());
+//! }
+//! let program = lda::load(&tape)?;
+//! assert_eq!(program.start, Some(0o1000));
+//! assert_eq!(program.memory[0o1000..0o1004], [0x12, 0x84, 0xa8, 0x15]);
+//! # Ok::<(), ObjError>(())
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::obj11::{self, ObjError};
+
+/// How many bytes of memory an absolute loader can load (64 KiB).
+pub const MEMORY_SIZE: usize = 0x10000;
+
+/// One block from an absolute loader tape.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct Block<'a> {
+    /// Where to load the data (or the start address, for the last block).
+    pub address: u16,
+    pub data: &'a [u8],
+}
+
+impl Block<'_> {
+    /// Whether this is the last block on the tape (it has no data).
+    pub fn is_last(&self) -> bool { self.data.is_empty() }
+
+    /// For the last block, the address to start the program at. Returns `None` if the address is odd (the loader
+    /// halts instead), or if this isn't the last block.
+    pub fn start_address(&self) -> Option<u16> { (self.is_last() && self.address.is_multiple_of(2)).then_some(self.address) }
+}
+
+/// Iterate over the blocks on an absolute loader tape. Leader and trailer (zero bytes) are skipped, checksums
+/// are verified, and iteration stops after the last block or the first error. A record too short to hold an
+/// address is reported as [`ObjError::Truncated`].
+///
+/// # Examples
+/// ```
+/// # use radix50::{lda::{self, Block}, obj11::ObjError};
+/// let tape = [1, 0, 7, 0, 0, 2, 9, 0xed, 1, 0, 6, 0, 1, 0, 0xf8, 1, 0, 6, 0, 0, 0, 0xf9];
+/// let mut blocks = lda::blocks(&tape);
+/// assert_eq!(blocks.next(), Some(Ok(Block { address: 0o1000, data: &[9] })));
+/// assert_eq!(blocks.next(), Some(Ok(Block { address: 1, data: &[] })));
+/// assert_eq!(blocks.next(), None);
+/// assert_eq!(lda::blocks(&[1, 0, 5, 0, 0, 0xfa]).next(), Some(Err(ObjError::Truncated { offset: 0 })));
+/// ```
+pub fn blocks(bytes: &[u8]) -> impl Iterator<Item = Result<Block<'_>, ObjError>> {
+    let mut records = obj11::records(bytes);
+    let mut done = false;
+    core::iter::from_fn(move || {
+        if done { return None }
+        let block = match records.next()? {
+            Ok(&[lo, hi, ref data @ ..]) => Ok(Block { address: u16::from_le_bytes([lo, hi]), data }),
+            // The record's header is the 4 bytes before its data.
+            Ok(record)                   => Err(ObjError::Truncated { offset: record.as_ptr() as usize - bytes.as_ptr() as usize - 4 }),
+            Err(e)                       => Err(e),
+        };
+        done = block.map_or(true, |b| b.is_last());
+        Some(block)
+    })
+}
+
+/// A program loaded from an absolute loader tape.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Program {
+    /// All 64 KiB of memory, with the tape's blocks loaded (and zeros everywhere else).
+    pub memory: Vec<u8>,
+    /// Where to start the program (see [`Block::start_address`]).
+    pub start: Option<u16>,
+}
+
+/// Load an absolute loader tape into memory, the way the absolute loader would. Blocks that run past the top of
+/// memory are cut off there.
+pub fn load(bytes: &[u8]) -> Result<Program, ObjError> {
+    let mut program = Program { memory: vec![0; MEMORY_SIZE], start: None };
+    for block in blocks(bytes) {
+        let block = block?;
+        let at = block.address as usize;
+        let len = block.data.len().min(MEMORY_SIZE - at);
+        program.memory[at..at + len].copy_from_slice(&block.data[..len]);
+        program.start = block.start_address();
+    }
+    Ok(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obj11::RecordType;
+
+    // An absolute loader block is a formatted binary record whose "type" word is the address.
+    fn block(address: u16, data: &[u8], tape: &mut Vec<u8>) { obj11::write_record(RecordType::from(address), data, tape) }
+
+    #[test]
+    fn tape() {
+        let mut tape = vec![0; 10];
+        block(0o1000, &[1, 2, 3], &mut tape);
+        block(0o177776, &[4, 5, 6, 7], &mut tape);
+        block(0o2000, &[], &mut tape);
+        block(0o3000, &[8], &mut tape);
+        let found: Vec<Block> = blocks(&tape).map(Result::unwrap).collect();
+        assert_eq!(found, [Block { address: 0o1000, data: &[1, 2, 3] }, Block { address: 0o177776, data: &[4, 5, 6, 7] },
+                           Block { address: 0o2000, data: &[] }]);
+        assert_eq!(found.iter().map(Block::start_address).collect::<Vec<_>>(), [None, None, Some(0o2000)]);
+
+        let program = load(&tape).unwrap();
+        assert_eq!(program.start, Some(0o2000));
+        assert_eq!(program.memory[0o1000..0o1003], [1, 2, 3]);
+        assert_eq!(program.memory[0o177776..], [4, 5]);
+        assert_eq!(program.memory[0o3000], 0);
+
+        let mut bad = tape.clone();
+        bad[15] ^= 1;
+        assert_eq!(load(&bad), Err(ObjError::Checksum { offset: 10 }));
+        assert_eq!(load(&[]), Ok(Program { memory: vec![0; MEMORY_SIZE], start: None }));
+    }
+}
@@ -0,0 +1,213 @@
+// RADIX-50 literals in MACRO-10 source
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Reading and writing MACRO-10 `^R` RADIX-50 literals, for PDP-10 cross tools that read or generate assembler
+//! source.
+//!
+//! `^R` is followed by a symbol of up to 6 [RADIX-50 characters](crate::pdp10::RADIX50_DECODE), and makes a word
+//! from it the same way [`pdp10::encode_word`] does: the name is left justified, with spaces padding it on the
+//! right. Like the rest of MACRO-10, lower case letters are converted to upper case, and only the first 6
+//! characters of a longer symbol count.
+//!
+//! # Examples
+//! ```
+//! # use radix50::{macro10, pdp10};
+//! assert_eq!(macro10::parse_literal("^RSTART"), Some(pdp10::encode_word("START").unwrap()));
+//! assert_eq!(macro10::format_literal(pdp10::encode_word("START").unwrap()), Some("^RSTART".to_string()));
+//! for literal in macro10::literals("\tMOVE\tT1,[^RPIP]\t; The name\n") {
+//!     println!("{}:{}: {} = {:012o}", literal.line, literal.column, literal.text, literal.word?);
+//! }
+//! # Ok::<(), macro10::MacroError>(())
+//! ```
+
+use alloc::{format, string::String};
+use core::fmt;
+
+use crate::pdp10;
+
+/// A `^R` literal found in MACRO-10 source.
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+pub struct Literal<'a> {
+    /// The line it's on, counting from 1.
+    pub line: usize,
+    /// The column (in characters) it starts at, counting from 1.
+    pub column: usize,
+    /// The source text, including the `^R`.
+    pub text: &'a str,
+    /// The word it assembles to.
+    pub word: Result<u32, MacroError>,
+}
+
+/// Errors from reading `^R` literals.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+#[non_exhaustive]
+pub enum MacroError {
+    /// The `^R` isn't followed by a symbol.
+    Empty,
+}
+
+impl core::error::Error for MacroError {
+}
+
+impl fmt::Display for MacroError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MacroError::Empty => write!(f, "Nothing after ^R"),
+        }
+    }
+}
+
+// Space is in the character set, but it ends a symbol.
+fn is_symbol_char(c: char) -> bool { c != ' ' && pdp10::char_to_code(c.to_ascii_uppercase()).is_some() }
+
+// The length of the `^R` literal at the start of `s` (0 if there isn't one), and its word.
+fn literal(s: &str) -> (usize, Result<u32, MacroError>) {
+    let Some(symbol) = s.strip_prefix("^R").or_else(|| s.strip_prefix("^r")) else { return (0, Err(MacroError::Empty)) };
+    let len = symbol.find(|c| !is_symbol_char(c)).unwrap_or(symbol.len());
+    if len == 0 { return (2, Err(MacroError::Empty)) }
+    let name = symbol[..len.min(pdp10::CHARS_PER_WORD)].to_ascii_uppercase();
+    (2 + len, Ok(pdp10::encode_word(&name).expect("symbol chars are in the character set")))
+}
+
+/// Parse a `^R` literal (`^RSTART`, say) into its word. Returns `None` if `s` isn't a `^R` literal (with nothing
+/// after it).
+///
+/// # Examples
+/// ```
+/// # use radix50::{macro10::parse_literal, pdp10};
+/// assert_eq!(parse_literal("^RPAT"), Some(pdp10::encode_word("PAT   ").unwrap()));
+/// assert_eq!(parse_literal("^rpat.."), Some(pdp10::encode_word("PAT..").unwrap()));
+/// assert_eq!(parse_literal("^RLONGSYMBOL"), Some(pdp10::encode_word("LONGSY").unwrap()));
+/// assert_eq!(parse_literal("^RA+1"), None);
+/// assert_eq!(parse_literal("^R"), None);
+/// ```
+pub fn parse_literal(s: &str) -> Option<u32> {
+    match literal(s) {
+        (len, Ok(word)) if len == s.len() => Some(word),
+        _                                 => None,
+    }
+}
+
+/// Write a word as a `^R` literal, for generating MACRO-10 source.
+///
+/// Returns `None` if a `^R` literal can't make the word: it's not a [valid word](pdp10::is_valid_word), it's blank,
+/// or it has spaces anywhere but the end (since `^R` left justifies, and a space would end the symbol).
+///
+/// # Examples
+/// ```
+/// # use radix50::{macro10::format_literal, pdp10};
+/// assert_eq!(format_literal(pdp10::encode_word("PAT..").unwrap()), Some("^RPAT..".to_string()));
+/// assert_eq!(format_literal(pdp10::encode_word("  PAT").unwrap()), None);
+/// assert_eq!(format_literal(0), None);
+/// ```
+pub fn format_literal(word: u32) -> Option<String> {
+    if !pdp10::is_valid_word(word) { return None }
+    let name = pdp10::decode_word(word);
+    let name = name.trim_end_matches(' ');
+    (!name.is_empty() && !name.contains(' ')).then(|| format!("^R{}", name))
+}
+
+/// Iterate over the `^R` literals in MACRO-10 source.
+///
+/// Comments are skipped, and so are quoted constants (`"AB"` and `'AB'`), the text of `ASCII`, `ASCIZ`, `SIXBIT`,
+/// and `COMMENT` pseudo-ops, and the rest of `TITLE` and `SUBTTL` lines.
+///
+/// # Examples
+/// ```
+/// # use radix50::{macro10::{self, MacroError}, pdp10};
+/// let mut literals = macro10::literals("\tEXP\t^RFOO,^R\n\tASCIZ\t/^RBAR/\t; ^RBAZ\n");
+/// let foo = literals.next().unwrap();
+/// assert_eq!((foo.line, foo.column, foo.text, foo.word), (1, 6, "^RFOO", Ok(pdp10::encode_word("FOO").unwrap())));
+/// assert_eq!(literals.next().unwrap().word, Err(MacroError::Empty));
+/// assert_eq!(literals.next(), None);
+/// ```
+pub fn literals(source: &str) -> impl Iterator<Item = Literal<'_>> {
+    source.lines().enumerate().flat_map(|(n, line)| line_literals(n + 1, line))
+}
+
+fn line_literals(line: usize, text: &str) -> impl Iterator<Item = Literal<'_>> {
+    let mut at = 0;
+    let mut previous = ' ';
+    core::iter::from_fn(move || {
+        while let Some(c) = text[at..].chars().next() {
+            let start = at;
+            at += c.len_utf8();
+            match c {
+                ';' => break,
+                '"' | '\'' => at = text[at..].find(c).map_or(text.len(), |end| at + end + 1),
+                '^' => {
+                    let (len, word) = literal(&text[start..]);
+                    if len == 0 { continue }
+                    at = start + len;
+                    previous = text[..at].chars().next_back().unwrap_or(' ');
+                    return Some(Literal { line, column: text[..start].chars().count() + 1, text: &text[start..at], word });
+                },
+                _ if !is_symbol_char(previous) && is_symbol_char(c) => {
+                    let len = text[start..].find(|c| !is_symbol_char(c)).unwrap_or(text.len() - start);
+                    at = start + len;
+                    match text[start..at].to_ascii_uppercase().as_str() {
+                        "ASCII" | "ASCIZ" | "SIXBIT" | "COMMENT" => {
+                            // The first character after the blanks is the delimiter
+                            let rest = text[at..].trim_start();
+                            at = match rest.chars().next() {
+                                Some(d) => {
+                                    let inside = text.len() - rest.len() + d.len_utf8();
+                                    text[inside..].find(d).map_or(text.len(), |end| inside + end + d.len_utf8())
+                                },
+                                None => text.len(),
+                            };
+                        },
+                        "TITLE" | "SUBTTL" => break,
+                        _ => {},
+                    }
+                },
+                _ => {},
+            }
+            previous = text[..at].chars().next_back().unwrap_or(' ');
+        }
+        at = text.len();
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn words(source: &str) -> Vec<(usize, usize, Result<u32, MacroError>)> {
+        literals(source).map(|l| (l.line, l.column, l.word)).collect()
+    }
+
+    #[test]
+    fn parse() {
+        let w = |s| pdp10::encode_word(s).unwrap();
+        assert_eq!(parse_literal("^RA"), Some(w("A     ")));
+        assert_eq!(parse_literal("^R%$.123"), Some(w("%$.123")));
+        assert_eq!(parse_literal("^R1234567"), Some(w("123456")));
+        assert_eq!(parse_literal("^R A"), None);
+        assert_eq!(parse_literal("RABC"), None);
+        assert_eq!(parse_literal(""), None);
+
+        for name in ["A", "PAT..", "%$.123", "999999"] {
+            let literal = format_literal(w(name)).unwrap();
+            assert_eq!(literal, format!("^R{}", name));
+            assert_eq!(parse_literal(&literal), Some(w(name)));
+        }
+        assert_eq!(format_literal(w("A B")), None);
+        assert_eq!(format_literal(pdp10::MAX_WORD + 1), None);
+    }
+
+    #[test]
+    fn source() {
+        let w = |s| Ok(pdp10::encode_word(s).unwrap());
+        assert_eq!(words("A=^RABC\nB:\tEXP ^rx, ^R.JBSYM^RQ"), [(1, 3, w("ABC")), (2, 8, w("X")), (2, 13, w(".JBSYM")), (2, 21, w("Q"))]);
+        assert_eq!(words("MOVEI 1,\"^R\" ; ^RX\nMOVE 1,['^RA']"), []);
+        assert_eq!(words("ASCIZ /^RA/ ^RB\nSIXBIT|^RA|\nCOMMENT ^RA^ ^RC\nTITLE ^RD"), [(1, 13, w("B")), (3, 14, w("C"))]);
+        assert_eq!(words("XASCIZ ^RA/ ^RB\nASCII ^RA"), [(1, 8, w("A")), (1, 13, w("B"))]);
+        assert_eq!(words("^R-1"), [(1, 1, Err(MacroError::Empty))]);
+        assert_eq!(words("ASCIZ é^Ré ^RX"), [(1, 12, w("X"))]);
+    }
+}
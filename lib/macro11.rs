@@ -0,0 +1,231 @@
+// RADIX-50 expressions in MACRO-11 source
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Finding and evaluating the RADIX-50 expressions in MACRO-11 source, so the words the assembler generated can
+//! be checked against this crate.
+//!
+//! MACRO-11 has two ways to write RADIX-50:
+//!
+//! - The `.RAD50` directive takes strings between matching delimiters (`.RAD50 /ABC/`), and character codes in
+//!   angle brackets (`.RAD50 /AB/<35>`). The characters are packed 3 to a word, with spaces padding the last one.
+//! - The `^R` operator makes a single word from the (up to 3) characters right after it (`MOV #^RABC,R0`).
+//!
+//! Lower case letters are converted to upper case, the way MACRO-11 does it. Numbers in angle brackets are octal,
+//! or decimal if they end with a `.`.
+//!
+//! # Examples
+//! ```
+//! # use radix50::macro11;
+//! let source = "NAME:  .RAD50  /PIP/     ; The name\n\
+//!               \tMOV\t#^RSY,R0\n";
+//! for expr in macro11::expressions(source) {
+//!     let expr = expr?;
+//!     println!("{}:{}: {} = {:06o}", expr.line, expr.column, expr.text, expr.words[0]);
+//! }
+//! # assert_eq!(macro11::expressions(source).map(|e| e.unwrap().words).collect::<Vec<_>>(), [[0o62570], [0o75250]]);
+//! # Ok::<(), macro11::MacroError>(())
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use crate::pdp11;
+
+/// A RADIX-50 expression found in MACRO-11 source.
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+pub struct Expression<'a> {
+    /// The line it's on, counting from 1.
+    pub line: usize,
+    /// The column (in characters) it starts at, counting from 1.
+    pub column: usize,
+    /// The source text: the whole directive (`.RAD50 /ABC/`) or operator (`^RABC`).
+    pub text: &'a str,
+    /// The words the assembler would generate.
+    pub words: Vec<u16>,
+}
+
+/// Errors from evaluating RADIX-50 expressions in MACRO-11 source. The `line` and `column` (counting from 1) point
+/// at the problem.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+#[non_exhaustive]
+pub enum MacroError {
+    /// A string (or `<>` value) has no closing delimiter on its line.
+    Unterminated { line: usize, column: usize },
+    /// A `<>` value isn't a number from 0 to 47 (octal).
+    BadValue { line: usize, column: usize },
+    /// A character can't be encoded in RADIX-50.
+    IllegalChar { line: usize, column: usize, char: char },
+    /// A `^R` isn't followed by any RADIX-50 characters.
+    Empty { line: usize, column: usize },
+}
+
+impl core::error::Error for MacroError {
+}
+
+impl fmt::Display for MacroError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MacroError::Unterminated { line, column }      => write!(f, "Unterminated string at {}:{}", line, column),
+            MacroError::BadValue { line, column }          => write!(f, "Bad character value at {}:{}", line, column),
+            MacroError::IllegalChar { line, column, char } => write!(f, "Illegal char '{}' at {}:{}", char, line, column),
+            MacroError::Empty { line, column }             => write!(f, "Nothing after ^R at {}:{}", line, column),
+        }
+    }
+}
+
+/// Iterate over the `.RAD50` directives and `^R` operators in MACRO-11 source, evaluating each one.
+///
+/// Comments are skipped, and so are the strings in `.ASCII`, `.ASCIZ`, and `.IDENT` directives and the text of
+/// `.TITLE` and `.SBTTL`. An error ends its line (the rest of the source is still searched).
+///
+/// # Examples
+/// ```
+/// # use radix50::macro11::{self, Expression, MacroError};
+/// let mut exprs = macro11::expressions("\t.RAD50\t/ABCDE/<35>\n\t.ASCII\t/^RXYZ/\n\t.RAD50\t|X;Y|");
+/// assert_eq!(exprs.next(), Some(Ok(Expression { line: 1, column: 2, text: ".RAD50\t/ABCDE/<35>", words: vec![0o3223, 0o14745] })));
+/// assert_eq!(exprs.next(), Some(Err(MacroError::IllegalChar { line: 3, column: 11, char: ';' })));
+/// assert_eq!(exprs.next(), None);
+/// ```
+pub fn expressions(source: &str) -> impl Iterator<Item = Result<Expression<'_>, MacroError>> {
+    source.lines().enumerate().flat_map(|(n, line)| line_expressions(n + 1, line))
+}
+
+fn is_symbol_char(b: u8) -> bool { b.is_ascii_alphanumeric() || b == b'$' || b == b'.' }
+
+fn line_expressions(line: usize, text: &str) -> Vec<Result<Expression<'_>, MacroError>> {
+    let bytes = text.as_bytes();
+    let column = |at: usize| text[..at].chars().count() + 1;
+    let mut found = Vec::new();
+    let mut at = 0;
+    while let Some(&b) = bytes.get(at) {
+        match b {
+            b';' => break,
+            // Character constants (so `'^` and `"^R` aren't operators)
+            b'\'' | b'"' => at += 1 + text[at + 1..].chars().take(if b == b'"' { 2 } else { 1 }).map(char::len_utf8).sum::<usize>(),
+            b'^' if matches!(bytes.get(at + 1), Some(b'R' | b'r')) => {
+                let len = bytes[at + 2..].iter().take(pdp11::CHARS_PER_WORD)
+                                         .take_while(|&&c| c != b' ' && pdp11::char_to_code(c.to_ascii_uppercase() as char).is_some()).count();
+                if len == 0 { found.push(Err(MacroError::Empty { line, column: column(at) })); break }
+                let words = pdp11::encode(&text[at + 2..at + 2 + len].to_ascii_uppercase()).unwrap();
+                found.push(Ok(Expression { line, column: column(at), text: &text[at..at + 2 + len], words }));
+                at += 2 + len;
+            },
+            b'.' if at == 0 || !is_symbol_char(bytes[at - 1]) => {
+                let start = at;
+                at += 1 + bytes[at + 1..].iter().take_while(|&&c| is_symbol_char(c)).count();
+                let directive = text[start + 1..at].to_ascii_uppercase();
+                match directive.as_str() {
+                    "RAD50" => match rad50_operands(line, text, at, column) {
+                        Ok((words, end)) => { found.push(Ok(Expression { line, column: column(start), text: &text[start..end], words })); at = end },
+                        Err(e)           => { found.push(Err(e)); break },
+                    },
+                    "ASCII" | "ASCIZ" | "IDENT" => match skip_strings(text, at, if directive == "IDENT" { 1 } else { usize::MAX }) {
+                        Some(end) => at = end,
+                        None      => break,
+                    },
+                    "TITLE" | "SBTTL" => break,
+                    _ => {},
+                }
+            },
+            _ => at += text[at..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    found
+}
+
+fn skip_blanks(bytes: &[u8], at: usize) -> usize { at + bytes[at..].iter().take_while(|c| c.is_ascii_whitespace()).count() }
+
+// The delimited strings and `<>` values after `.RAD50`, up to the end of the line or a comment. Returns the words
+// and where the last operand ends.
+fn rad50_operands(line: usize, text: &str, mut at: usize, column: impl Fn(usize) -> usize) -> Result<(Vec<u16>, usize), MacroError> {
+    let bytes = text.as_bytes();
+    let mut chars = String::new();
+    let mut end = at;
+    loop {
+        at = skip_blanks(bytes, at);
+        let Some(delimiter) = text[at..].chars().next().filter(|&c| c != ';') else { break };
+        let close = if delimiter == '<' { '>' } else { delimiter };
+        let inside = at + delimiter.len_utf8();
+        let Some(len) = text[inside..].find(close) else { return Err(MacroError::Unterminated { line, column: column(at) }) };
+        let operand = &text[inside..inside + len];
+        if delimiter == '<' {
+            let operand = operand.trim();
+            let value = match operand.strip_suffix('.') {
+                Some(decimal) => decimal.parse(),
+                None          => u8::from_str_radix(operand, 8),
+            };
+            chars.push(value.ok().and_then(pdp11::code_to_char).ok_or(MacroError::BadValue { line, column: column(at) })?);
+        } else {
+            for (n, c) in operand.char_indices() {
+                let c = if c == ' ' || pdp11::char_to_code(c.to_ascii_uppercase()).is_some() { c.to_ascii_uppercase() }
+                        else { return Err(MacroError::IllegalChar { line, column: column(inside + n), char: c }) };
+                chars.push(c);
+            }
+        }
+        at = inside + len + close.len_utf8();
+        end = at;
+    }
+    Ok((pdp11::encode(&chars).unwrap(), end))
+}
+
+// Skip (up to `count`) delimited strings and `<>` values of `.ASCII` style directives. Returns `None` if one isn't
+// terminated.
+fn skip_strings(text: &str, mut at: usize, count: usize) -> Option<usize> {
+    for _ in 0..count {
+        at = skip_blanks(text.as_bytes(), at);
+        let (open, close) = match text[at..].chars().next() {
+            None | Some(';') => return Some(at),
+            Some('<')        => ('<', '>'),
+            Some(d)          => (d, d),
+        };
+        at += open.len_utf8();
+        at += text[at..].find(close)? + close.len_utf8();
+    }
+    Some(at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn words(source: &str) -> Vec<Result<Vec<u16>, MacroError>> { expressions(source).map(|e| e.map(|e| e.words)).collect() }
+
+    #[test]
+    fn rad50() {
+        assert_eq!(words(".RAD50 /ABC/"), [Ok(vec![0o3223])]);
+        assert_eq!(words("\t.rad50\t/abcd/ ; comment /XYZ/"), [Ok(vec![0o3223, 0o14400])]);
+        assert_eq!(words("X: .RAD50 /A/<0>/B/<10.><36>\"X\""), [Ok(vec![0o3102, 0o41510])]);
+        assert_eq!(words(".RAD50 ||"), [Ok(vec![])]);
+        assert_eq!(words(".RAD50"), [Ok(vec![])]);
+        assert_eq!(expressions(" .RAD50 /AB/   ").next().unwrap().unwrap().text, ".RAD50 /AB/");
+
+        assert_eq!(words(".RAD50 /ABC"),  [Err(MacroError::Unterminated { line: 1, column: 8 })]);
+        assert_eq!(words(".RAD50 <1"),    [Err(MacroError::Unterminated { line: 1, column: 8 })]);
+        assert_eq!(words(".RAD50 <50>"),  [Err(MacroError::BadValue { line: 1, column: 8 })]);
+        assert_eq!(words(".RAD50 <X>"),   [Err(MacroError::BadValue { line: 1, column: 8 })]);
+        assert_eq!(words(".RAD50 /A-B/"), [Err(MacroError::IllegalChar { line: 1, column: 10, char: '-' })]);
+        assert_eq!(words("\n\n.RAD50 /é/"), [Err(MacroError::IllegalChar { line: 3, column: 9, char: 'é' })]);
+    }
+
+    #[test]
+    fn caret_r() {
+        assert_eq!(words("MOV #^RABC,R0"), [Ok(vec![0o3223])]);
+        assert_eq!(words(".WORD ^Rx, ^R$.9 ^RABCD"), [Ok(vec![0o113000]), Ok(vec![0o126507]), Ok(vec![0o3223])]);
+        assert_eq!(expressions("A=^RAB C").next().unwrap().unwrap().text, "^RAB");
+        assert_eq!(words("MOV #^R,R0"), [Err(MacroError::Empty { line: 1, column: 6 })]);
+        assert_eq!(words("MOVB #'^,R0 ; ^RXYZ"), []);
+    }
+
+    #[test]
+    fn skipped() {
+        assert_eq!(words(".ASCII /^RAB/<15>/.RAD50 /\n.ASCIZ <12>|X|\n^RX"), [Ok(vec![0o113000])]);
+        assert_eq!(words(".TITLE ^RAB .RAD50 /X/"), []);
+        assert_eq!(words(".IDENT /V1.RAD50/ .RAD50 /X/"), [Ok(vec![0o113000])]);
+        assert_eq!(words("X.RAD50 /A/ 1.RAD50"), []);
+        assert_eq!(words(".IDENT ÀÀ"), []);
+        assert_eq!(words(".ASCII é^RAé ; ^RX\n.IDENT ÀV1À ^RY"), [Ok(vec![0o116100])]);
+    }
+}
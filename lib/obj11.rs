@@ -0,0 +1,624 @@
+// PDP-11 object modules
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Reading PDP-11 object modules (`.OBJ` files from MACRO-11 and friends).
+//!
+//! An object file is a sequence of formatted binary records. Each one starts with a 1 byte, a 0 byte, and a 16
+//! bit byte count (which includes those 4 bytes), then the data, then a checksum byte that makes all the bytes of
+//! the record sum to 0. Zero bytes between records are padding. The first word of each record's data is its
+//! [`RecordType`].
+//!
+//! The global symbol directory (GSD) records name the module and list its program sections and global symbols,
+//! all as [`Symbol6`] RADIX-50 names. [`gsd`] collects them ([`gsd_into`] streams them), and [`write_gsd`] goes
+//! the other way. [`cref`] cross references the global symbols of a whole set of modules.
+//!
+//! On RSX-11 disks, object files are stored as [variable length records](variable_records) instead of formatted
+//! binary, but the data in each record is the same.
+//!
+//! # Examples
+//! ```
+//! # use radix50::obj11::{self, GsdKind};
+//! # let obj = [1, 0, 22, 0, 1, 0, 113, 81, 128, 87, 0, 0, 0, 0, 217, 102, 166, 90, 8, 4, 52, 18, 190];
+//! for entry in obj11::gsd(&obj)? {
+//!     if entry.kind == GsdKind::GlobalSymbol && entry.is_definition() {
+//!         println!("{:6} {:06o}", entry.name, entry.value);
+//!     }
+//! }
+//! # assert_eq!(obj11::gsd(&obj)?[1].name.to_string(), "PRINTF");
+//! # Ok::<(), obj11::ObjError>(())
+//! ```
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt;
+
+use crate::{Error, Symbol6, report::ReportSink};
+
+/// GSD entry flag bits.
+///
+/// The same bit can mean different things for different [kinds](GsdKind) of entries, so the constants are named
+/// after the kind they apply to.
+pub mod flags {
+    /// Global symbol: a weak definition or reference.
+    pub const GLOBAL_WEAK: u8 = 0o1;
+    /// Global symbol: defined here (otherwise it's a reference).
+    pub const GLOBAL_DEFINITION: u8 = 0o10;
+    /// Global symbol: relocatable (otherwise absolute).
+    pub const GLOBAL_RELOCATABLE: u8 = 0o40;
+
+    /// Psect: stays resident in the root of an overlaid program.
+    pub const PSECT_SAVE: u8 = 0o1;
+    /// Psect: overlaid with same-named psects from other modules (otherwise concatenated).
+    pub const PSECT_OVERLAY: u8 = 0o4;
+    /// Psect: read only (otherwise read/write).
+    pub const PSECT_READ_ONLY: u8 = 0o20;
+    /// Psect: relocatable (otherwise absolute).
+    pub const PSECT_RELOCATABLE: u8 = 0o40;
+    /// Psect: global scope across overlay segments (otherwise local).
+    pub const PSECT_GLOBAL: u8 = 0o100;
+    /// Psect: data (otherwise instructions).
+    pub const PSECT_DATA: u8 = 0o200;
+}
+
+/// The type of a formatted binary record (the first word of its data).
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum RecordType {
+    /// Global symbol directory
+    Gsd,
+    /// End of the global symbol directory
+    EndGsd,
+    /// Text (the module's code and data)
+    Txt,
+    /// Relocation directory
+    Rld,
+    /// Internal symbol directory
+    Isd,
+    /// End of module
+    EndMod,
+    /// Librarian header
+    LibHeader,
+    /// Librarian end
+    LibEnd,
+    /// Anything else
+    Unknown(u16),
+}
+
+impl From<u16> for RecordType {
+    fn from(w: u16) -> Self {
+        match w {
+            1 => RecordType::Gsd,
+            2 => RecordType::EndGsd,
+            3 => RecordType::Txt,
+            4 => RecordType::Rld,
+            5 => RecordType::Isd,
+            6 => RecordType::EndMod,
+            7 => RecordType::LibHeader,
+            8 => RecordType::LibEnd,
+            w => RecordType::Unknown(w),
+        }
+    }
+}
+
+impl From<RecordType> for u16 {
+    fn from(t: RecordType) -> Self {
+        match t {
+            RecordType::Gsd        => 1,
+            RecordType::EndGsd     => 2,
+            RecordType::Txt        => 3,
+            RecordType::Rld        => 4,
+            RecordType::Isd        => 5,
+            RecordType::EndMod     => 6,
+            RecordType::LibHeader  => 7,
+            RecordType::LibEnd     => 8,
+            RecordType::Unknown(w) => w,
+        }
+    }
+}
+
+/// What a GSD entry describes.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum GsdKind {
+    /// The module name
+    ModuleName,
+    /// A control section (the pre-psect name for one)
+    ControlSection,
+    /// An internal symbol
+    InternalSymbol,
+    /// The transfer (start) address: `value` is the offset into the psect called `name`
+    TransferAddress,
+    /// A global symbol, defined or referenced
+    GlobalSymbol,
+    /// A program section
+    Psect,
+    /// The module's version (from `.IDENT`)
+    Ident,
+    /// A mapped array
+    MappedArray,
+    /// A completion routine
+    CompletionRoutine,
+    /// Anything else
+    Unknown(u8),
+}
+
+impl From<u8> for GsdKind {
+    fn from(b: u8) -> Self {
+        match b {
+            0 => GsdKind::ModuleName,
+            1 => GsdKind::ControlSection,
+            2 => GsdKind::InternalSymbol,
+            3 => GsdKind::TransferAddress,
+            4 => GsdKind::GlobalSymbol,
+            5 => GsdKind::Psect,
+            6 => GsdKind::Ident,
+            7 => GsdKind::MappedArray,
+            8 => GsdKind::CompletionRoutine,
+            b => GsdKind::Unknown(b),
+        }
+    }
+}
+
+impl From<GsdKind> for u8 {
+    fn from(k: GsdKind) -> Self {
+        match k {
+            GsdKind::ModuleName        => 0,
+            GsdKind::ControlSection    => 1,
+            GsdKind::InternalSymbol    => 2,
+            GsdKind::TransferAddress   => 3,
+            GsdKind::GlobalSymbol      => 4,
+            GsdKind::Psect             => 5,
+            GsdKind::Ident             => 6,
+            GsdKind::MappedArray       => 7,
+            GsdKind::CompletionRoutine => 8,
+            GsdKind::Unknown(b)        => b,
+        }
+    }
+}
+
+/// One entry from a GSD record: 2 words of name, a flags byte, a kind byte, and a value word.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct GsdEntry {
+    /// The name of the module, psect, symbol, etc.
+    pub name: Symbol6,
+    /// The flags. What they mean depends on the kind.
+    pub flags: u8,
+    /// What kind of entry it is.
+    pub kind: GsdKind,
+    /// The symbol's value, the psect's size, the transfer address, etc.
+    pub value: u16,
+}
+
+impl GsdEntry {
+    /// How many bytes an entry takes in a GSD record.
+    pub const SIZE: usize = 8;
+
+    /// Make an entry, encoding `name` (which can be up to 6 characters).
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{Error, obj11::{GsdEntry, GsdKind, flags}};
+    /// let start = GsdEntry::new("START", GsdKind::GlobalSymbol, flags::GLOBAL_DEFINITION | flags::GLOBAL_RELOCATABLE, 0o1000).unwrap();
+    /// assert!(start.is_definition());
+    /// assert_eq!(GsdEntry::new("TOOLONG", GsdKind::GlobalSymbol, 0, 0), Err(Error::InputTooLong { len: 7, max: 6 }));
+    /// ```
+    pub fn new(name: &str, kind: GsdKind, flags: u8, value: u16) -> Result<Self, Error> {
+        Ok(GsdEntry { name: Symbol6::encode(name)?, flags, kind, value })
+    }
+
+    /// Parse an entry from its 8 bytes.
+    pub fn from_bytes(b: &[u8; GsdEntry::SIZE]) -> Self {
+        GsdEntry {
+            name: Symbol6::new([u16::from_le_bytes([b[0], b[1]]), u16::from_le_bytes([b[2], b[3]])]),
+            flags: b[4],
+            kind: GsdKind::from(b[5]),
+            value: u16::from_le_bytes([b[6], b[7]]),
+        }
+    }
+
+    /// The entry's 8 bytes, the way they're stored in a GSD record.
+    pub fn to_bytes(&self) -> [u8; GsdEntry::SIZE] {
+        let [n1, n2] = self.name.words();
+        let ([a, b], [c, d], [e, f]) = (n1.to_le_bytes(), n2.to_le_bytes(), self.value.to_le_bytes());
+        [a, b, c, d, self.flags, self.kind.into(), e, f]
+    }
+
+    /// For a global symbol, whether this defines it (rather than referring to it).
+    pub fn is_definition(&self) -> bool { self.flags & flags::GLOBAL_DEFINITION != 0 }
+}
+
+/// Errors from reading formatted binary records.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+#[non_exhaustive]
+pub enum ObjError {
+    /// A record at byte `offset` doesn't start with 1, 0.
+    BadHeader { offset: usize },
+    /// The record at byte `offset` runs past the end of the file (or its byte count is too small to be valid).
+    Truncated { offset: usize },
+    /// The record at byte `offset` has a bad checksum.
+    Checksum { offset: usize },
+}
+
+impl core::error::Error for ObjError {
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::BadHeader { offset } => write!(f, "Bad record header at byte {}", offset),
+            ObjError::Truncated { offset } => write!(f, "Truncated record at byte {}", offset),
+            ObjError::Checksum { offset }  => write!(f, "Bad checksum in record at byte {}", offset),
+        }
+    }
+}
+
+/// Iterate over the formatted binary records in `bytes`, yielding each one's data (without the header and
+/// checksum). The checksum of each record is verified. Iteration stops after the first error.
+///
+/// # Examples
+/// ```
+/// # use radix50::obj11::{self, ObjError};
+/// let bytes = [0, 0, 1, 0, 6, 0, 2, 0, 0xf7, 0, 1, 0, 6, 0, 2, 0, 0xf6];
+/// let mut records = obj11::records(&bytes);
+/// assert_eq!(records.next(), Some(Ok(&[2, 0][..])));
+/// assert_eq!(records.next(), Some(Err(ObjError::Checksum { offset: 10 })));
+/// assert_eq!(records.next(), None);
+/// ```
+pub fn records(bytes: &[u8]) -> impl Iterator<Item = Result<&[u8], ObjError>> {
+    let mut at = 0;
+    let mut failed = false;
+    core::iter::from_fn(move || {
+        if failed { return None }
+        while bytes.get(at) == Some(&0) { at += 1 }
+        if at >= bytes.len() { return None }
+        let offset = at;
+        let record = read_record(&bytes[at..], offset);
+        match record {
+            Ok(data) => at += data.len() + 5,
+            Err(_)   => failed = true,
+        }
+        Some(record)
+    })
+}
+
+fn read_record(bytes: &[u8], offset: usize) -> Result<&[u8], ObjError> {
+    if bytes.len() < 2 || bytes[..2] != [1, 0] { return Err(ObjError::BadHeader { offset }) }
+    let [_, _, lo, hi, ..] = *bytes else { return Err(ObjError::Truncated { offset }) };
+    let len = u16::from_le_bytes([lo, hi]) as usize;
+    if len < 4 || bytes.len() <= len { return Err(ObjError::Truncated { offset }) }
+    if bytes[..=len].iter().fold(0u8, |sum, b| sum.wrapping_add(*b)) != 0 { return Err(ObjError::Checksum { offset }) }
+    Ok(&bytes[4..len])
+}
+
+/// Iterate over the variable length records in `bytes`, the way RSX-11 stores object and symbol table files on
+/// disk: a byte count word, then that many bytes of data, then a pad byte if the count is odd. Yields each record's
+/// data (the same as [`records`] does). Iteration stops after the first error.
+///
+/// # Examples
+/// ```
+/// # use radix50::obj11::{self, ObjError};
+/// let bytes = [2, 0, 2, 0, 3, 0, 1, 2, 3, 0, 4, 0, 6, 0];
+/// let mut records = obj11::variable_records(&bytes);
+/// assert_eq!(records.next(), Some(Ok(&[2, 0][..])));
+/// assert_eq!(records.next(), Some(Ok(&[1, 2, 3][..])));
+/// assert_eq!(records.next(), Some(Err(ObjError::Truncated { offset: 10 })));
+/// assert_eq!(records.next(), None);
+/// ```
+pub fn variable_records(bytes: &[u8]) -> impl Iterator<Item = Result<&[u8], ObjError>> {
+    let mut at = 0;
+    let mut failed = false;
+    core::iter::from_fn(move || {
+        if failed || at >= bytes.len() { return None }
+        let offset = at;
+        let record = match bytes[at..] {
+            [lo, hi, ref rest @ ..] if rest.len() >= u16::from_le_bytes([lo, hi]) as usize => {
+                let len = u16::from_le_bytes([lo, hi]) as usize;
+                at += 2 + len + len % 2;
+                Ok(&rest[..len])
+            },
+            _ => Err(ObjError::Truncated { offset }),
+        };
+        failed = record.is_err();
+        Some(record)
+    })
+}
+
+/// Iterate over the entries in a GSD record's data (as returned by [`records`]). Any partial entry at the end is
+/// ignored.
+pub fn gsd_entries(data: &[u8]) -> impl Iterator<Item = GsdEntry> + '_ {
+    data.get(2..).unwrap_or_default()
+                 .chunks_exact(GsdEntry::SIZE)
+                 .map(|b| GsdEntry::from_bytes(b.try_into().expect("chunks are GsdEntry::SIZE long")))
+}
+
+/// Collect the GSD entries from every GSD record in an object file, in order.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Symbol6, obj11::{self, GsdKind}};
+/// let obj = [1, 0, 14, 0, 1, 0, 113, 81, 128, 87, 0, 0, 0, 0, 87];
+/// let gsd = obj11::gsd(&obj).unwrap();
+/// assert_eq!(gsd[0].kind, GsdKind::ModuleName);
+/// assert_eq!(gsd[0].name, "MAIN".parse::<Symbol6>().unwrap());
+/// ```
+pub fn gsd(bytes: &[u8]) -> Result<Vec<GsdEntry>, ObjError> {
+    let mut entries: Vec<Result<GsdEntry, ObjError>> = Vec::new();
+    gsd_into(bytes, &mut entries);
+    entries.into_iter().collect()
+}
+
+/// Like [`gsd`], but hands each entry to `sink` as it's read instead of collecting them. If a record is bad, the
+/// entries before it have already gone to the sink when it gets the error.
+///
+/// # Examples
+/// ```
+/// # use radix50::obj11::{self, GsdEntry, ObjError};
+/// let obj = [1, 0, 14, 0, 1, 0, 113, 81, 128, 87, 0, 0, 0, 0, 87, 1, 0, 6, 0];
+/// let mut results: Vec<Result<GsdEntry, ObjError>> = Vec::new();
+/// obj11::gsd_into(&obj, &mut results);
+/// assert_eq!(results[0].as_ref().unwrap().name.to_string(), "MAIN");
+/// assert_eq!(results[1], Err(ObjError::Truncated { offset: 15 }));
+/// ```
+pub fn gsd_into<S: ReportSink<GsdEntry, ObjError> + ?Sized>(bytes: &[u8], sink: &mut S) {
+    for data in records(bytes) {
+        let data = match data {
+            Ok(data)   => data,
+            Err(error) => return sink.on_error(error),
+        };
+        if data.len() >= 2 && RecordType::from(u16::from_le_bytes([data[0], data[1]])) == RecordType::Gsd {
+            gsd_entries(data).for_each(|entry| sink.on_entry(entry));
+        }
+    }
+}
+
+/// Where a global symbol is defined and referenced, from [`cref`]. Modules are identified by their names (a module
+/// with no name entry is blank).
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+pub struct CrossReference {
+    /// The global symbol.
+    pub name: Symbol6,
+    /// The modules that define the symbol. More than one means it's multiply defined, and none means it's undefined.
+    pub definitions: Vec<Symbol6>,
+    /// The modules that reference the symbol.
+    pub references: Vec<Symbol6>,
+}
+
+/// Cross reference the global symbols in a set of object files, like the old CREF listings: for each symbol,
+/// which modules define it and which reference it. A file can hold more than one module (each ends with an end of
+/// module record), like a library does.
+///
+/// The symbols are sorted in RADIX-50 order (letters, then `$`, `.`, and digits). The modules are listed in the
+/// order they were found, each one once.
+///
+/// # Examples
+/// ```
+/// # use radix50::obj11::{self, GsdEntry, GsdKind, RecordType, flags};
+/// let module = |name, defs: &[&str], refs: &[&str]| -> Result<Vec<u8>, radix50::Error> {
+///     let mut entries = vec![GsdEntry::new(name, GsdKind::ModuleName, 0, 0)?];
+///     for def in defs { entries.push(GsdEntry::new(def, GsdKind::GlobalSymbol, flags::GLOBAL_DEFINITION, 0)?) }
+///     for r in refs   { entries.push(GsdEntry::new(r, GsdKind::GlobalSymbol, 0, 0)?) }
+///     let mut obj = Vec::new();
+///     obj11::write_gsd(&entries, &mut obj);
+///     obj11::write_record(RecordType::EndMod, &[], &mut obj);
+///     Ok(obj)
+/// };
+/// let (main, io) = (module("MAIN", &["START"], &["PRINT"])?, module("IO", &["PRINT"], &[])?);
+/// for xref in obj11::cref([main.as_slice(), io.as_slice()])? {
+///     println!("{:6} defined in {:?}, referenced by {:?}", xref.name, xref.definitions, xref.references);
+/// }
+/// let xref = obj11::cref([main.as_slice(), io.as_slice()])?;
+/// assert_eq!(xref[0].name.to_string(), "PRINT");
+/// assert_eq!((xref[0].definitions[0].to_string(), xref[0].references[0].to_string()), ("IO".into(), "MAIN".into()));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn cref<'a, I>(objects: I) -> Result<Vec<CrossReference>, ObjError> where I: IntoIterator<Item = &'a [u8]> {
+    let mut xref: BTreeMap<Symbol6, CrossReference> = BTreeMap::new();
+    for bytes in objects {
+        let mut module = Symbol6::default();
+        for data in records(bytes) {
+            let data = data?;
+            if data.len() < 2 { continue }
+            match RecordType::from(u16::from_le_bytes([data[0], data[1]])) {
+                RecordType::Gsd => for entry in gsd_entries(data) {
+                    match entry.kind {
+                        GsdKind::ModuleName   => module = entry.name,
+                        GsdKind::GlobalSymbol => {
+                            let x = xref.entry(entry.name).or_insert_with(|| CrossReference { name: entry.name, definitions: Vec::new(),
+                                                                                              references: Vec::new() });
+                            let modules = if entry.is_definition() { &mut x.definitions } else { &mut x.references };
+                            if !modules.contains(&module) { modules.push(module) }
+                        },
+                        _ => {},
+                    }
+                },
+                RecordType::EndMod => module = Symbol6::default(),
+                _                  => {},
+            }
+        }
+    }
+    Ok(xref.into_values().collect())
+}
+
+/// The most entries [`write_gsd`] puts in one record. MACRO-11 keeps its records short, so this does too: this
+/// many entries makes a 128 byte record.
+pub const MAX_GSD_ENTRIES: usize = 15;
+
+/// Append a formatted binary record to `out`: the header, `record_type`'s word, `payload`, and the checksum.
+///
+/// # Panics
+/// If the record would be bigger than the 16 bit byte count can describe.
+///
+/// # Examples
+/// ```
+/// # use radix50::obj11::{self, RecordType};
+/// let mut obj = Vec::new();
+/// obj11::write_record(RecordType::EndMod, &[], &mut obj);
+/// assert_eq!(obj, [1, 0, 6, 0, 6, 0, 0xf3]);
+/// assert_eq!(obj11::records(&obj).next(), Some(Ok(&[6, 0][..])));
+/// ```
+pub fn write_record(record_type: RecordType, payload: &[u8], out: &mut Vec<u8>) {
+    let len = u16::try_from(6 + payload.len()).expect("formatted binary records are at most 65535 bytes");
+    let start = out.len();
+    out.extend([1, 0]);
+    out.extend(len.to_le_bytes());
+    out.extend(u16::from(record_type).to_le_bytes());
+    out.extend(payload);
+    let sum = out[start..].iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+    out.push(sum.wrapping_neg());
+}
+
+/// Append a variable length record to `out`, the RSX-11 way that [`variable_records`] reads: the byte count,
+/// `record_type`'s word, `payload`, and a pad byte if the count is odd.
+///
+/// # Panics
+/// If the record would be bigger than the 16 bit byte count can describe.
+///
+/// # Examples
+/// ```
+/// # use radix50::obj11::{self, RecordType};
+/// let mut obj = Vec::new();
+/// obj11::write_variable_record(RecordType::Gsd, &[1], &mut obj);
+/// assert_eq!(obj, [3, 0, 1, 0, 1, 0]);
+/// assert_eq!(obj11::variable_records(&obj).next(), Some(Ok(&[1, 0, 1][..])));
+/// ```
+pub fn write_variable_record(record_type: RecordType, payload: &[u8], out: &mut Vec<u8>) {
+    let len = u16::try_from(2 + payload.len()).expect("variable length records are at most 65535 bytes");
+    out.extend(len.to_le_bytes());
+    out.extend(u16::from(record_type).to_le_bytes());
+    out.extend(payload);
+    if len % 2 == 1 { out.push(0) }
+}
+
+/// Append GSD records holding `entries` (at most [`MAX_GSD_ENTRIES`] per record) and then an end of GSD record to
+/// `out`.
+///
+/// Conventionally the first entry is the [module name](GsdKind::ModuleName). The text, relocation, and end of
+/// module records that make up the rest of an object module can be written with [`write_record`].
+///
+/// # Examples
+/// ```
+/// # use radix50::obj11::{self, GsdEntry, GsdKind, RecordType, flags};
+/// let entries = [GsdEntry::new("HELLO", GsdKind::ModuleName, 0, 0)?,
+///                GsdEntry::new(". ABS.", GsdKind::Psect, flags::PSECT_GLOBAL | flags::PSECT_OVERLAY, 0)?,
+///                GsdEntry::new("START", GsdKind::GlobalSymbol, flags::GLOBAL_DEFINITION, 0o1000)?,
+///                GsdEntry::new("$PRINT", GsdKind::GlobalSymbol, 0, 0)?];
+/// let mut obj = Vec::new();
+/// obj11::write_gsd(&entries, &mut obj);
+/// obj11::write_record(RecordType::EndMod, &[], &mut obj);
+/// assert_eq!(obj11::gsd(&obj).unwrap(), entries);
+/// # Ok::<(), radix50::Error>(())
+/// ```
+pub fn write_gsd(entries: &[GsdEntry], out: &mut Vec<u8>) {
+    for chunk in entries.chunks(MAX_GSD_ENTRIES) {
+        let payload: Vec<u8> = chunk.iter().flat_map(GsdEntry::to_bytes).collect();
+        write_record(RecordType::Gsd, &payload, out);
+    }
+    write_record(RecordType::EndGsd, &[], out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn record(data: &[u8]) -> Vec<u8> {
+        let mut r = vec![1, 0];
+        r.extend((data.len() as u16 + 4).to_le_bytes());
+        r.extend(data);
+        r.push(r.iter().fold(0u8, |s, b| s.wrapping_sub(*b)));
+        r
+    }
+
+    fn entry(name: &str, flags: u8, kind: u8, value: u16) -> Vec<u8> {
+        let mut b = Vec::new();
+        for w in Symbol6::encode(name).unwrap().words() { b.extend(w.to_le_bytes()) }
+        b.extend([flags, kind]);
+        b.extend(value.to_le_bytes());
+        b
+    }
+
+    #[test]
+    fn globals() {
+        let mut gsd1 = vec![1, 0];
+        gsd1.extend(entry("HELLO", 0, 0, 0));
+        gsd1.extend(entry(". ABS.", 0o104, 5, 0));
+        gsd1.extend(entry("START", 0o150, 4, 0o1000));
+        let mut gsd2 = vec![1, 0];
+        gsd2.extend(entry("$PRINT", 0, 4, 0));
+        let mut obj = record(&gsd1);
+        obj.extend([0; 3]);
+        obj.extend(record(&[3, 0, 0, 0, 1, 2, 3, 4]));
+        obj.extend(record(&gsd2));
+        obj.extend(record(&[2, 0]));
+        obj.extend(record(&[6, 0]));
+
+        assert_eq!(records(&obj).map(|r| RecordType::from(u16::from_le_bytes([r.unwrap()[0], 0]))).collect::<Vec<_>>(),
+                   [RecordType::Gsd, RecordType::Txt, RecordType::Gsd, RecordType::EndGsd, RecordType::EndMod]);
+        let gsd = gsd(&obj).unwrap();
+        assert_eq!(gsd.iter().map(|e| (e.name.to_string(), e.kind)).collect::<Vec<_>>(),
+                   [("HELLO".into(), GsdKind::ModuleName), (". ABS.".into(), GsdKind::Psect),
+                    ("START".into(), GsdKind::GlobalSymbol), ("$PRINT".into(), GsdKind::GlobalSymbol)]);
+        assert!(gsd[2].is_definition() && !gsd[3].is_definition());
+        assert_eq!(gsd[2].value, 0o1000);
+    }
+
+    #[test]
+    fn bad_records() {
+        let good = record(&[2, 0]);
+        let mut obj = good.clone();
+        obj.extend(&good[..5]);
+        assert_eq!(gsd(&obj), Err(ObjError::Truncated { offset: 7 }));
+        assert_eq!(gsd(&[1, 0, 3, 0, 0]), Err(ObjError::Truncated { offset: 0 }));
+        assert_eq!(gsd(&[2, 0, 4, 0, 0]), Err(ObjError::BadHeader { offset: 0 }));
+        assert_eq!(gsd(&[0, 0, 1]), Err(ObjError::BadHeader { offset: 2 }));
+        assert_eq!(gsd(&[]), Ok(vec![]));
+        for kind in 0..=255 { assert_eq!(u8::from(GsdKind::from(kind)), kind) }
+        for t in 0..=9 { assert_eq!(u16::from(RecordType::from(t)), t) }
+    }
+
+    #[test]
+    fn writing() {
+        let entries: Vec<GsdEntry> = (0..40).map(|n| GsdEntry::new(&alloc::format!("G{n}"), GsdKind::GlobalSymbol, flags::GLOBAL_DEFINITION, n)
+                                                       .unwrap()).collect();
+        for entry in &entries { assert_eq!(GsdEntry::from_bytes(&entry.to_bytes()), *entry) }
+        let mut obj = Vec::new();
+        write_gsd(&entries, &mut obj);
+        let records: Vec<&[u8]> = records(&obj).map(Result::unwrap).collect();
+        assert_eq!(records.iter().map(|r| r.len()).collect::<Vec<_>>(), [2 + 15 * 8, 2 + 15 * 8, 2 + 10 * 8, 2]);
+        assert_eq!(records[3], [2, 0]);
+        assert_eq!(gsd(&obj), Ok(entries));
+
+        let mut obj = Vec::new();
+        write_gsd(&[], &mut obj);
+        assert_eq!(obj, record(&[2, 0]));
+    }
+
+    #[test]
+    fn cross_reference() {
+        let module = |name: Option<&str>, globals: &[(&str, bool)], obj: &mut Vec<u8>| {
+            let mut entries: Vec<GsdEntry> = name.map(|name| GsdEntry::new(name, GsdKind::ModuleName, 0, 0).unwrap()).into_iter().collect();
+            entries.push(GsdEntry::new(". ABS.", GsdKind::Psect, 0, 0).unwrap());
+            for &(global, defined) in globals {
+                entries.push(GsdEntry::new(global, GsdKind::GlobalSymbol, if defined { flags::GLOBAL_DEFINITION } else { 0 }, 0).unwrap());
+            }
+            write_gsd(&entries, obj);
+            write_record(RecordType::EndMod, &[], obj);
+        };
+        let mut lib = Vec::new();
+        module(Some("SUB1"), &[("SUB1", true), ("X", false), ("X", false)], &mut lib);
+        module(None, &[("SUB2", true), ("SUB1", false)], &mut lib);
+        let mut main = Vec::new();
+        module(Some("MAIN"), &[("START", true), ("SUB1", false), ("SUB2", false), ("SUB1", true)], &mut main);
+
+        let xref: Vec<(String, Vec<String>, Vec<String>)> = cref([main.as_slice(), &lib]).unwrap().iter()
+            .map(|x| (x.name.to_string(), x.definitions.iter().map(Symbol6::to_string).collect(), x.references.iter().map(Symbol6::to_string).collect()))
+            .collect();
+        let strings = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+        assert_eq!(xref, [("START".into(), strings(&["MAIN"]), strings(&[])),
+                          ("SUB1".into(), strings(&["MAIN", "SUB1"]), strings(&["MAIN", ""])),
+                          ("SUB2".into(), strings(&[""]), strings(&["MAIN"])),
+                          ("X".into(), strings(&[]), strings(&["SUB1"]))]);
+
+        assert_eq!(cref([]), Ok(vec![]));
+        assert_eq!(cref([main.as_slice(), &[1, 0, 9]]), Err(ObjError::Truncated { offset: 0 }));
+    }
+}
@@ -0,0 +1,265 @@
+// Files-11 ODS-1 file names
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Files-11 ODS-1 file names, as stored in RSX-11 (and early VMS) file headers and directory entries.
+//!
+//! An ODS-1 file name is a 9 character name (3 [PDP-11 RADIX-50][pdp11] words), a 3 character type (1 word), and
+//! a binary version number (1 word). They're written `NAME.TYP;VER`, with the version in octal, the way RSX shows
+//! them.
+//!
+//! [pdp11]: crate::pdp11::RADIX50_DECODE
+//!
+//! # Examples
+//! ```
+//! # use radix50::ods1::FileName;
+//! let f: FileName = "STARTUP.CMD;3".parse().unwrap();
+//! assert_eq!(f.name, [31201, 29621, 25600]);
+//! assert_eq!(f.typ, 5324);
+//! assert_eq!(f.version, 3);
+//! assert_eq!(f.to_string(), "STARTUP.CMD;3");
+//! ```
+//!
+//! [`Header::parse`] reads the file headers in an ODS-1 volume's index file.
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{Error, dos11::Uic, pdp11, rt11::split_filename};
+
+/// How many characters fit in the name part of a file name.
+pub const NAME_CHARS: usize = 9;
+
+/// How many characters fit in the type part of a file name.
+pub const TYPE_CHARS: usize = 3;
+
+/// An ODS-1 file name: the raw RADIX-50 name and type words plus the version number.
+///
+/// It parses from (and displays as) `NAME.TYP;VER`. Only letters and digits are allowed in the name and type (lower
+/// case letters are upper cased), the `.TYP` and `;VER` parts are optional, and the version is octal. A missing
+/// version is 0, which isn't displayed. Parsing returns an [`Error::IllegalChar`] for a bad character (including a
+/// non-octal or overflowing version digit) and an [`Error::InputTooLong`] for a name or type that doesn't fit.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error, ods1::FileName};
+/// let f = FileName { name: [29584, 50853, 26099], typ: 31419, version: 0o10 };
+/// assert_eq!(f.to_string(), "RSX11MPLS.SYS;10");
+/// assert_eq!("rsx11mpls.sys;10".parse(), Ok(f));
+///
+/// assert_eq!("A".parse::<FileName>().unwrap().to_string(), "A.");
+/// assert_eq!("A.B;8".parse::<FileName>(), Err(Error::IllegalChar { char: '8', pos: 5 }));
+/// assert_eq!("TENLETTERS".parse::<FileName>(), Err(Error::InputTooLong { len: 10, max: 9 }));
+/// ```
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,Default)]
+pub struct FileName {
+    /// The name, 3 characters per word.
+    pub name: [u16; 3],
+    /// The file type.
+    pub typ: u16,
+    /// The version number.
+    pub version: u16,
+}
+
+impl FileName {
+    /// Build a file name from the 5 words (name, type, version) in the order they're stored in directory entries
+    /// and file headers.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::ods1::FileName;
+    /// assert_eq!(FileName::from_words([1600, 0, 0, 0, 1]).to_string(), "A.;1");
+    /// ```
+    pub const fn from_words(words: [u16; 5]) -> Self {
+        FileName { name: [words[0], words[1], words[2]], typ: words[3], version: words[4] }
+    }
+
+    /// The 5 words (name, type, version) in the order they're stored in directory entries and file headers.
+    pub const fn to_words(self) -> [u16; 5] {
+        [self.name[0], self.name[1], self.name[2], self.typ, self.version]
+    }
+}
+
+impl fmt::Display for FileName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut name = pdp11::decode(self.name);
+        name.truncate(name.trim_end_matches(' ').len());
+        write!(f, "{name}.{}", pdp11::decode_word(self.typ).trim_end_matches(' '))?;
+        if self.version != 0 { write!(f, ";{:o}", self.version)? }
+        Ok(())
+    }
+}
+
+impl FromStr for FileName {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (s, version) = s.split_once(';').unwrap_or((s, ""));
+        let (name, typ) = split_filename(s, NAME_CHARS, TYPE_CHARS)?;
+        let mut v: u16 = 0;
+        for (i, c) in version.chars().enumerate() {
+            v = c.to_digit(8)
+                 .and_then(|d| v.checked_mul(8)?.checked_add(d as u16))
+                 .ok_or(Error::IllegalChar { char: c, pos: s.chars().count() + 2 + i })?;
+        }
+        let mut words = [0; 3];
+        pdp11::encode_into_slice(&name, &mut words)?;
+        Ok(FileName { name: words, typ: pdp11::encode_word(&typ)?, version: v })
+    }
+}
+
+/// The size of a file header, in bytes.
+pub const HEADER_SIZE: usize = 512;
+
+/// The structure level of an ODS-1 file header (version 1, level 1).
+pub const STRUCTURE_LEVEL: u16 = 0o401;
+
+/// The parts of an ODS-1 file header needed to list the files in an index file.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct Header {
+    /// The file number (its position in the index file).
+    pub file_number: u16,
+    /// The file sequence number (bumped each time the header is reused).
+    pub sequence: u16,
+    /// The owner.
+    pub owner: Uic,
+    /// The protection code.
+    pub protection: u16,
+    /// The file name, type, and version from the ident area.
+    pub name: FileName,
+    /// The revision number from the ident area.
+    pub revision: u16,
+}
+
+/// Errors from [`Header::parse`].
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+#[non_exhaustive]
+pub enum HeaderError {
+    /// The checksum in the last word doesn't match the sum of the others.
+    Checksum { stored: u16, computed: u16 },
+    /// The structure level isn't [`STRUCTURE_LEVEL`].
+    StructureLevel(u16),
+    /// The ident area offset points outside the header (or overlaps the header area).
+    IdentOffset(u8),
+}
+
+impl core::error::Error for HeaderError {
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeaderError::Checksum { stored, computed } => write!(f, "Bad header checksum {:06o} (should be {:06o})", stored, computed),
+            HeaderError::StructureLevel(level)        => write!(f, "Unknown structure level {:06o}", level),
+            HeaderError::IdentOffset(offset)          => write!(f, "Ident area offset {} is out of range", offset),
+        }
+    }
+}
+
+impl Header {
+    // Word offsets within the header area
+    const FNUM: usize = 1;
+    const FSEQ: usize = 2;
+    const FLEV: usize = 3;
+    const FOWN: usize = 4;
+    const FPRO: usize = 5;
+    // The header area is 23 words, and the ident area needs 6
+    const HEADER_WORDS: u8 = 23;
+    const IDENT_WORDS: usize = 6;
+
+    /// Parse a file header block from an index file.
+    ///
+    /// Returns `Ok(None)` for an unused header (one with a zero file number). Otherwise the checksum and structure
+    /// level are checked before anything is decoded.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{dos11::Uic, ods1::{Header, HeaderError}};
+    /// let mut block = [0u8; 512];
+    /// let words: [u16; 29] = [0x1817, 5, 1, 0o401, 0o401, 0o164000, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ///                         31201, 29621, 25600, 5324, 3, 1];
+    /// for (w, b) in words.iter().zip(block.chunks_exact_mut(2)) { b.copy_from_slice(&w.to_le_bytes()) }
+    /// let sum = block[..510].chunks_exact(2).fold(0u16, |s, b| s.wrapping_add(u16::from_le_bytes([b[0], b[1]])));
+    /// block[510..].copy_from_slice(&sum.to_le_bytes());
+    ///
+    /// let header = Header::parse(&block).unwrap().unwrap();
+    /// assert_eq!(header.name.to_string(), "STARTUP.CMD;3");
+    /// assert_eq!((header.file_number, header.owner), (5, Uic { group: 1, member: 1 }));
+    ///
+    /// block[100] ^= 1;
+    /// assert!(matches!(Header::parse(&block), Err(HeaderError::Checksum { .. })));
+    /// assert_eq!(Header::parse(&[0; 512]), Ok(None));
+    /// ```
+    pub fn parse(block: &[u8; HEADER_SIZE]) -> Result<Option<Header>, HeaderError> {
+        let word = |n: usize| u16::from_le_bytes([block[n * 2], block[n * 2 + 1]]);
+        if word(Self::FNUM) == 0 { return Ok(None) }
+        let computed = (0..HEADER_SIZE / 2 - 1).fold(0u16, |sum, n| sum.wrapping_add(word(n)));
+        let stored = word(HEADER_SIZE / 2 - 1);
+        if stored != computed { return Err(HeaderError::Checksum { stored, computed }) }
+        if word(Self::FLEV) != STRUCTURE_LEVEL { return Err(HeaderError::StructureLevel(word(Self::FLEV))) }
+        let ident_offset = block[0];
+        if ident_offset < Self::HEADER_WORDS || ident_offset as usize + Self::IDENT_WORDS > HEADER_SIZE / 2 - 1 {
+            return Err(HeaderError::IdentOffset(ident_offset))
+        }
+        let i = ident_offset as usize;
+        Ok(Some(Header {
+            file_number: word(Self::FNUM),
+            sequence: word(Self::FSEQ),
+            owner: Uic::from(word(Self::FOWN)),
+            protection: word(Self::FPRO),
+            name: FileName::from_words([word(i), word(i + 1), word(i + 2), word(i + 3), word(i + 4)]),
+            revision: word(i + 5),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn round_trip() {
+        for name in ["A.", "ABCDEFGHI.XYZ;1", ".SYS;77777", "X.Y;177777", "."] {
+            let f: FileName = name.parse().unwrap();
+            assert_eq!(f.to_string(), name);
+            assert_eq!(FileName::from_words(f.to_words()), f);
+        }
+        assert_eq!("abc.def;".parse::<FileName>().unwrap().to_string(), "ABC.DEF");
+        assert_eq!("".parse::<FileName>(), Ok(FileName::default()));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!("A.B;200000".parse::<FileName>(), Err(Error::IllegalChar { char: '0', pos: 10 }));
+        assert_eq!("A.B;1;2".parse::<FileName>(), Err(Error::IllegalChar { char: ';', pos: 6 }));
+        assert_eq!("A;B.C".parse::<FileName>(), Err(Error::IllegalChar { char: 'B', pos: 3 }));
+        assert_eq!("A.TEXT".parse::<FileName>(), Err(Error::InputTooLong { len: 4, max: 3 }));
+    }
+
+    fn header(words: &[u16]) -> [u8; HEADER_SIZE] {
+        let mut block = [0; HEADER_SIZE];
+        for (w, b) in words.iter().zip(block.chunks_exact_mut(2)) { b.copy_from_slice(&w.to_le_bytes()) }
+        let sum = words.iter().fold(0u16, |s, w| s.wrapping_add(*w));
+        block[HEADER_SIZE - 2..].copy_from_slice(&sum.to_le_bytes());
+        block
+    }
+
+    #[test]
+    fn headers() {
+        let name: FileName = "INDEXF.SYS;1".parse().unwrap();
+        let mut words = vec![0x1e1e, 1, 7, STRUCTURE_LEVEL, 0o10001, 0o164000];
+        words.resize(30, 0);
+        words.extend(name.to_words());
+        words.push(2);
+        assert_eq!(Header::parse(&header(&words)), Ok(Some(Header { file_number: 1, sequence: 7, owner: Uic { group: 0o20, member: 1 },
+                                                                    protection: 0o164000, name, revision: 2 })));
+        words[3] = 0o402;
+        assert_eq!(Header::parse(&header(&words)), Err(HeaderError::StructureLevel(0o402)));
+        words[3] = STRUCTURE_LEVEL;
+        words[0] = 0x1e00 | 250;
+        assert_eq!(Header::parse(&header(&words)), Err(HeaderError::IdentOffset(250)));
+        words[0] = 0x1e00 | 10;
+        assert_eq!(Header::parse(&header(&words)), Err(HeaderError::IdentOffset(10)));
+    }
+}
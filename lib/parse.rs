@@ -0,0 +1,198 @@
+// Parsing of numeric word values
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Parsing numeric word values from text.
+//!
+//! [`parse_word`] reads the flexible syntax the cli takes (`0x`/`0o`/`0b` prefixes, `_` separators), and
+//! [`lenient_parse`] reads numbers from OCR'd text.
+
+use alloc::{vec, vec::Vec};
+
+/// Errors from parsing numbers.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum ParseError {
+    /// There were no digits.
+    Empty,
+    /// `char` (at 1-based position `pos`) isn't a digit in the requested radix (and couldn't be corrected).
+    InvalidDigit { char: char, pos: usize },
+    /// The number doesn't fit in a u64.
+    Overflow,
+}
+
+impl core::error::Error for ParseError {
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParseError::Empty                    => write!(f, "No digits"),
+            ParseError::InvalidDigit {char, pos} => write!(f, "Invalid digit '{}' at position {}", char, pos),
+            ParseError::Overflow                 => write!(f, "Number too big"),
+        }
+    }
+}
+
+/// A change [`lenient_parse`] made to its input.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Correction {
+    /// The 1-based position of the character in the input.
+    pub pos: usize,
+    /// The character in the input.
+    pub found: char,
+    /// What it was replaced with (or `None` if it was dropped).
+    pub replacement: Option<char>,
+}
+
+// Common OCR misreadings of digits
+const OCR_DIGITS: [(char, char); 12] = [('O', '0'), ('o', '0'), ('Q', '0'), ('D', '0'),
+                                        ('l', '1'), ('I', '1'), ('i', '1'), ('|', '1'),
+                                        ('Z', '2'), ('z', '2'), ('S', '5'), ('B', '8')];
+
+/// Parse a number from OCR'd text (a scanned listing, say), correcting common misreadings.
+///
+/// Letters that OCR commonly confuses with digits (`O`→`0`, `l`/`I`→`1`, `S`→`5`, `B`→`8`, etc.) are replaced,
+/// but only when they aren't valid digits in `radix` already (so `B` stays `B` in hex). Spaces inside the number
+/// are dropped. Leading and trailing whitespace is ignored (and isn't reported as a correction).
+///
+/// Returns the value along with every [`Correction`] that was applied so that the caller can flag the number
+/// for review.
+///
+/// # Panics
+/// If `radix` isn't between 2 and 36.
+///
+/// # Examples
+/// ```
+/// # use radix50::parse::{Correction, ParseError, lenient_parse};
+/// assert_eq!(lenient_parse("003223", 8), Ok((0o3223, vec![])));
+/// assert_eq!(lenient_parse(" OO32 2l ", 8), Ok((0o3221, vec![Correction { pos: 2, found: 'O', replacement: Some('0') },
+///                                                           Correction { pos: 3, found: 'O', replacement: Some('0') },
+///                                                           Correction { pos: 6, found: ' ', replacement: None },
+///                                                           Correction { pos: 8, found: 'l', replacement: Some('1') }])));
+/// assert_eq!(lenient_parse("7B", 16), Ok((0x7b, vec![])));
+/// assert_eq!(lenient_parse("7B", 10), Ok((78, vec![Correction { pos: 2, found: 'B', replacement: Some('8') }])));
+/// assert_eq!(lenient_parse("0o17", 8), Ok((0o17, vec![Correction { pos: 2, found: 'o', replacement: Some('0') }])));
+/// assert_eq!(lenient_parse("12X4", 8), Err(ParseError::InvalidDigit { char: 'X', pos: 3 }));
+/// assert_eq!(lenient_parse("8", 8), Err(ParseError::InvalidDigit { char: '8', pos: 1 }));
+/// ```
+pub fn lenient_parse(s: &str, radix: u32) -> Result<(u64, Vec<Correction>), ParseError> {
+    assert!((2..=36).contains(&radix), "radix must be between 2 and 36");
+    let leading = s.chars().take_while(|c| c.is_whitespace()).count();
+    let mut value: u64 = 0;
+    let mut digits = 0;
+    let mut corrections = vec![];
+    for (i, c) in s.chars().enumerate().skip(leading).take(s.trim().chars().count()) {
+        let pos = i + 1;
+        let digit = match c.to_digit(radix) {
+            Some(d) => d,
+            None if c.is_whitespace() => { corrections.push(Correction { pos, found: c, replacement: None }); continue },
+            None => {
+                let fixed = OCR_DIGITS.iter().find(|(from, _)| *from == c).map(|(_, to)| *to);
+                match fixed.and_then(|f| f.to_digit(radix).map(|d| (f, d))) {
+                    Some((f, d)) => { corrections.push(Correction { pos, found: c, replacement: Some(f) }); d },
+                    None => return Err(ParseError::InvalidDigit { char: c, pos }),
+                }
+            },
+        };
+        value = value.checked_mul(radix as u64).and_then(|v| v.checked_add(digit as u64)).ok_or(ParseError::Overflow)?;
+        digits += 1;
+    }
+    if digits == 0 { return Err(ParseError::Empty) }
+    Ok((value, corrections))
+}
+
+/// Parse a word value: a number in `radix` (2 to 36), or in hex, octal, or binary with a `0x`, `0o`, or `0b`
+/// prefix. Underscores between the digits are ignored (`0o177_777`), as are surrounding whitespace and a trailing
+/// comma, so values pasted from a list work.
+///
+/// # Panics
+/// If `radix` isn't between 2 and 36.
+///
+/// # Examples
+/// ```
+/// # use radix50::parse::{ParseError, parse_word};
+/// assert_eq!(parse_word("123", 10), Ok(123));
+/// assert_eq!(parse_word("123", 8), Ok(0o123));
+/// assert_eq!(parse_word("0x7b", 8), Ok(123));
+/// assert_eq!(parse_word("0o1_7_3,", 10), Ok(123));
+/// assert_eq!(parse_word(" 0b1111011 ", 10), Ok(123));
+/// assert_eq!(parse_word("0x", 10), Err(ParseError::Empty));
+/// assert_eq!(parse_word("0o78", 10), Err(ParseError::InvalidDigit { char: '8', pos: 4 }));
+/// ```
+pub fn parse_word(s: &str, radix: u32) -> Result<u64, ParseError> {
+    assert!((2..=36).contains(&radix), "radix must be between 2 and 36");
+    let trimmed = s.trim_end();
+    let body = trimmed.strip_suffix(',').unwrap_or(trimmed);
+    let leading = s.chars().count() - s.trim_start().chars().count();
+    let body = body.trim_start();
+    let (radix, digits) = match body.get(..2) {
+        Some("0x" | "0X") => (16, &body[2..]),
+        Some("0o" | "0O") => (8,  &body[2..]),
+        Some("0b" | "0B") => (2,  &body[2..]),
+        _                 => (radix, body),
+    };
+    let skipped = leading + body.len() - digits.len();
+    let mut value: u64 = 0;
+    let mut any = false;
+    for (i, c) in digits.chars().enumerate() {
+        if c == '_' { continue }
+        let digit = c.to_digit(radix).ok_or(ParseError::InvalidDigit { char: c, pos: skipped + i + 1 })?;
+        value = value.checked_mul(radix as u64).and_then(|v| v.checked_add(digit as u64)).ok_or(ParseError::Overflow)?;
+        any = true;
+    }
+    if !any { return Err(ParseError::Empty) }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_input() {
+        assert_eq!(lenient_parse("0",      8), Ok((0, vec![])));
+        assert_eq!(lenient_parse("177777", 8), Ok((0o177777, vec![])));
+        assert_eq!(lenient_parse("\t63999\n", 10), Ok((63999, vec![])));
+        assert_eq!(lenient_parse("ffff",  16), Ok((0xffff, vec![])));
+        assert_eq!(lenient_parse("1011",   2), Ok((0b1011, vec![])));
+    }
+
+    #[test]
+    fn corrections() {
+        let fix = |pos, found, to| Correction { pos, found, replacement: Some(to) };
+        assert_eq!(lenient_parse("I7", 8),   Ok((0o17, vec![fix(1, 'I', '1')])));
+        assert_eq!(lenient_parse("|OQ", 8),  Ok((0o100, vec![fix(1, '|', '1'), fix(2, 'O', '0'), fix(3, 'Q', '0')])));
+        assert_eq!(lenient_parse("S5", 10),  Ok((55, vec![fix(1, 'S', '5')])));
+        assert_eq!(lenient_parse("S5", 8),   Ok((0o55, vec![fix(1, 'S', '5')])));
+        assert_eq!(lenient_parse("D", 16),   Ok((0xd, vec![])));
+        assert_eq!(lenient_parse("1 2 3", 8), Ok((0o123, vec![Correction { pos: 2, found: ' ', replacement: None },
+                                                             Correction { pos: 4, found: ' ', replacement: None }])));
+    }
+
+    #[test]
+    fn words() {
+        assert_eq!(parse_word("0",          10), Ok(0));
+        assert_eq!(parse_word("177_777",     8), Ok(0o177777));
+        assert_eq!(parse_word("0XFF",       10), Ok(0xff));
+        assert_eq!(parse_word("ff,",        16), Ok(0xff));
+        assert_eq!(parse_word("\t1_000\n",  10), Ok(1000));
+        assert_eq!(parse_word("0b",         10), Err(ParseError::Empty));
+        assert_eq!(parse_word(",",          10), Err(ParseError::Empty));
+        assert_eq!(parse_word("_",          10), Err(ParseError::Empty));
+        assert_eq!(parse_word("1,,",        10), Err(ParseError::InvalidDigit { char: ',', pos: 2 }));
+        assert_eq!(parse_word("  12a",      10), Err(ParseError::InvalidDigit { char: 'a', pos: 5 }));
+        assert_eq!(parse_word("-1",         10), Err(ParseError::InvalidDigit { char: '-', pos: 1 }));
+        assert_eq!(parse_word("0x1_0000_0000_0000_0000", 10), Err(ParseError::Overflow));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(lenient_parse("",    8), Err(ParseError::Empty));
+        assert_eq!(lenient_parse("   ", 8), Err(ParseError::Empty));
+        assert_eq!(lenient_parse("B",   8), Err(ParseError::InvalidDigit { char: 'B', pos: 1 })); // 8 isn't octal
+        assert_eq!(lenient_parse("12-", 8), Err(ParseError::InvalidDigit { char: '-', pos: 3 }));
+        assert_eq!(lenient_parse("18446744073709551615", 10), Ok((u64::MAX, vec![])));
+        assert_eq!(lenient_parse("18446744073709551616", 10), Err(ParseError::Overflow));
+    }
+}
@@ -0,0 +1,84 @@
+// Patching RADIX-50 encoded names in binary data
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Rewriting RADIX-50 encoded names in place in binary data (program images, disk images, object files), to
+//! rename a symbol or a device without reassembling anything.
+
+use alloc::{format, vec::Vec};
+
+use crate::{Encoding, Endian, Error, search::find_symbol};
+
+/// Replace every occurrence of `old` in `image` with `new`, both RADIX-50 encoded with `encoding` and stored with
+/// `endian` byte order. Returns the byte offsets that were patched, in order.
+///
+/// Occurrences are found with [`find_symbol`], so everything it says about padding and alignment applies here.
+/// Matches that overlap one that was already patched are skipped.
+///
+/// The patch can't change the size of anything, so `new` is space padded to the encoded length of `old` (a whole
+/// number of words). It returns an [`Error::InputTooLong`] if `new` is longer than that, or an
+/// [`Error::IllegalChar`] if either name can't be encoded. `image` isn't changed if there's an error.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Encoding, Endian, patch::replace_symbol, pdp11};
+/// // Point a program at DU: instead of DK:
+/// let mut image = [0, 0, 0xb8, 0x1a, 0, 0, 0xb8, 0x1a];
+/// assert_eq!(replace_symbol(&mut image, "DK", "DU", Encoding::Pdp11, Endian::Little)?, [2, 6]);
+/// assert_eq!(image[2..4], pdp11::encode_word("DU").unwrap().to_le_bytes());
+/// # Ok::<(), radix50::Error>(())
+/// ```
+pub fn replace_symbol(image: &mut [u8], old: &str, new: &str, encoding: Encoding, endian: Endian) -> Result<Vec<usize>, Error> {
+    let max = crate::encode(encoding, old)?.len() * encoding.chars_per_word();
+    let len = new.chars().count();
+    let words = crate::encode(encoding, &format!("{:max$}", new))?;
+    if len > max { return Err(Error::InputTooLong { len, max }) }
+    let mut replacement = Vec::new();
+    for w in words { crate::bytes::write_word(w, encoding.word_bytes(), endian, &mut replacement) }
+
+    let mut patched: Vec<usize> = Vec::new();
+    for at in find_symbol(image, old, encoding, endian)? {
+        if patched.last().is_some_and(|&last| at < last + replacement.len()) { continue }
+        image[at..at + replacement.len()].copy_from_slice(&replacement);
+        patched.push(at);
+    }
+    Ok(patched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pdp10, pdp11};
+    use alloc::vec;
+
+    #[test]
+    fn replace() {
+        let [a, b] = pdp11::encode_symbol("PRINTF").unwrap();
+        let mut image = vec![0xff];
+        image.extend([a, b, 0, a, b].iter().flat_map(|w| w.to_le_bytes()));
+        assert_eq!(replace_symbol(&mut image, "PRINTF", "PUTS", Encoding::Pdp11, Endian::Little), Ok(vec![1, 7]));
+        assert_eq!(find_symbol(&image, "PUTS  ", Encoding::Pdp11, Endian::Little), Ok(vec![1, 7]));
+        assert_eq!(find_symbol(&image, "PRINTF", Encoding::Pdp11, Endian::Little), Ok(vec![]));
+
+        let mut image: Vec<u8> = [pdp10::encode_word("FOOBAR").unwrap(); 2].iter().flat_map(|w| w.to_be_bytes()).collect();
+        assert_eq!(replace_symbol(&mut image, "FOOBAR", "BAZ", Encoding::Pdp10, Endian::Big), Ok(vec![0, 4]));
+        assert_eq!(image[..4], pdp10::encode_word("BAZ").unwrap().to_be_bytes());
+        assert_eq!(replace_symbol(&mut image, "FOOBAR", "BAZ", Encoding::Pdp10, Endian::Big), Ok(vec![]));
+    }
+
+    #[test]
+    fn overlaps_and_errors() {
+        // "   " is 0, so it matches at every offset of a run of zeros.
+        let mut image = [0; 5];
+        assert_eq!(replace_symbol(&mut image, "", "X", Encoding::Pdp11, Endian::Little), Err(Error::InputTooLong { len: 1, max: 0 }));
+        assert_eq!(replace_symbol(&mut image, "   ", "A", Encoding::Pdp11, Endian::Big), Ok(vec![0, 2]));
+        assert_eq!(image, [0x06, 0x40, 0x06, 0x40, 0]);
+
+        let mut image = [0xb8, 0x1a];
+        assert_eq!(replace_symbol(&mut image, "DK", "DK01", Encoding::Pdp11, Endian::Little), Err(Error::InputTooLong { len: 4, max: 3 }));
+        assert_eq!(replace_symbol(&mut image, "DK", "dk", Encoding::Pdp11, Endian::Little), Err(Error::IllegalChar { char: 'd', pos: 1 }));
+        assert_eq!(replace_symbol(&mut image, "dk", "DK", Encoding::Pdp11, Endian::Little), Err(Error::IllegalChar { char: 'd', pos: 1 }));
+        assert_eq!(image, [0xb8, 0x1a]);
+    }
+}
@@ -0,0 +1,193 @@
+// RADIX-50 for the 18 bit machines (PDP-9, PDP-15)
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! RADIX-50 on DEC's 18 bit machines (PDP-9, PDP-15): 3 characters per 18 bit word.
+//!
+//! The characters are packed just like the [PDP-11's](crate::pdp11::RADIX50_DECODE), but the words are 18 bits
+//! wide. They're stored in `u32`s, and the decoding functions in this module ignore everything above the low 18
+//! bits (see [`WORD_MASK`]), so words pulled out of wider values (a half of a 36 bit word, say) don't need to be
+//! cleaned up first. Use the [`Pdp15`] [`Codec`] directly if you want the extra bits to count against the word.
+//!
+//! # Examples
+//! ```
+//! # use radix50::r50_18bit;
+//! assert_eq!(r50_18bit::encode("THIS IS A TEST").unwrap(), [32329, 30409, 30401, 805, 31200]);
+//! assert_eq!(r50_18bit::decode([0o7000000 | 32329, 30409]), "THIS I");
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::borrow::Borrow;
+
+use crate::{Codec, Error, pdp11};
+
+/// The 18 bit RADIX-50 [`Codec`].
+///
+/// The functions in this module are shortcuts for the [`Codec`] methods on this type (with the decoders masking
+/// off the bits above the low 18).
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,Default)]
+pub struct Pdp15;
+
+impl Codec for Pdp15 {
+    type Word = u32;
+    const CHARS: usize = CHARS_PER_WORD;
+    const DECODE: [char; 40] = pdp11::RADIX50_DECODE;
+}
+
+/// How many characters are encoded in each 18 bit word.
+pub const CHARS_PER_WORD: usize = 3;
+
+/// The bits of a `u32` that make up an 18 bit word.
+pub const WORD_MASK: u32 = 0o777777;
+
+/// The largest valid 18 bit RADIX-50 word (40<sup>3</sup>-1, which decodes to `"999"`).
+pub const MAX_WORD: u32 = 63999;
+
+/// Check whether the low 18 bits of a u32 are a valid RADIX-50 word (that is, no bigger than [`MAX_WORD`]).
+///
+/// # Examples
+/// ```
+/// # use radix50::r50_18bit::{is_valid_word,MAX_WORD};
+/// assert!(is_valid_word(0));
+/// assert!(is_valid_word(MAX_WORD));
+/// assert!(is_valid_word(0o7000000 | MAX_WORD));
+/// assert!(!is_valid_word(0o200000));
+/// assert!(!is_valid_word(MAX_WORD + 1));
+/// ```
+pub const fn is_valid_word(w: u32) -> bool { w & WORD_MASK <= MAX_WORD }
+
+/// Iterate over the character set (the same as the [PDP-11's](crate::pdp11::RADIX50_DECODE)) as `(code, char)`
+/// pairs, in code order.
+///
+/// # Examples
+/// ```
+/// # use radix50::r50_18bit::charset;
+/// assert_eq!(charset().next(), Some((0, ' ')));
+/// assert_eq!(charset().count(), 40);
+/// ```
+pub fn charset() -> impl Iterator<Item = (u8, char)> { pdp11::charset() }
+
+/// Encode a string into 18 bit RADIX-50 words, space padding it to a multiple of 3 characters.
+///
+/// It will return an [`Error::IllegalChar`] if any of the characters are not in the character set.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,r50_18bit::encode};
+/// assert_eq!(encode("ABC").unwrap(), [1683]);
+/// assert_eq!(encode("ab"), Err(Error::IllegalChar { char: 'a', pos: 1 }));
+/// ```
+pub fn encode(s: &str) -> Result<Vec<u32>, Error> { Pdp15::encode(s) }
+
+/// Encode 3 characters into an 18 bit RADIX-50 word. Missing characters are assumed to be spaces.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,r50_18bit::encode_word};
+/// assert_eq!(encode_word("ABC").unwrap(), 1683);
+/// assert_eq!(encode_word("A").unwrap(), encode_word("A  ").unwrap());
+/// assert_eq!(encode_word("A-"), Err(Error::IllegalChar { char: '-', pos: 2 }));
+/// ```
+pub fn encode_word(s: &str) -> Result<u32, Error> { Pdp15::encode_word(s) }
+
+/// Encode a string, encoding illegal characters as spaces and returning an [`Error::IllegalChar`] for each one
+/// along with the words.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,r50_18bit::{encode,encode_lossy}};
+/// assert_eq!(encode_lossy("A-B"), (encode("A B").unwrap(), vec![Error::IllegalChar { char: '-', pos: 2 }]));
+/// ```
+pub fn encode_lossy(s: &str) -> (Vec<u32>, Vec<Error>) { Pdp15::encode_lossy(s) }
+
+/// Check a string, returning an [`Error::IllegalChar`] for every character that isn't in the character set.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,r50_18bit::validate};
+/// assert_eq!(validate("PDP.15"), Ok(()));
+/// assert_eq!(validate("pdp-15"), Err(vec![Error::IllegalChar { char: 'p', pos: 1 },
+///                                         Error::IllegalChar { char: 'd', pos: 2 },
+///                                         Error::IllegalChar { char: 'p', pos: 3 },
+///                                         Error::IllegalChar { char: '-', pos: 4 }]));
+/// ```
+pub fn validate(s: &str) -> Result<(), Vec<Error>> { Pdp15::validate(s) }
+
+/// Decode a sequence of 18 bit RADIX-50 words into a string, ignoring bits above the low 18.
+///
+/// # Examples
+/// ```
+/// # use radix50::r50_18bit::decode;
+/// assert_eq!(decode([32329, 30409, 30401, 805, 31200]), "THIS IS A TEST ");
+/// assert_eq!(decode([0o1000000 | 1683]), "ABC");
+/// ```
+pub fn decode<I>(words: I) -> String where I: IntoIterator, I::Item: Borrow<u32> { Pdp15::decode(masked(words)) }
+
+/// Decode a sequence of 18 bit RADIX-50 words into a string, ignoring bits above the low 18 but checking that
+/// what's left is in range. It returns an [`Error::WordOutOfRange`] (with the word as given) for the first one
+/// that isn't.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,r50_18bit::decode_checked};
+/// assert_eq!(decode_checked([0o1000000 | 1683]), Ok("ABC".to_string()));
+/// assert_eq!(decode_checked([1683, 0o764000]), Err(Error::WordOutOfRange { word: 0o764000, index: 1 }));
+/// ```
+pub fn decode_checked<I>(words: I) -> Result<String, Error> where I: IntoIterator, I::Item: Borrow<u32> {
+    let words: Vec<u32> = words.into_iter().map(|w| *w.borrow()).collect();
+    match Pdp15::decode_checked(masked(&words)) {
+        Err(Error::WordOutOfRange { index, .. }) => Err(Error::WordOutOfRange { word: words[index] as u64, index }),
+        result                                   => result,
+    }
+}
+
+/// Decode an 18 bit RADIX-50 word into a 3 character string, ignoring bits above the low 18.
+///
+/// # Examples
+/// ```
+/// # use radix50::r50_18bit::decode_word;
+/// assert_eq!(decode_word(1683), "ABC");
+/// assert_eq!(decode_word(0o3000000 | 1683), "ABC");
+/// ```
+pub fn decode_word(word: u32) -> String { Pdp15::decode_word(word & WORD_MASK) }
+
+/// Decode an 18 bit RADIX-50 word into an array of 3 characters, ignoring bits above the low 18.
+///
+/// # Examples
+/// ```
+/// # use radix50::r50_18bit::decode_word_chars;
+/// assert_eq!(decode_word_chars(1683), ['A', 'B', 'C']);
+/// ```
+pub fn decode_word_chars(word: u32) -> [char; 3] { pdp11::decode_word_chars((word & WORD_MASK) as u16) }
+
+fn masked<I>(words: I) -> impl Iterator<Item = u32> where I: IntoIterator, I::Item: Borrow<u32> {
+    words.into_iter().map(|w| *w.borrow() & WORD_MASK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "pdp10")]
+    #[test]
+    fn matches_pdp11() {
+        for golden in crate::testdata::CORPUS {
+            let words = encode(golden.text).unwrap();
+            assert!(words.iter().zip(golden.pdp11).all(|(&a, &b)| a == b as u32));
+            assert_eq!(words.len(), golden.pdp11.len());
+            let wide: Vec<u32> = words.iter().map(|w| w | 0o77000000).collect();
+            assert_eq!(decode(&wide), pdp11::decode(golden.pdp11));
+            assert_eq!(decode_checked(&wide), Ok(pdp11::decode(golden.pdp11)));
+        }
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(decode_word_chars(MAX_WORD), ['9', '9', '9']);
+        assert!(!Pdp15::is_valid_word(0o1000000));
+        assert!(!is_valid_word(0o200000));
+        assert_eq!(decode_checked([64000]), Err(Error::WordOutOfRange { word: 64000, index: 0 }));
+        assert_eq!(decode_checked([0o1000000]), Ok("   ".into()));
+    }
+}
@@ -5,6 +5,10 @@
 
 // To update README: `cargo rdme` (`cargo install rdme` if not installed)
 #![allow(rustdoc::redundant_explicit_links)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+// The only unsafe code is mapping the file in convert::decode_file(), and it's opt in.
+#![cfg_attr(not(feature = "mmap"), forbid(unsafe_code))]
+#![cfg_attr(feature = "mmap", deny(unsafe_code))]
 //! radix50
 //! =======
 //!
@@ -26,6 +30,7 @@
 //! ### Example
 //!
 //! ```rust
+//! # #[cfg(all(feature = "pdp10", feature = "pdp11"))] {
 //! use radix50::{pdp10,pdp11};
 //!
 //! let pdp10_encoded = pdp10::encode("THIS IS A TEST").unwrap();
@@ -39,8 +44,65 @@
 //!
 //! assert_eq!(pdp10_decoded, "THIS IS A TEST    ");
 //! assert_eq!(pdp11_decoded, "THIS IS A TEST ");
+//! # }
 //! ```
 //!
+//! Compile Time Literals
+//! ---------------------
+//!
+//! The `radix50-macros` crate has `rad50!("THIS IS A TEST")` and `rad50_pdp10!("THIS IS A TEST")` macros, which
+//! encode string literals into `[u16; N]`/`[u32; N]` arrays at compile time. Strings that can't be encoded are
+//! compile errors.
+//!
+//! Stability
+//! ---------
+//!
+//! RADIX-50 is an archival format, so the encoded output for any given input is guaranteed to stay the same
+//! (bit for bit) across releases. This is enforced by golden tests over a canonical corpus, which is available
+//! to downstream crates as `radix50::testdata` when the `testdata` feature is enabled.
+//!
+//! no_std
+//! ------
+//!
+//! The `std` feature is on by default. Without it the crate is `#![no_std]`, though it still needs `alloc` (encoding
+//! and decoding return `Vec`s and `String`s):
+//!
+//! ```toml
+//! [dependencies]
+//! radix50 = { version = "0.2", default-features = false }
+//! ```
+//!
+//! The `convert` module (which reads and writes files) needs `std`, as do the `binrw` and `miette` features.
+//! Completely heapless code can use the `_into_slice` functions (like [`pdp11::encode_into_slice`]), which work
+//! with caller supplied buffers.
+//!
+//! Optional Features
+//! -----------------
+//!
+//! - `pdp10`, `pdp11` (on by default): The two codecs. Size constrained builds can turn off the one they don't
+//!   need (with `default-features = false`). The runtime `Encoding` API and the `convert` module need both.
+//! - `arbitrary`: [`Arbitrary`](https://docs.rs/arbitrary) impls for the word, symbol and string types that only
+//!   generate valid RADIX-50 data, for fuzzers and property tests.
+//! - `arrayvec`, `heapless`: [`Codec`] methods that encode and decode into fixed capacity `ArrayVec`/`ArrayString`
+//!   and `heapless::Vec`/`heapless::String` buffers, for targets without a heap.
+//! - `binrw`: [`BinRead`/`BinWrite`](https://docs.rs/binrw) impls for the word and symbol types, so RADIX-50
+//!   fields can be embedded in binrw structure definitions.
+//! - `defmt`: [`defmt::Format`](https://docs.rs/defmt) impls for `Error` and the word and symbol types, for logging
+//!   on embedded targets.
+//! - `miette`: A [`miette::Diagnostic`](https://docs.rs/miette) impl for `Error`, and `Error::with_source()`,
+//!   which attaches the source text so the report labels the offending character.
+//! - `decode-table`: Decode with a precomputed 192KB table of every word's character codes instead of dividing.
+//!   It's faster when decoding lots of words (like whole disk images), at the cost of binary size.
+//! - `simd`: Makes [`Codec::decode_bulk`] (and [`pdp11::decode_bulk`], etc.) decode 8 words at a time with SIMD
+//!   arithmetic, using [`wide`](https://docs.rs/wide) so it works on stable.
+//! - `rayon`: `Codec::par_encode()` and `Codec::par_decode()` (and `pdp11::par_encode()`, etc.), which split big
+//!   inputs into chunks and convert them in parallel with [rayon](https://docs.rs/rayon).
+//! - `tokio`: The `async_io` module, with [tokio](https://docs.rs/tokio) `AsyncRead`/`AsyncWrite` adapters that
+//!   decode and encode streams of words without blocking.
+//! - `mmap`: `convert::decode_file()`, which decodes a raw word file from a memory map instead of reading it all
+//!   into memory. This is the only feature that brings in any `unsafe` code.
+//! - `bytemuck`, `zerocopy`: Lets slices of words and symbols be reinterpreted from bytes without copying.
+//!
 //! Documentation Shortcuts
 //! -----------------------
 //! - PDP-10 [Encodings](crate::pdp10::RADIX50_DECODE)
@@ -48,39 +110,180 @@
 //!   - [`pdp10::encode_word`](crate::pdp10::encode_word)
 //!   - [`pdp10::decode`](crate::pdp10::decode)
 //!   - [`pdp10::decode_word`](crate::pdp10::decode_word)
+//!   - [`rel10::modules`](crate::rel10::modules)
+//!   - [`exe10::Exe`](crate::exe10::Exe)
+//!   - [`ddt::Table`](crate::ddt::Table)
+//!   - [`macro10::parse_literal`](crate::macro10::parse_literal)
 //! - PDP-11 [Encodings](crate::pdp11::RADIX50_DECODE)
 //!   - [`pdp11::encode`](crate::pdp11::encode)
 //!   - [`pdp11::encode_word`](crate::pdp11::encode_word)
 //!   - [`pdp11::decode`](crate::pdp11::decode)
 //!   - [`pdp11::decode_word`](crate::pdp11::decode_word)
+//!   - [`rt11::encode_filename`](crate::rt11::encode_filename)
+//!   - [`rt11::decode_filename`](crate::rt11::decode_filename)
+//!   - [`ods1::FileName`](crate::ods1::FileName)
+//!   - [`dos11::Uic`](crate::dos11::Uic)
+//!   - [`dos11::tape_files`](crate::dos11::tape_files)
+//!   - [`dectape::dos11_files`](crate::dectape::dos11_files)
+//!   - [`rsts::Ppn`](crate::rsts::Ppn)
+//!   - [`xxdp::entries`](crate::xxdp::entries)
+//!   - [`obj11::gsd`](crate::obj11::gsd)
+//!   - [`obj11::write_gsd`](crate::obj11::write_gsd)
+//!   - [`stb::SymbolTable`](crate::stb::SymbolTable)
+//!   - [`tsk::Label`](crate::tsk::Label)
+//!   - [`lda::load`](crate::lda::load)
+//!   - [`macro11::expressions`](crate::macro11::expressions)
+//! - PDP-9/PDP-15 [18 bit RADIX-50](crate::r50_18bit)
+//!   - [`r50_18bit::encode`](crate::r50_18bit::encode)
+//!   - [`r50_18bit::decode`](crate::r50_18bit::decode)
+//! - DEC [SIXBIT](crate::sixbit) (not RADIX-50, but it's often found alongside it)
+//!   - [`sixbit::encode`](crate::sixbit::encode)
+//!   - [`sixbit::decode`](crate::sixbit::decode)
+//! - [PDP-10 symbol words](crate::symbol10) (PDP-10 RADIX-50 with flag bits, as found in symbol tables)
+//!   - [`symbol10::encode_symbol`](crate::symbol10::encode_symbol)
+//!   - [`symbol10::decode_symbol`](crate::symbol10::decode_symbol)
+//! - [SIMH tape images](crate::tap) (for getting at the files on old tapes)
+//!   - [`tap::items`](crate::tap::items)
+//! - [Packing words into bytes](crate::bytes) (byte orders, and the 36 bit PDP-10 packings)
+//!   - [`bytes::Packing`](crate::bytes::Packing)
+//! - [Recognizing file formats](crate::formats) (which parser to hand a file to)
+//!   - [`formats::detect`](crate::formats::detect)
+//! - [Streaming results](crate::report) out of the scanner and format walkers
+//!   - [`report::ReportSink`](crate::report::ReportSink)
+
+extern crate alloc;
 
-use std::str::Chars;
+use alloc::{format, string::{String, ToString}, vec, vec::Vec};
+use core::borrow::Borrow;
 
 use const_for::const_for;
 
+#[cfg(feature = "tokio")]
+pub mod async_io;
+pub mod bytes;
+#[cfg(feature = "pdp11")]
+mod code;
+#[cfg(feature = "pdp11")]
+pub mod consts;
+#[cfg(all(feature = "std", feature = "pdp10", feature = "pdp11"))]
+pub mod convert;
+mod custom;
+#[cfg(feature = "pdp10")]
+pub mod ddt;
+#[cfg(feature = "pdp11")]
+pub mod dectape;
+pub mod diff;
+mod display;
+#[cfg(feature = "pdp11")]
+pub mod dos11;
+mod encoder;
+#[cfg(feature = "pdp10")]
+pub mod exe10;
+mod ext;
+#[cfg(all(feature = "pdp10", feature = "pdp11"))]
+pub mod formats;
+mod inline;
+#[cfg(feature = "pdp10")]
+pub mod macro10;
+#[cfg(feature = "pdp11")]
+pub mod lda;
+#[cfg(feature = "pdp11")]
+pub mod macro11;
+#[cfg(feature = "pdp11")]
+pub mod obj11;
+#[cfg(feature = "pdp11")]
+pub mod ods1;
+pub mod parse;
+#[cfg(all(feature = "pdp10", feature = "pdp11"))]
+pub mod patch;
+#[cfg(feature = "pdp11")]
+pub mod r50_18bit;
+#[cfg(feature = "pdp10")]
+pub mod rel10;
+pub mod report;
+#[cfg(feature = "pdp11")]
+pub mod rsts;
+#[cfg(feature = "pdp11")]
+pub mod rt11;
+#[cfg(all(feature = "pdp10", feature = "pdp11"))]
+pub mod scan;
+#[cfg(all(feature = "pdp10", feature = "pdp11"))]
+pub mod search;
+pub mod sixbit;
+#[cfg(feature = "pdp11")]
+pub mod stb;
+pub mod tap;
+#[cfg(all(any(test, feature = "testdata"), feature = "pdp10", feature = "pdp11"))]
+pub mod testdata;
+#[cfg(feature = "pdp11")]
+pub mod tsk;
+mod string;
+#[cfg(feature = "pdp11")]
+mod symbol;
+#[cfg(feature = "pdp10")]
+pub mod symbol10;
+mod word;
+#[cfg(feature = "pdp11")]
+pub mod xxdp;
+
+#[cfg(feature = "pdp11")]
+pub use code::Code;
+pub use custom::CustomCodec;
+pub use display::Rad50Display;
+pub use encoder::Encoder;
+pub use ext::{FromRadix50, ToRadix50};
+pub use inline::InlineStr;
+pub use string::Rad50String;
+#[cfg(feature = "pdp11")]
+pub use symbol::Symbol6;
+pub use word::Rad50Word;
+
+#[cfg(not(any(feature = "pdp10", feature = "pdp11")))]
+compile_error!("At least one of the `pdp10` and `pdp11` features must be enabled");
+
+// The codec that the generic types use when one isn't given.
+#[cfg(feature = "pdp11")]
+type DefaultCodec = pdp11::Pdp11;
+#[cfg(not(feature = "pdp11"))]
+type DefaultCodec = pdp10::Pdp10;
+
 // https://en.wikipedia.org/wiki/DEC_RADIX_50
 
+#[cfg(feature = "pdp10")]
 pub mod pdp10 {
-    use super::{Error,GenericCodec};
+    use alloc::{string::String, vec::Vec};
+    use core::borrow::Borrow;
+    use super::{Codec,Error};
 
-    struct Codec {}
+    /// The [PDP-10 RADIX-50][`RADIX50_DECODE`] [`Codec`].
+    ///
+    /// The functions in this module are shortcuts for the [`Codec`] methods on this type.
+    #[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,Default)]
+    pub struct Pdp10;
 
-    impl GenericCodec for Codec {
+    impl Codec for Pdp10 {
         type Word = u32;
-        const CHARS: usize = 6;
-        const ENCODE: [Option<u8>; 128] = RADIX50_ENCODE;
+        const CHARS: usize = CHARS_PER_WORD;
         const DECODE: [char; 40] = RADIX50_DECODE;
+        const ENCODE: [Option<u8>; 128] = RADIX50_ENCODE;
+    }
 
-        fn encode_word(s: &str) -> Result<Self::Word, Error> {
-            let mut it = s.chars();
-            let w: Self::Word = Self::encode16(&mut it, 0)? as u32 * 40*40*40 + Self::encode16(&mut it, 3)? as u32;
-            Ok(w)
-        }
+    /// How many characters are encoded in each u32 word.
+    pub const CHARS_PER_WORD: usize = 6;
 
-        fn decode_word(w: Self::Word) -> String {
-            Self::decode16((w/(40*40*40)) as u16) + &Self::decode16((w % (40*40*40)) as u16)
-        }
-    }
+    /// The largest valid PDP-10 RADIX-50 word (40<sup>6</sup>-1, which decodes to `"%%%%%%"`).
+    pub const MAX_WORD: u32 = 4_095_999_999;
+
+    /// Check whether a u32 is a valid PDP-10 RADIX-50 word (that is, it's no bigger than [`MAX_WORD`]).
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::{is_valid_word,MAX_WORD};
+    /// assert!(is_valid_word(0));
+    /// assert!(is_valid_word(MAX_WORD));
+    /// assert!(!is_valid_word(MAX_WORD + 1));
+    /// ```
+    pub const fn is_valid_word(w: u32) -> bool { w <= MAX_WORD }
 
     /// The RADIX-50 character set used on the PDP-10, PDP-6, DECsystem-10, and DECSYSTEM-20.
     ///
@@ -134,6 +337,46 @@ pub mod pdp10 {
 
     const RADIX50_ENCODE: [Option<u8>; 128] = super::invert(&RADIX50_DECODE);
 
+    /// Iterate over the [PDP-10 RADIX-50 character set][`RADIX50_DECODE`] as `(code, char)` pairs, in code order.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::charset;
+    /// let mut it = charset();
+    /// assert_eq!(it.next(), Some((0, ' ')));
+    /// assert_eq!(it.next(), Some((1, '0')));
+    /// assert_eq!(it.last(), Some((39, '%')));
+    /// ```
+    pub fn charset() -> impl Iterator<Item = (u8, char)> { super::charset(&RADIX50_DECODE) }
+
+    /// Look up the [PDP-10 RADIX-50][`RADIX50_DECODE`] code for a single character.
+    ///
+    /// Returns `None` if the character isn't part of the character set.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::char_to_code;
+    /// assert_eq!(char_to_code('A'), Some(11));
+    /// assert_eq!(char_to_code(' '), Some(0));
+    /// assert_eq!(char_to_code('a'), None);
+    /// ```
+    pub const fn char_to_code(c: char) -> Option<u8> { super::char_to_code(&RADIX50_ENCODE, c) }
+
+    /// Look up the character for a single [PDP-10 RADIX-50][`RADIX50_DECODE`] code.
+    ///
+    /// Returns `None` if the code isn't in the range 0-39.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::code_to_char;
+    /// assert_eq!(code_to_char(11), Some('A'));
+    /// assert_eq!(code_to_char(0), Some(' '));
+    /// assert_eq!(code_to_char(40), None);
+    /// ```
+    pub const fn code_to_char(code: u8) -> Option<char> { super::code_to_char(&RADIX50_DECODE, code) }
+
+    crate::assert_charset!(RADIX50_DECODE, crate::RADIX50_REPERTOIRE);
+
     /// Encode a string into [PDP-10 RADIX-50 format][`RADIX50_DECODE`].
     ///
     /// The input string will be space padded to a multiple of 6 characters before encoding. This is because
@@ -158,7 +401,23 @@ pub mod pdp10 {
     /// assert_eq!(encode("PADDING123").unwrap(),   encode("PADDING123  ").unwrap());
     /// assert_eq!(encode("PADDING").unwrap(),      encode("PADDING     ").unwrap());
     /// ```
-    pub fn encode(s: &str) -> Result<Vec<u32>, Error> { Codec::encode(s) }
+    pub fn encode(s: &str) -> Result<Vec<u32>, Error> { Pdp10::encode(s) }
+
+    /// Encode a string into [PDP-10 RADIX-50 format][`RADIX50_DECODE`], and count the padding.
+    ///
+    /// This is like [`encode`], but it also returns how many spaces were added to pad the string out to a whole
+    /// number of words. [`decode`] gives back the padding too, so `decode(encode(s))` can be longer than `s`;
+    /// [`decode_exact`] takes the count and gives back exactly `s`. Keep the count alongside the words (in a
+    /// header, say) when the original length matters.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::{decode_exact,encode,encode_exact};
+    /// let (words, pad) = encode_exact("THIS IS A TEST").unwrap();
+    /// assert_eq!((words.clone(), pad), (encode("THIS IS A TEST").unwrap(), 4));
+    /// assert_eq!(decode_exact(&words, pad), "THIS IS A TEST");
+    /// ```
+    pub fn encode_exact(s: &str) -> Result<(Vec<u32>, usize), Error> { Pdp10::encode_exact(s) }
 
     /// Encode 6 characters into a [PDP-10 RADIX-50 formatted][`RADIX50_DECODE`] word.
     ///
@@ -182,9 +441,93 @@ pub mod pdp10 {
     /// let result = encode_word("AB-");
     /// assert_eq!(result, Err(Error::IllegalChar { char: '-', pos: 3 }))
     /// ```
-    pub fn encode_word(s: &str) -> Result<u32, Error> { Codec::encode_word(s) }
+    pub fn encode_word(s: &str) -> Result<u32, Error> { Pdp10::encode_word(s) }
+
+    /// Encode a string into [PDP-10 RADIX-50 format][`RADIX50_DECODE`], carrying on past illegal characters.
+    ///
+    /// This is like [`encode`] except that instead of stopping at the first character that isn't in the
+    /// [RADIX-50 character set][`RADIX50_DECODE`], it encodes each one as a space and keeps going. It returns
+    /// the words along with an [`Error::IllegalChar`] for every character that was replaced (the same errors
+    /// [`validate`] would return). If the error list is empty then the words are exactly what [`encode`] would
+    /// have returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{Error,pdp10::{encode,encode_lossy}};
+    /// let (words, errors) = encode_lossy("THIS_IS A TEST!");
+    /// assert_eq!(words, encode("THIS IS A TEST ").unwrap());
+    /// assert_eq!(errors, [Error::IllegalChar { char: '_', pos: 5 }, Error::IllegalChar { char: '!', pos: 15 }]);
+    ///
+    /// assert_eq!(encode_lossy("THIS IS A TEST"), (encode("THIS IS A TEST").unwrap(), vec![]));
+    /// ```
+    pub fn encode_lossy(s: &str) -> (Vec<u32>, Vec<Error>) { Pdp10::encode_lossy(s) }
+
+    /// Check that every character of a string is in the [PDP-10 RADIX-50 character set][`RADIX50_DECODE`].
+    ///
+    /// Unlike [`encode`], which stops at the first bad character, this returns an [`Error::IllegalChar`] for
+    /// every bad character in the string, so they can all be reported (or highlighted) at once.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{Error,pdp10::validate};
+    /// assert_eq!(validate("THIS IS A TEST"), Ok(()));
+    /// assert_eq!(validate("THIS_IS A TEST!"), Err(vec![Error::IllegalChar { char: '_', pos: 5 },
+    ///                                                  Error::IllegalChar { char: '!', pos: 15 }]));
+    /// ```
+    pub fn validate(s: &str) -> Result<(), Vec<Error>> { Pdp10::validate(s) }
+
+    /// Compare two strings in [PDP-10 RADIX-50][`RADIX50_DECODE`] collation order.
+    ///
+    /// Characters are compared by their RADIX-50 codes instead of their ASCII values, which is the order DEC's
+    /// utilities list things in (it's the numeric order of the encoded words). The shorter string is treated as
+    /// if it were padded with spaces, so `"A"` and `"A  "` compare equal. Characters that aren't in the character
+    /// set sort after all the ones that are.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::cmp::Ordering;
+    /// # use radix50::pdp10::cmp;
+    /// assert_eq!(cmp("A", "1"), Ordering::Greater); // Digits come before letters on the PDP-10
+    /// assert_eq!(cmp("A", "$"), Ordering::Less);
+    /// assert_eq!(cmp("A", "A  "), Ordering::Equal);
+    /// ```
+    pub fn cmp(a: &str, b: &str) -> core::cmp::Ordering { Pdp10::collate(a, b) }
+
+    /// Sort strings into [PDP-10 RADIX-50][`RADIX50_DECODE`] collation order (see [`cmp`]). The sort is stable.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::sort;
+    /// let mut names = ["FOO", "BAR", "9LIVES", "$A", "A1", "A"];
+    /// sort(&mut names);
+    /// assert_eq!(names, ["9LIVES", "A", "A1", "BAR", "FOO", "$A"]);
+    /// ```
+    pub fn sort<S: AsRef<str>>(strings: &mut [S]) { strings.sort_by(|a, b| cmp(a.as_ref(), b.as_ref())) }
+
+    /// Get the symbol after some [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] words, in collation order.
+    ///
+    /// The words are treated as one big base-40 number (the last word being the least significant) and
+    /// incremented, carrying from word to word. This is handy for enumerating symbol ranges or generating
+    /// temporary labels the way old assemblers did. It returns `None` if the words are already the last symbol
+    /// (all `'%'`) or if any word is out of range. See [`prev_symbol`] for the other direction.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::{encode,decode,next_symbol,prev_symbol};
+    /// assert_eq!(decode(next_symbol(&encode("TMP").unwrap()).unwrap()), "TMP  0");
+    /// assert_eq!(decode(prev_symbol(&encode("TMP").unwrap()).unwrap()), "TMO%%%");
+    /// assert_eq!(next_symbol(&encode("%%%%%%").unwrap()), None);
+    /// ```
+    pub fn next_symbol(words: &[u32]) -> Option<Vec<u32>> { Pdp10::next_symbol(words) }
 
-    /// Decode a [`slice`] of [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] 32 bit words into a string.
+    /// Get the symbol before some [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] words, in collation order. It
+    /// returns `None` if the words are already all spaces or if any word is out of range. See [`next_symbol`].
+    pub fn prev_symbol(words: &[u32]) -> Option<Vec<u32>> { Pdp10::prev_symbol(words) }
+
+    /// Decode a sequence of [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] 32 bit words into a string.
+    ///
+    /// The words can come from anything iterable: a [`slice`], a [`std::vec::Vec`], or an iterator chain
+    /// (yielding either words or references to words), so there's no need to collect them first.
     ///
     /// The output is a String.
     ///
@@ -192,8 +535,56 @@ pub mod pdp10 {
     /// ```
     /// # use radix50::pdp10::decode;
     /// assert_eq!(decode(&[3119342419, 2970305215, 3046400000]), "THIS IS A TEST    ");
+    /// assert_eq!(decode([3119342419_u32, 2970305215, 3046400000].into_iter().take(2)), "THIS IS A TE");
+    /// ```
+    pub fn decode<I>(words: I) -> String where I: IntoIterator, I::Item: Borrow<u32> { Pdp10::decode(words) }
+
+    /// Decode a sequence of [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] words into a string, dropping the last `pad`
+    /// characters.
+    ///
+    /// `pad` is the padding count from [`encode_exact`], so this gives back exactly the string that was encoded.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::decode_exact;
+    /// assert_eq!(decode_exact([3119342419, 2970305215, 3046400000], 0), "THIS IS A TEST    ");
+    /// assert_eq!(decode_exact([3119342419, 2970305215, 3046400000], 4), "THIS IS A TEST");
+    /// ```
+    pub fn decode_exact<I>(words: I, pad: usize) -> String where I: IntoIterator, I::Item: Borrow<u32> { Pdp10::decode_exact(words, pad) }
+
+    /// Decode a sequence of [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] words into a string, checking that each
+    /// word is in range.
+    ///
+    /// Not every u32 is a valid RADIX-50 word (the largest is [`MAX_WORD`]). [`decode`] happily decodes
+    /// out-of-range words into garbage, but this returns an [`Error::WordOutOfRange`] for the first one instead.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{Error,pdp10::decode_checked};
+    /// assert_eq!(decode_checked([3119342419, 2970305215, 3046400000]), Ok("THIS IS A TEST    ".to_string()));
+    /// assert_eq!(decode_checked([3119342419, 4096000000, 3046400000, 4294967295]), Err(Error::WordOutOfRange { word: 4096000000, index: 1 }));
+    /// ```
+    pub fn decode_checked<I>(words: I) -> Result<String, Error> where I: IntoIterator, I::Item: Borrow<u32> { Pdp10::decode_checked(words) }
+
+    /// Decode a sequence of [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] words into a string, collecting every
+    /// out-of-range word.
+    ///
+    /// This is for assessing damaged data: rather than stopping at the first bad word (like [`decode_checked`])
+    /// it decodes everything and returns an [`Error::WordOutOfRange`] (with its index) for each word that was out
+    /// of range. If `marker` is given then each bad word decodes to 6 of that character, otherwise it's decoded
+    /// the same way [`decode`] would.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{Error,pdp10::decode_lossy};
+    /// let (text, errors) = decode_lossy([3119342419, 4096000000, 3046400000, 4294967295], Some('*'));
+    /// assert_eq!(text, "THIS I******ST    ******");
+    /// assert_eq!(errors, [Error::WordOutOfRange { word: 4096000000, index: 1 },
+    ///                     Error::WordOutOfRange { word: 4294967295, index: 3 }]);
+    ///
+    /// assert_eq!(decode_lossy([3119342419, 2970305215, 3046400000], None), ("THIS IS A TEST    ".to_string(), vec![]));
     /// ```
-    pub fn decode(words: &[u32]) -> String { Codec::decode(words) }
+    pub fn decode_lossy<I>(words: I, marker: Option<char>) -> (String, Vec<Error>) where I: IntoIterator, I::Item: Borrow<u32> { Pdp10::decode_lossy(words, marker) }
 
     /// Decode a [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] 32 bit word into a 6 character string.
     ///
@@ -205,29 +596,161 @@ pub mod pdp10 {
     /// assert_eq!(decode_word(3324), "   123");
     /// assert_eq!(decode_word(504456086), "3.1415");
     /// ```
-    pub fn decode_word(word: u32) -> String { Codec::decode_word(word) }
+    pub fn decode_word(word: u32) -> String { Pdp10::decode_word(word) }
+
+    /// Decode a [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] 32 bit word into an array of 6 characters.
+    ///
+    /// This is like [`decode_word`] but doesn't build a String, which is handy when you only need to compare or
+    /// copy the characters.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::decode_word_chars;
+    /// assert_eq!(decode_word_chars(3324), [' ', ' ', ' ', '1', '2', '3']);
+    /// assert_eq!(decode_word_chars(504456086), ['3', '.', '1', '4', '1', '5']);
+    /// ```
+    pub fn decode_word_chars(word: u32) -> [char; 6] {
+        let hi = super::decode16(&RADIX50_DECODE, (word / (40*40*40)) as u16);
+        let lo = super::decode16(&RADIX50_DECODE, (word % (40*40*40)) as u16);
+        [hi[0], hi[1], hi[2], lo[0], lo[1], lo[2]]
+    }
+
+    /// Decode a [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] 32 bit word into `buf`, returning it as a `&str`.
+    ///
+    /// This is like [`decode_word`] but doesn't allocate.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::decode_word_into;
+    /// let mut buf = [0; 6];
+    /// assert_eq!(decode_word_into(504456086, &mut buf), "3.1415");
+    /// ```
+    pub fn decode_word_into(word: u32, buf: &mut [u8; 6]) -> &str { Pdp10::decode_into_slice([word], buf).expect("a word fits in 6 bytes") }
+
+    /// Decode a [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] word into an [`InlineStr`](crate::InlineStr).
+    ///
+    /// This is like [`decode_word`] but returns the text by value, without allocating.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::decode_word_inline;
+    /// assert_eq!(decode_word_inline(504456086), "3.1415");
+    /// ```
+    pub fn decode_word_inline(word: u32) -> crate::InlineStr<6> { Pdp10::decode_inline([word]).expect("a word fits in 6 bytes") }
+
+    /// Encode a string into [PDP-10 RADIX-50 format][`RADIX50_DECODE`] without allocating, returning how many
+    /// words of `out` were used.
+    ///
+    /// It returns an [`Error::InputTooLong`] if `out` isn't big enough.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{Error,pdp10::encode_into_slice};
+    /// let mut words = [0; 4];
+    /// assert_eq!(encode_into_slice("THIS IS A TEST", &mut words), Ok(3));
+    /// assert_eq!(words[..3], [3119342419, 2970305215, 3046400000]);
+    /// assert_eq!(encode_into_slice("THIS IS A LONGER TEST", &mut words[..3]), Err(Error::InputTooLong { len: 21, max: 18 }));
+    /// ```
+    pub fn encode_into_slice(s: &str, out: &mut [u32]) -> Result<usize, Error> { Pdp10::encode_into_slice(s, out) }
+
+    /// Decode [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] words into `out` without allocating, returning the text.
+    ///
+    /// It returns an [`Error::InputTooLong`] if `out` isn't big enough.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{Error,pdp10::decode_into_slice};
+    /// let mut buf = [0; 32];
+    /// assert_eq!(decode_into_slice([3119342419, 2970305215, 3046400000], &mut buf), Ok("THIS IS A TEST    "));
+    /// assert_eq!(decode_into_slice([3119342419, 2970305215, 3046400000], &mut buf[..12]), Err(Error::InputTooLong { len: 18, max: 12 }));
+    /// ```
+    pub fn decode_into_slice<I>(words: I, out: &mut [u8]) -> Result<&str, Error> where I: IntoIterator, I::Item: Borrow<u32> { Pdp10::decode_into_slice(words, out) }
+
+    /// Decode [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] words straight into a [`fmt::Write`](core::fmt::Write),
+    /// without building a String.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::decode_fmt;
+    /// use std::fmt::Write;
+    /// let mut line = String::from("name: ");
+    /// decode_fmt([3119342419, 2970305215, 3046400000], &mut line).unwrap();
+    /// assert_eq!(line, "name: THIS IS A TEST    ");
+    /// ```
+    pub fn decode_fmt<I, W>(words: I, out: &mut W) -> core::fmt::Result where I: IntoIterator, I::Item: Borrow<u32>, W: core::fmt::Write + ?Sized {
+        Pdp10::decode_fmt(words, out)
+    }
+
+    /// Decode a slice of [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] words into a string, many words at a time.
+    ///
+    /// The result is the same as [`decode`]'s, but this is faster on big inputs (especially with the `simd`
+    /// feature). See [`Codec::decode_bulk`](crate::Codec::decode_bulk).
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::decode_bulk;
+    /// assert_eq!(decode_bulk(&[3119342419, 2970305215, 3046400000]), "THIS IS A TEST    ");
+    /// ```
+    pub fn decode_bulk(words: &[u32]) -> String { Pdp10::decode_bulk(words) }
+
+    /// Encode a string into [PDP-10 RADIX-50][`RADIX50_DECODE`] words, using all the cores. See
+    /// [`Codec::par_encode`](crate::Codec::par_encode).
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::par_encode;
+    /// assert_eq!(par_encode("THIS IS A TEST").unwrap(), [3119342419, 2970305215, 3046400000]);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_encode(s: &str) -> Result<Vec<u32>, Error> { Pdp10::par_encode(s) }
+
+    /// Decode a slice of [PDP-10 RADIX-50 encoded][`RADIX50_DECODE`] words, using all the cores. See
+    /// [`Codec::par_decode`](crate::Codec::par_decode).
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp10::par_decode;
+    /// assert_eq!(par_decode(&[3119342419, 2970305215, 3046400000]), "THIS IS A TEST    ");
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_decode(words: &[u32]) -> String { Pdp10::par_decode(words) }
 }
 
+#[cfg(feature = "pdp11")]
 pub mod pdp11 {
-    use super::{Error,GenericCodec};
+    use alloc::{string::String, vec::Vec};
+    use core::borrow::Borrow;
+    use super::{Codec,Error};
 
-    struct Codec {}
+    /// The [PDP-11 RADIX-50][`RADIX50_DECODE`] [`Codec`].
+    ///
+    /// The functions in this module are shortcuts for the [`Codec`] methods on this type.
+    #[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,Default)]
+    pub struct Pdp11;
 
-    impl GenericCodec for Codec {
+    impl Codec for Pdp11 {
         type Word = u16;
-        const CHARS: usize = 3;
-        const ENCODE: [Option<u8>; 128] = RADIX50_ENCODE;
+        const CHARS: usize = CHARS_PER_WORD;
         const DECODE: [char; 40] = RADIX50_DECODE;
+        const ENCODE: [Option<u8>; 128] = RADIX50_ENCODE;
+    }
 
-        fn encode_word(s: &str) -> Result<Self::Word, Error> {
-            let w: Self::Word = Self::encode16(&mut s.chars(), 0)?;
-            Ok(w)
-        }
+    /// How many characters are encoded in each u16 word.
+    pub const CHARS_PER_WORD: usize = 3;
 
-        fn decode_word(w: Self::Word) -> String {
-            Self::decode16(w)
-        }
-    }
+    /// The largest valid PDP-11 RADIX-50 word (40<sup>3</sup>-1, which decodes to `"999"`).
+    pub const MAX_WORD: u16 = 63_999;
+
+    /// Check whether a u16 is a valid PDP-11 RADIX-50 word (that is, it's no bigger than [`MAX_WORD`]).
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::{is_valid_word,MAX_WORD};
+    /// assert!(is_valid_word(0));
+    /// assert!(is_valid_word(MAX_WORD));
+    /// assert!(!is_valid_word(MAX_WORD + 1));
+    /// ```
+    pub const fn is_valid_word(w: u16) -> bool { w <= MAX_WORD }
 
     /// The RADIX-50 character set used on the PDP-11 and VAX.
     ///
@@ -281,6 +804,49 @@ pub mod pdp11 {
 
     const RADIX50_ENCODE: [Option<u8>; 128] = super::invert(&RADIX50_DECODE);
 
+    /// Iterate over the [PDP-11 RADIX-50 character set][`RADIX50_DECODE`] as `(code, char)` pairs, in code order.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::charset;
+    /// let mut it = charset();
+    /// assert_eq!(it.next(), Some((0, ' ')));
+    /// assert_eq!(it.next(), Some((1, 'A')));
+    /// assert_eq!(it.last(), Some((39, '9')));
+    /// ```
+    pub fn charset() -> impl Iterator<Item = (u8, char)> { super::charset(&RADIX50_DECODE) }
+
+    /// Look up the [PDP-11 RADIX-50][`RADIX50_DECODE`] code for a single character.
+    ///
+    /// Returns `None` if the character isn't part of the character set.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::char_to_code;
+    /// assert_eq!(char_to_code('A'), Some(1));
+    /// assert_eq!(char_to_code(' '), Some(0));
+    /// assert_eq!(char_to_code('a'), None);
+    /// ```
+    pub const fn char_to_code(c: char) -> Option<u8> { super::char_to_code(&RADIX50_ENCODE, c) }
+
+    /// Look up the character for a single [PDP-11 RADIX-50][`RADIX50_DECODE`] code.
+    ///
+    /// Returns `None` if the code isn't in the range 0-39.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::code_to_char;
+    /// assert_eq!(code_to_char(1), Some('A'));
+    /// assert_eq!(code_to_char(0), Some(' '));
+    /// assert_eq!(code_to_char(40), None);
+    /// ```
+    pub const fn code_to_char(code: u8) -> Option<char> { super::code_to_char(&RADIX50_DECODE, code) }
+
+    crate::assert_charset!(RADIX50_DECODE, crate::RADIX50_REPERTOIRE);
+
+    // Encode a word at compile time (used to build constant tables like [`crate::consts`]).
+    pub(crate) const fn encode_word_const(s: &str) -> u16 { super::encode_const(&RADIX50_ENCODE, s, 3) as u16 }
+
     /// Encode a string into [PDP-11 RADIX-50 format][`RADIX50_DECODE`].
     ///
     /// The input string will be space padded to a multiple of 3 characters before encoding. This is because
@@ -304,7 +870,23 @@ pub mod pdp11 {
     /// assert_eq!(encode("PADDING1").unwrap(),  encode("PADDING1 ").unwrap());
     /// assert_eq!(encode("PADDING").unwrap(),   encode("PADDING  ").unwrap());
     /// ```
-    pub fn encode(s: &str) -> Result<Vec<u16>, Error> { Codec::encode(s) }
+    pub fn encode(s: &str) -> Result<Vec<u16>, Error> { Pdp11::encode(s) }
+
+    /// Encode a string into [PDP-11 RADIX-50 format][`RADIX50_DECODE`], and count the padding.
+    ///
+    /// This is like [`encode`], but it also returns how many spaces were added to pad the string out to a whole
+    /// number of words. [`decode`] gives back the padding too, so `decode(encode(s))` can be longer than `s`;
+    /// [`decode_exact`] takes the count and gives back exactly `s`. Keep the count alongside the words (in a
+    /// header, say) when the original length matters.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::{decode_exact,encode,encode_exact};
+    /// let (words, pad) = encode_exact("THIS IS A TEST").unwrap();
+    /// assert_eq!((words.clone(), pad), (encode("THIS IS A TEST").unwrap(), 1));
+    /// assert_eq!(decode_exact(&words, pad), "THIS IS A TEST");
+    /// ```
+    pub fn encode_exact(s: &str) -> Result<(Vec<u16>, usize), Error> { Pdp11::encode_exact(s) }
 
     /// Encode 3 characters into a [PDP-11 RADIX-50 formatted][`RADIX50_DECODE`] word.
     ///
@@ -328,113 +910,1329 @@ pub mod pdp11 {
     /// let result = encode_word("AB-");
     /// assert_eq!(result, Err(Error::IllegalChar { char: '-', pos: 3 }))
     /// ```
-    pub fn encode_word(s: &str) -> Result<u16, Error> { Codec::encode_word(s) }
+    pub fn encode_word(s: &str) -> Result<u16, Error> { Pdp11::encode_word(s) }
 
-    /// Decode a [`slice`] of [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] words into a string.
+    /// Encode a string into [PDP-11 RADIX-50 format][`RADIX50_DECODE`], carrying on past illegal characters.
     ///
-    /// The output is a String.
+    /// This is like [`encode`] except that instead of stopping at the first character that isn't in the
+    /// [RADIX-50 character set][`RADIX50_DECODE`], it encodes each one as a space and keeps going. It returns
+    /// the words along with an [`Error::IllegalChar`] for every character that was replaced (the same errors
+    /// [`validate`] would return). If the error list is empty then the words are exactly what [`encode`] would
+    /// have returned.
     ///
     /// # Examples
     /// ```
-    /// # use radix50::pdp11::decode;
-    /// assert_eq!(decode(&[32329, 30409, 30401, 805, 31200]), "THIS IS A TEST ");
+    /// # use radix50::{Error,pdp11::{encode,encode_lossy}};
+    /// let (words, errors) = encode_lossy("THIS_IS A TEST!");
+    /// assert_eq!(words, encode("THIS IS A TEST ").unwrap());
+    /// assert_eq!(errors, [Error::IllegalChar { char: '_', pos: 5 }, Error::IllegalChar { char: '!', pos: 15 }]);
+    ///
+    /// assert_eq!(encode_lossy("THIS IS A TEST"), (encode("THIS IS A TEST").unwrap(), vec![]));
     /// ```
-    pub fn decode(words: &[u16]) -> String { Codec::decode(words) }
+    pub fn encode_lossy(s: &str) -> (Vec<u16>, Vec<Error>) { Pdp11::encode_lossy(s) }
 
-    /// Decode a [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] word into a 3 character string.
+    /// Check that every character of a string is in the [PDP-11 RADIX-50 character set][`RADIX50_DECODE`].
     ///
-    /// The output is a String.
+    /// Unlike [`encode`], which stops at the first bad character, this returns an [`Error::IllegalChar`] for
+    /// every bad character in the string, so they can all be reported (or highlighted) at once.
     ///
     /// # Examples
     /// ```
-    /// # use radix50::pdp11::decode_word;
-    /// assert_eq!(decode_word(50913), "123");
+    /// # use radix50::{Error,pdp11::validate};
+    /// assert_eq!(validate("THIS IS A TEST"), Ok(()));
+    /// assert_eq!(validate("THIS_IS A TEST!"), Err(vec![Error::IllegalChar { char: '_', pos: 5 },
+    ///                                                  Error::IllegalChar { char: '!', pos: 15 }]));
     /// ```
-    pub fn decode_word(word: u16) -> String { Codec::decode_word(word) }
-}
-
-const fn invert(radix50_table: &[char; 40]) -> [Option<u8>; 128] {
-    let mut out = [None; 128];
-    const_for!(i in 0..40 => {
-        out[radix50_table[i] as usize] = Some(i as u8);
-    });
-    out
-}
+    pub fn validate(s: &str) -> Result<(), Vec<Error>> { Pdp11::validate(s) }
 
-trait GenericCodec {
-    type Word: Copy;                 // Type to use for the machine word
-    const CHARS: usize;              // How many radix-50 characters are encoded in one machine word
-    const ENCODE: [Option<u8>; 128]; // The encode table
-    const DECODE: [char; 40];        // The decode table
+    /// Compare two strings in [PDP-11 RADIX-50][`RADIX50_DECODE`] collation order.
+    ///
+    /// Characters are compared by their RADIX-50 codes instead of their ASCII values, which is the order DEC's
+    /// utilities list things in (it's the numeric order of the encoded words). The shorter string is treated as
+    /// if it were padded with spaces, so `"A"` and `"A  "` compare equal. Characters that aren't in the character
+    /// set sort after all the ones that are.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::cmp::Ordering;
+    /// # use radix50::pdp11::cmp;
+    /// assert_eq!(cmp("A", "1"), Ordering::Less); // Digits come last on the PDP-11
+    /// assert_eq!(cmp("A", "$"), Ordering::Less);
+    /// assert_eq!(cmp("A", "A  "), Ordering::Equal);
+    /// ```
+    pub fn cmp(a: &str, b: &str) -> core::cmp::Ordering { Pdp11::collate(a, b) }
 
-    fn encode_word(s: &str) -> Result<Self::Word, Error>;
-    fn decode_word(w: Self::Word) -> String;
+    /// Sort strings into [PDP-11 RADIX-50][`RADIX50_DECODE`] collation order (see [`cmp`]). The sort is stable.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::sort;
+    /// let mut names = ["FOO", "BAR", "9LIVES", "$A", "A1", "A"];
+    /// sort(&mut names);
+    /// assert_eq!(names, ["A", "A1", "BAR", "FOO", "$A", "9LIVES"]);
+    /// ```
+    pub fn sort<S: AsRef<str>>(strings: &mut [S]) { strings.sort_by(|a, b| cmp(a.as_ref(), b.as_ref())) }
 
-    fn encode(s: &str) -> Result<Vec<Self::Word>, Error> {
-        let mut out = Vec::with_capacity(s.len()/Self::CHARS);
-        let mut i=0;
-        for (i, chunk) in s.split_inclusive(|_| { i+=1; i % Self::CHARS == 0 }).enumerate() {
-            out.push(Self::encode_word(&chunk).map_err(|e| match e { Error::IllegalChar { char, pos } => Error::IllegalChar{char, pos: i*Self::CHARS + pos} })?);
-        }
-        Ok(out)
-    }
+    /// Get the symbol after some [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] words, in collation order.
+    ///
+    /// The words are treated as one big base-40 number (the last word being the least significant) and
+    /// incremented, carrying from word to word. This is handy for enumerating symbol ranges or generating
+    /// temporary labels the way old assemblers did. It returns `None` if the words are already the last symbol
+    /// (all `'9'`) or if any word is out of range. See [`prev_symbol`] for the other direction.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::{encode,decode,next_symbol,prev_symbol};
+    /// assert_eq!(decode(next_symbol(&encode("TMP").unwrap()).unwrap()), "TMQ");
+    /// assert_eq!(decode(next_symbol(&encode("L99999").unwrap()).unwrap()), "M     "); // Carries into the first word
+    /// assert_eq!(decode(prev_symbol(&encode("M").unwrap()).unwrap()), "L99");
+    /// assert_eq!(next_symbol(&encode("999").unwrap()), None);
+    /// assert_eq!(prev_symbol(&[0, 0]), None);
+    /// ```
+    pub fn next_symbol(words: &[u16]) -> Option<Vec<u16>> { Pdp11::next_symbol(words) }
 
-    fn encode16(it: &mut Chars, pos: usize) -> Result<u16, Error> {
-        let c = [Self::radix50_from_char(it.next().unwrap_or(' '), pos + 1)?,
-                 Self::radix50_from_char(it.next().unwrap_or(' '), pos + 2)?,
-                 Self::radix50_from_char(it.next().unwrap_or(' '), pos + 3)?];
-        Ok(c[0] as u16 * 40_u16.pow(2) +
-            c[1] as u16 * 40_u16.pow(1) +
-            c[2] as u16 * 40_u16.pow(0))
-    }
+    /// Get the symbol before some [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] words, in collation order. It
+    /// returns `None` if the words are already all spaces or if any word is out of range. See [`next_symbol`].
+    pub fn prev_symbol(words: &[u16]) -> Option<Vec<u16>> { Pdp11::prev_symbol(words) }
 
-    fn radix50_from_char(c: char, pos: usize) -> Result<u8, Error> {
-        if c > '\u{7f}' {
-            Err(Error::IllegalChar { char: c, pos })?;
-        }
-        match Self::ENCODE[c as usize] {
-            Some(v) => Ok(v),
-            None => Err(Error::IllegalChar { char: c, pos }),
-        }
-    }
+    /// Decode a sequence of [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] words into a string.
+    ///
+    /// The words can come from anything iterable: a [`slice`], a [`std::vec::Vec`], or an iterator chain
+    /// (yielding either words or references to words), so there's no need to collect them first.
+    ///
+    /// The output is a String.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::decode;
+    /// assert_eq!(decode(&[32329, 30409, 30401, 805, 31200]), "THIS IS A TEST ");
+    /// assert_eq!(decode([32329_u16, 30409, 30401].into_iter().map(|w| w + 1)), "THJS JS B");
+    /// ```
+    pub fn decode<I>(words: I) -> String where I: IntoIterator, I::Item: Borrow<u16> { Pdp11::decode(words) }
 
-    fn decode(words: &[Self::Word]) -> String {
-        words.iter().fold(String::new(), |mut s, w| { s.push_str(&Self::decode_word(*w)); s })
-    }
+    /// Decode a sequence of [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] words into a string, dropping the last `pad`
+    /// characters.
+    ///
+    /// `pad` is the padding count from [`encode_exact`], so this gives back exactly the string that was encoded.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::decode_exact;
+    /// assert_eq!(decode_exact([32329, 30409, 30401, 805, 31200], 0), "THIS IS A TEST ");
+    /// assert_eq!(decode_exact([32329, 30409, 30401, 805, 31200], 1), "THIS IS A TEST");
+    /// ```
+    pub fn decode_exact<I>(words: I, pad: usize) -> String where I: IntoIterator, I::Item: Borrow<u16> { Pdp11::decode_exact(words, pad) }
 
-    fn decode16(w: u16) -> String {
-        // Unsafe rationalization: bytes can only come from the RADIX50_DECODE look up table and so are guaranteed
-        // to be ASCII (and therefore valid utf8).
-        unsafe { String::from_utf8_unchecked(vec![Self::DECODE[(w / 40_u16.pow(2) % 40) as usize] as u8,
-                                                  Self::DECODE[(w / 40_u16.pow(1) % 40) as usize] as u8,
-                                                  Self::DECODE[(w / 40_u16.pow(0) % 40) as usize] as u8])
-        }
-    }
-}
+    /// Decode a sequence of [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] words into a string, checking that each
+    /// word is in range.
+    ///
+    /// Not every u16 is a valid RADIX-50 word (the largest is [`MAX_WORD`]). [`decode`] happily decodes
+    /// out-of-range words into garbage, but this returns an [`Error::WordOutOfRange`] for the first one instead.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{Error,pdp11::decode_checked};
+    /// assert_eq!(decode_checked([32329, 30409, 30401, 805, 31200]), Ok("THIS IS A TEST ".to_string()));
+    /// assert_eq!(decode_checked([32329, 64000, 30401, 805, 65535]), Err(Error::WordOutOfRange { word: 64000, index: 1 }));
+    /// ```
+    pub fn decode_checked<I>(words: I) -> Result<String, Error> where I: IntoIterator, I::Item: Borrow<u16> { Pdp11::decode_checked(words) }
+
+    /// Decode a sequence of [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] words into a string, collecting every
+    /// out-of-range word.
+    ///
+    /// This is for assessing damaged data: rather than stopping at the first bad word (like [`decode_checked`])
+    /// it decodes everything and returns an [`Error::WordOutOfRange`] (with its index) for each word that was out
+    /// of range. If `marker` is given then each bad word decodes to 3 of that character, otherwise it's decoded
+    /// the same way [`decode`] would.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{Error,pdp11::decode_lossy};
+    /// let (text, errors) = decode_lossy([32329, 64000, 30401, 805, 65535], Some('*'));
+    /// assert_eq!(text, "THI***S A TE***");
+    /// assert_eq!(errors, [Error::WordOutOfRange { word: 64000, index: 1 },
+    ///                     Error::WordOutOfRange { word: 65535, index: 4 }]);
+    ///
+    /// assert_eq!(decode_lossy([32329, 30409, 30401, 805, 31200], None), ("THIS IS A TEST ".to_string(), vec![]));
+    /// ```
+    pub fn decode_lossy<I>(words: I, marker: Option<char>) -> (String, Vec<Error>) where I: IntoIterator, I::Item: Borrow<u16> { Pdp11::decode_lossy(words, marker) }
+
+    /// Decode a [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] word into a 3 character string.
+    ///
+    /// The output is a String.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::decode_word;
+    /// assert_eq!(decode_word(50913), "123");
+    /// ```
+    pub fn decode_word(word: u16) -> String { Pdp11::decode_word(word) }
+
+    /// Decode a [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] word into an array of 3 characters.
+    ///
+    /// This is like [`decode_word`] but doesn't build a String, which is handy when you only need to compare or
+    /// copy the characters.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::decode_word_chars;
+    /// assert_eq!(decode_word_chars(50913), ['1', '2', '3']);
+    /// assert_eq!(decode_word_chars(46400), ['%', ' ', ' ']);
+    /// ```
+    pub fn decode_word_chars(word: u16) -> [char; 3] { super::decode16(&RADIX50_DECODE, word) }
+
+    /// Decode a [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] word into `buf`, returning it as a `&str`.
+    ///
+    /// This is like [`decode_word`] but doesn't allocate.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::decode_word_into;
+    /// let mut buf = [0; 3];
+    /// assert_eq!(decode_word_into(50913, &mut buf), "123");
+    /// ```
+    pub fn decode_word_into(word: u16, buf: &mut [u8; 3]) -> &str { Pdp11::decode_into_slice([word], buf).expect("a word fits in 3 bytes") }
+
+    /// Decode a [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] word into an [`InlineStr`](crate::InlineStr).
+    ///
+    /// This is like [`decode_word`] but returns the text by value, without allocating.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::decode_word_inline;
+    /// assert_eq!(decode_word_inline(50913), "123");
+    /// ```
+    pub fn decode_word_inline(word: u16) -> crate::InlineStr<3> { Pdp11::decode_inline([word]).expect("a word fits in 3 bytes") }
+
+    /// Encode a string into [PDP-11 RADIX-50 format][`RADIX50_DECODE`] without allocating, returning how many
+    /// words of `out` were used.
+    ///
+    /// It returns an [`Error::InputTooLong`] if `out` isn't big enough.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{Error,pdp11::encode_into_slice};
+    /// let mut words = [0; 8];
+    /// assert_eq!(encode_into_slice("THIS IS A TEST", &mut words), Ok(5));
+    /// assert_eq!(words[..5], [32329, 30409, 30401, 805, 31200]);
+    /// assert_eq!(encode_into_slice("THIS IS A TEST", &mut words[..4]), Err(Error::InputTooLong { len: 14, max: 12 }));
+    /// ```
+    pub fn encode_into_slice(s: &str, out: &mut [u16]) -> Result<usize, Error> { Pdp11::encode_into_slice(s, out) }
+
+    /// Decode [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] words into `out` without allocating, returning the text.
+    ///
+    /// It returns an [`Error::InputTooLong`] if `out` isn't big enough.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{Error,pdp11::decode_into_slice};
+    /// let mut buf = [0; 16];
+    /// assert_eq!(decode_into_slice([32329, 30409, 30401, 805, 31200], &mut buf), Ok("THIS IS A TEST "));
+    /// assert_eq!(decode_into_slice([32329, 30409, 30401, 805, 31200], &mut buf[..14]), Err(Error::InputTooLong { len: 15, max: 14 }));
+    /// ```
+    pub fn decode_into_slice<I>(words: I, out: &mut [u8]) -> Result<&str, Error> where I: IntoIterator, I::Item: Borrow<u16> { Pdp11::decode_into_slice(words, out) }
+
+    /// Decode [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] words straight into a [`fmt::Write`](core::fmt::Write),
+    /// without building a String.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::decode_fmt;
+    /// use std::fmt::Write;
+    /// let mut line = String::from("name: ");
+    /// decode_fmt([32329, 30409, 30401, 805, 31200], &mut line).unwrap();
+    /// assert_eq!(line, "name: THIS IS A TEST ");
+    /// ```
+    pub fn decode_fmt<I, W>(words: I, out: &mut W) -> core::fmt::Result where I: IntoIterator, I::Item: Borrow<u16>, W: core::fmt::Write + ?Sized {
+        Pdp11::decode_fmt(words, out)
+    }
+
+    /// Decode a slice of [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] words into a string, many words at a time.
+    ///
+    /// The result is the same as [`decode`]'s, but this is faster on big inputs (especially with the `simd`
+    /// feature). See [`Codec::decode_bulk`](crate::Codec::decode_bulk).
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::decode_bulk;
+    /// assert_eq!(decode_bulk(&[32329, 30409, 30401, 805, 31200]), "THIS IS A TEST ");
+    /// ```
+    pub fn decode_bulk(words: &[u16]) -> String { Pdp11::decode_bulk(words) }
+
+    /// Encode a string into [PDP-11 RADIX-50][`RADIX50_DECODE`] words, using all the cores. See
+    /// [`Codec::par_encode`](crate::Codec::par_encode).
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::par_encode;
+    /// assert_eq!(par_encode("THIS IS A TEST").unwrap(), [32329, 30409, 30401, 805, 31200]);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_encode(s: &str) -> Result<Vec<u16>, Error> { Pdp11::par_encode(s) }
+
+    /// Decode a slice of [PDP-11 RADIX-50 encoded][`RADIX50_DECODE`] words, using all the cores. See
+    /// [`Codec::par_decode`](crate::Codec::par_decode).
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::par_decode;
+    /// assert_eq!(par_decode(&[32329, 30409, 30401, 805, 31200]), "THIS IS A TEST ");
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_decode(words: &[u16]) -> String { Pdp11::par_decode(words) }
+
+    /// Encode a symbol name of up to 6 characters into 2 words, the way MACRO-11, the linkers, and RSX store them.
+    ///
+    /// Short names are space padded. Names longer than 6 characters return an [`Error::InputTooLong`]. See
+    /// [`Symbol6`](crate::Symbol6) for a type that wraps the words.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{Error,pdp11::encode_symbol};
+    /// assert_eq!(encode_symbol("$DSW"), Ok([43379, 36800]));
+    /// assert_eq!(encode_symbol("R0"), Ok([30000, 0]));
+    /// assert_eq!(encode_symbol("PRINTF"), Ok([26329, 23206]));
+    /// assert_eq!(encode_symbol("PRINTF2"), Err(Error::InputTooLong { len: 7, max: 6 }));
+    /// ```
+    pub fn encode_symbol(s: &str) -> Result<[u16; 2], Error> {
+        let len = s.chars().count();
+        if len > 6 { return Err(Error::InputTooLong { len, max: 6 }) }
+        let mut it = s.chars();
+        Ok([super::encode16(&RADIX50_ENCODE, &mut it, 0)?, super::encode16(&RADIX50_ENCODE, &mut it, 3)?])
+    }
+
+    /// Decode a 2 word symbol name, trimming the trailing space padding.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::pdp11::decode_symbol;
+    /// assert_eq!(decode_symbol(&[43379, 36800]), "$DSW");
+    /// assert_eq!(decode_symbol(&[0, 0]), "");
+    /// ```
+    pub fn decode_symbol(words: &[u16; 2]) -> String {
+        let mut s = decode(words);
+        s.truncate(s.trim_end_matches(' ').len());
+        s
+    }
+}
+
+const fn invert(radix50_table: &[char; 40]) -> [Option<u8>; 128] {
+    let mut out = [None; 128];
+    const_for!(i in 0..40 => {
+        out[radix50_table[i] as usize] = Some(i as u8);
+    });
+    out
+}
+
+// Bit n is set if ASCII n is in the character set.
+const fn legal_mask(encode_table: &[Option<u8>; 128]) -> u128 {
+    let mut mask = 0;
+    const_for!(i in 0..128 => {
+        if encode_table[i].is_some() { mask |= 1 << i }
+    });
+    mask
+}
+
+const fn char_to_code(encode_table: &[Option<u8>; 128], c: char) -> Option<u8> {
+    if c.is_ascii() { encode_table[c as usize] } else { None }
+}
+
+const fn code_to_char(decode_table: &[char; 40], code: u8) -> Option<char> {
+    if (code as usize) < decode_table.len() { Some(decode_table[code as usize]) } else { None }
+}
+
+fn charset(decode_table: &'static [char; 40]) -> impl Iterator<Item = (u8, char)> {
+    decode_table.iter().enumerate().map(|(code, c)| (code as u8, *c))
+}
+
+/// The 40 characters of the standard RADIX-50 character set (in PDP-11 order). Both codecs' tables are
+/// permutations of it, and it's what [`assert_charset!`] checks a table against unless it's given another one.
+pub const RADIX50_REPERTOIRE: [char; 40] = [' ', 'A', 'B', 'C', 'D', 'E', 'F', 'G',
+                                            'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O',
+                                            'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W',
+                                            'X', 'Y', 'Z', '$', '.', '%', '0', '1',
+                                            '2', '3', '4', '5', '6', '7', '8', '9'];
+
+/// Check that `table` is a permutation of `repertoire` (the same 40 characters, each appearing exactly once, in any
+/// order).
+///
+/// This is a `const fn` so that it can be used to verify variant character sets at compile time. See
+/// [`assert_charset!`] for a convenient way to do that.
+///
+/// # Examples
+/// ```
+/// # #[cfg(all(feature = "pdp10", feature = "pdp11"))] {
+/// # use radix50::{is_permutation, pdp10, pdp11};
+/// assert!(is_permutation(&pdp10::RADIX50_DECODE, &pdp11::RADIX50_DECODE));
+///
+/// let mut broken = pdp11::RADIX50_DECODE;
+/// broken[29] = '$'; // Now there are 2 '$' and no '%'
+/// assert!(!is_permutation(&broken, &pdp11::RADIX50_DECODE));
+/// # }
+/// ```
+pub const fn is_permutation(table: &[char; 40], repertoire: &[char; 40]) -> bool {
+    // If all 40 chars are distinct and all of them are in the repertoire then the repertoire can't have anything else.
+    const_for!(i in 0..40 => {
+        let mut found = false;
+        const_for!(j in 0..40 => {
+            if j != i && table[j] == table[i] { return false }
+            if repertoire[j] == table[i] { found = true }
+        });
+        if !found { return false }
+    });
+    true
+}
+
+/// Assert at compile time that a RADIX-50 character table is a permutation of the standard RADIX-50 character
+/// repertoire ([`RADIX50_REPERTOIRE`]) or of a given repertoire.
+///
+/// A table with a duplicated or missing character would silently produce encodings that can't round trip, so
+/// this lets a variant table fail to compile instead.
+///
+/// # Examples
+/// ```
+/// # #[cfg(all(feature = "pdp10", feature = "pdp11"))] {
+/// const VARIANT: [char; 40] = [' ', 'A', 'B', 'C', 'D', 'E', 'F', 'G',
+///                              'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O',
+///                              'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W',
+///                              'X', 'Y', 'Z', '.', '$', '%', '0', '1',
+///                              '2', '3', '4', '5', '6', '7', '8', '9'];
+/// radix50::assert_charset!(VARIANT);
+/// radix50::assert_charset!(VARIANT, radix50::pdp10::RADIX50_DECODE);
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// const BROKEN: [char; 40] = [' ', 'A', 'B', 'C', 'D', 'E', 'F', 'G',
+///                             'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O',
+///                             'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W',
+///                             'X', 'Y', 'Z', '$', '.', '_', '0', '1',
+///                             '2', '3', '4', '5', '6', '7', '8', '9'];
+/// radix50::assert_charset!(BROKEN);
+/// ```
+#[macro_export]
+macro_rules! assert_charset {
+    ($table:expr) => { $crate::assert_charset!($table, $crate::RADIX50_REPERTOIRE); };
+    ($table:expr, $repertoire:expr) => {
+        const _: () = assert!($crate::is_permutation(&$table, &$repertoire),
+                              concat!(stringify!($table), " is not a permutation of the RADIX-50 character set"));
+    };
+}
+
+// Compile time version of encode_word(). Since it's const it can't return an Error, so it panics instead (which
+// turns into a compile error when used to initialize a const).
+#[cfg(feature = "pdp11")]
+const fn encode_const(encode_table: &[Option<u8>; 128], s: &str, chars: usize) -> u64 {
+    let b = s.as_bytes();
+    if b.len() > chars { panic!("Too many characters for a RADIX-50 word") }
+    let mut w = 0;
+    const_for!(i in 0..chars => {
+        let c = if i < b.len() { b[i] } else { b' ' };
+        w = w * 40 + match if c < 128 { encode_table[c as usize] } else { None } {
+            Some(v) => v as u64,
+            None => panic!("Illegal RADIX-50 character"),
+        };
+    });
+    w
+}
+
+/// A flavor of RADIX-50.
+///
+/// This is the generic machinery behind the [`pdp10`] and [`pdp11`] modules ([`pdp10::Pdp10`] and
+/// [`pdp11::Pdp11`] implement it). Implementing it for your own type gives you encoding, decoding, and padding
+/// for a site-specific RADIX-50 flavor (a different character ordering, say). Only the associated types and
+/// constants need to be provided.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "pdp11")] {
+/// use radix50::Codec;
+///
+/// // A PDP-11 flavored codec with '$' and '.' swapped
+/// struct Swapped;
+/// impl Codec for Swapped {
+///     type Word = u16;
+///     const CHARS: usize = 3;
+///     const DECODE: [char; 40] = [' ', 'A', 'B', 'C', 'D', 'E', 'F', 'G',
+///                                 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O',
+///                                 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W',
+///                                 'X', 'Y', 'Z', '.', '$', '%', '0', '1',
+///                                 '2', '3', '4', '5', '6', '7', '8', '9'];
+/// }
+/// radix50::assert_charset!(Swapped::DECODE);
+///
+/// assert_eq!(Swapped::encode_word("A.$").unwrap(), radix50::pdp11::encode_word("A$.").unwrap());
+/// assert_eq!(Swapped::decode(Swapped::encode("THIS.IS$A.TEST").unwrap()), "THIS.IS$A.TEST ");
+/// # }
+/// ```
+pub trait Codec {
+    /// Type to use for the machine word. It must be able to hold 40<sup>[`CHARS`](Codec::CHARS)</sup>-1.
+    type Word: Copy + Into<u64> + TryFrom<u64>;
+    /// How many RADIX-50 characters are encoded in one machine word. This must be a multiple of 3, and
+    /// [`Word`](Codec::Word) has to be big enough for that many. A codec that breaks either rule fails to compile
+    /// as soon as it's used:
+    ///
+    /// ```compile_fail
+    /// use radix50::Codec;
+    ///
+    /// struct Broken;
+    /// impl Codec for Broken {
+    ///     type Word = u32;
+    ///     const CHARS: usize = 4;
+    ///     const DECODE: [char; 40] = radix50::pdp11::RADIX50_DECODE;
+    /// }
+    /// Broken::encode("ABCD");
+    /// ```
+    const CHARS: usize;
+    /// The decode table: the character for each of the 40 RADIX-50 codes.
+    const DECODE: [char; 40];
+    /// The encode table: the code for each ASCII character (or `None` if it isn't in the character set). This
+    /// defaults to the inverse of [`DECODE`](Codec::DECODE).
+    const ENCODE: [Option<u8>; 128] = invert(&Self::DECODE);
+    /// The character set as a bit mask: bit n is set if ASCII character n has a code. This defaults to the
+    /// characters in [`ENCODE`](Codec::ENCODE).
+    const LEGAL: u128 = legal_mask(&Self::ENCODE);
+    /// The largest valid word: 40<sup>[`CHARS`](Codec::CHARS)</sup>-1.
+    const MAX_WORD: u64 = 40_u64.pow(Self::CHARS as u32) - 1;
+
+    /// Encode a string, space padding it to a multiple of [`CHARS`](Codec::CHARS) characters.
+    fn encode(s: &str) -> Result<Vec<Self::Word>, Error> { encode_with::<Self>(&Self::ENCODE, Self::LEGAL, s) }
+
+    /// Encode a string like [`encode`](Codec::encode), also returning how many spaces of padding were added, so
+    /// that [`decode_exact`](Codec::decode_exact) can give back exactly the original string.
+    fn encode_exact(s: &str) -> Result<(Vec<Self::Word>, usize), Error> {
+        let words = Self::encode(s)?;
+        let pad = words.len() * Self::CHARS - s.chars().count();
+        Ok((words, pad))
+    }
+
+    /// Encode a string, encoding illegal characters as spaces and returning an [`Error::IllegalChar`] for each one
+    /// along with the words.
+    fn encode_lossy(s: &str) -> (Vec<Self::Word>, Vec<Error>) { encode_lossy_with::<Self>(&Self::ENCODE, Self::LEGAL, s) }
+
+    /// Check a string, returning an [`Error::IllegalChar`] for every character that isn't in the character set.
+    fn validate(s: &str) -> Result<(), Vec<Error>> { validate_with(&Self::ENCODE, s) }
+
+    /// Encode [`CHARS`](Codec::CHARS) characters into a word. Missing characters are assumed to be spaces.
+    fn encode_word(s: &str) -> Result<Self::Word, Error> { encode_word_with::<Self>(&Self::ENCODE, s) }
+
+    /// Encode a string into `out` without allocating, returning how many words were used. It returns an
+    /// [`Error::InputTooLong`] if `out` is too small, and leaves `out` partially written if there's an error.
+    fn encode_into_slice(s: &str, out: &mut [Self::Word]) -> Result<usize, Error> { encode_into_slice_with::<Self>(&Self::ENCODE, s, out) }
+
+    /// Encode a string into an [`ArrayVec`](arrayvec::ArrayVec). It returns an [`Error::InputTooLong`] if it
+    /// doesn't fit.
+    #[cfg(feature = "arrayvec")]
+    fn encode_arrayvec<const N: usize>(s: &str) -> Result<arrayvec::ArrayVec<Self::Word, N>, Error> {
+        let mut out = arrayvec::ArrayVec::new();
+        for w in encode_words_with::<Self>(&Self::ENCODE, s) {
+            out.try_push(w?).map_err(|_| Error::InputTooLong { len: s.chars().count(), max: N * Self::CHARS })?;
+        }
+        Ok(out)
+    }
+
+    /// Encode a string into a [`heapless::Vec`]. It returns an [`Error::InputTooLong`] if it doesn't fit.
+    #[cfg(feature = "heapless")]
+    fn encode_heapless<const N: usize>(s: &str) -> Result<heapless::Vec<Self::Word, N>, Error> {
+        let mut out = heapless::Vec::new();
+        for w in encode_words_with::<Self>(&Self::ENCODE, s) {
+            out.push(w?).map_err(|_| Error::InputTooLong { len: s.chars().count(), max: N * Self::CHARS })?;
+        }
+        Ok(out)
+    }
+
+    /// Decode a sequence of words into a string.
+    fn decode<I>(words: I) -> String where I: IntoIterator, I::Item: Borrow<Self::Word> { decode_with::<Self, I>(&Self::DECODE, words) }
+
+    /// Decode a sequence of words into a string like [`decode`](Codec::decode), then drop the last `pad`
+    /// characters (the padding count from [`encode_exact`](Codec::encode_exact)).
+    fn decode_exact<I>(words: I, pad: usize) -> String where I: IntoIterator, I::Item: Borrow<Self::Word> {
+        let mut s = Self::decode(words);
+        // `pad` is in chars (like encode_exact() counts it), which aren't bytes if the table has non-ASCII characters
+        let end = match pad {
+            0   => s.len(),
+            pad => s.char_indices().nth_back(pad - 1).map_or(0, |(i, _)| i),
+        };
+        s.truncate(end);
+        s
+    }
+
+    /// Decode a word into a [`CHARS`](Codec::CHARS) character string.
+    fn decode_word(w: Self::Word) -> String { decode_word_with::<Self>(&Self::DECODE, w) }
+
+    /// Decode a sequence of words into an [`InlineStr`] that holds up to `N` bytes, so short strings can be
+    /// decoded without allocating. It returns an [`Error::InputTooLong`] if they don't fit.
+    fn decode_inline<const N: usize, I>(words: I) -> Result<InlineStr<N>, Error> where I: IntoIterator, I::Item: Borrow<Self::Word> {
+        InlineStr::fill(|buf| Self::decode_into_slice(words, buf))
+    }
+
+    /// Decode a sequence of words straight into a [`fmt::Write`](core::fmt::Write) (a `String`, a
+    /// [`Formatter`](core::fmt::Formatter), or some other sink) without allocating.
+    fn decode_fmt<I, W>(words: I, out: &mut W) -> core::fmt::Result where I: IntoIterator, I::Item: Borrow<Self::Word>, W: core::fmt::Write + ?Sized {
+        decode_fmt_with::<Self, I, W>(&Self::DECODE, words, out)
+    }
+
+    /// Decode a slice of words into a string, many words at a time.
+    ///
+    /// The result is the same as [`decode`](Codec::decode)'s. With the `simd` feature, the words are split into
+    /// character codes 8 at a time with SIMD arithmetic, which is a lot faster on big inputs like tape and disk
+    /// images. Without it, it's a plain loop.
+    fn decode_bulk(words: &[Self::Word]) -> String { decode_bulk_with::<Self>(&Self::DECODE, words) }
+
+    /// Encode a string like [`encode`](Codec::encode), but split it up and encode the pieces in parallel with
+    /// [rayon](https://docs.rs/rayon).
+    ///
+    /// The pieces are split on word boundaries, so the words are exactly the ones [`encode`](Codec::encode)
+    /// returns, and so is the error: it's the first illegal character in the whole string, with its position in
+    /// the whole string.
+    #[cfg(feature = "rayon")]
+    fn par_encode(s: &str) -> Result<Vec<Self::Word>, Error> where Self::Word: Send {
+        par_encode_with::<Self>(&Self::ENCODE, Self::LEGAL, s)
+    }
+
+    /// Decode words like [`decode_bulk`](Codec::decode_bulk), but decode chunks of them in parallel with
+    /// [rayon](https://docs.rs/rayon).
+    #[cfg(feature = "rayon")]
+    fn par_decode(words: &[Self::Word]) -> String where Self::Word: Sync {
+        par_decode_with::<Self>(&Self::DECODE, words)
+    }
+
+    /// Decode a sequence of words into `out` without allocating, returning the decoded part of `out` as a `&str`.
+    /// It returns an [`Error::InputTooLong`] (where the lengths are in bytes, which are characters unless the
+    /// [`DECODE`](Codec::DECODE) table has non-ASCII characters) if `out` is too small.
+    fn decode_into_slice<I>(words: I, out: &mut [u8]) -> Result<&str, Error> where I: IntoIterator, I::Item: Borrow<Self::Word> {
+        decode_into_slice_with::<Self, I>(&Self::DECODE, words, out)
+    }
+
+    /// Decode a sequence of words into an [`ArrayString`](arrayvec::ArrayString). It returns an
+    /// [`Error::InputTooLong`] if it doesn't fit.
+    #[cfg(feature = "arrayvec")]
+    fn decode_arrayvec<const N: usize, I>(words: I) -> Result<arrayvec::ArrayString<N>, Error> where I: IntoIterator, I::Item: Borrow<Self::Word> {
+        let mut out = arrayvec::ArrayString::new();
+        let mut words = words.into_iter();
+        while let Some(w) = words.next() {
+            for c in decode_word_chars_with::<Self>(&Self::DECODE, *w.borrow()) {
+                out.try_push(c).map_err(|_| Error::InputTooLong { len: (out.len() / Self::CHARS + 1 + words.by_ref().count()) * Self::CHARS, max: N })?;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Decode a sequence of words into a [`heapless::String`]. It returns an [`Error::InputTooLong`] if it doesn't
+    /// fit.
+    #[cfg(feature = "heapless")]
+    fn decode_heapless<const N: usize, I>(words: I) -> Result<heapless::String<N>, Error> where I: IntoIterator, I::Item: Borrow<Self::Word> {
+        let mut out = heapless::String::new();
+        let mut words = words.into_iter();
+        while let Some(w) = words.next() {
+            for c in decode_word_chars_with::<Self>(&Self::DECODE, *w.borrow()) {
+                out.push(c).map_err(|_| Error::InputTooLong { len: (out.len() / Self::CHARS + 1 + words.by_ref().count()) * Self::CHARS, max: N })?;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Decode a sequence of words into a string, returning an [`Error::WordOutOfRange`] for the first word that
+    /// isn't a valid RADIX-50 word.
+    fn decode_checked<I>(words: I) -> Result<String, Error> where I: IntoIterator, I::Item: Borrow<Self::Word> {
+        let mut s = String::new();
+        for (index, w) in words.into_iter().enumerate() {
+            let w = *w.borrow();
+            if !Self::is_valid_word(w) { return Err(Error::WordOutOfRange { word: w.into(), index }) }
+            s.extend(decode_word_chars_with::<Self>(&Self::DECODE, w));
+        }
+        Ok(s)
+    }
+
+    /// Decode a sequence of words into a string, returning an [`Error::WordOutOfRange`] for every word that
+    /// isn't a valid RADIX-50 word. Those words decode as [`CHARS`](Codec::CHARS) `marker`s, if given.
+    fn decode_lossy<I>(words: I, marker: Option<char>) -> (String, Vec<Error>) where I: IntoIterator, I::Item: Borrow<Self::Word> {
+        decode_lossy_with::<Self, I>(&Self::DECODE, words, marker)
+    }
+
+    /// Compare two strings by their RADIX-50 codes. The shorter string is treated as if it were space padded, and
+    /// characters that aren't in the character set sort after all the ones that are.
+    fn collate(a: &str, b: &str) -> core::cmp::Ordering { collate_with(&Self::ENCODE, a, b) }
+
+    /// Increment words as a base-40 number, carrying from word to word. Returns `None` on overflow or if any word
+    /// is out of range.
+    fn next_symbol(words: &[Self::Word]) -> Option<Vec<Self::Word>> { step_symbol::<Self>(words, true) }
+
+    /// Decrement words as a base-40 number, borrowing from word to word. Returns `None` on underflow or if any
+    /// word is out of range.
+    fn prev_symbol(words: &[Self::Word]) -> Option<Vec<Self::Word>> { step_symbol::<Self>(words, false) }
+
+    /// Check whether a word is a valid RADIX-50 word (that is, it's no bigger than [`MAX_WORD`](Codec::MAX_WORD)).
+    fn is_valid_word(w: Self::Word) -> bool { w.into() <= Self::MAX_WORD }
+
+    /// Look up the code for a single character (`None` if it isn't in the character set).
+    fn char_to_code(c: char) -> Option<u8> { char_to_code(&Self::ENCODE, c) }
+
+    /// Look up the character for a single code (`None` if it isn't in the range 0-39).
+    fn code_to_char(code: u8) -> Option<char> { code_to_char(&Self::DECODE, code) }
+}
+
+// The Codec machinery, parameterized by the tables so that it works with runtime tables (see CustomCodec) as well
+// as the Codec constants. C supplies the word layout.
+
+fn encode_with<C: Codec + ?Sized>(encode_table: &[Option<u8>; 128], legal: u128, s: &str) -> Result<Vec<C::Word>, Error> {
+    if let Some(words) = encode_legal_with::<C>(encode_table, legal, s.as_bytes()) { return Ok(words) }
+    // There's an illegal character somewhere. Go the slow way to find (and report) the first one.
+    let mut out = Vec::with_capacity(s.len()/C::CHARS);
+    for w in encode_words_with::<C>(encode_table, s) {
+        out.push(w?);
+    }
+    Ok(out)
+}
+
+// How many words each parallel job does. Big enough that the splitting and joining don't matter.
+#[cfg(feature = "rayon")]
+const PAR_CHUNK_WORDS: usize = 16384;
+
+#[cfg(feature = "rayon")]
+fn par_encode_with<C: Codec + ?Sized>(encode_table: &[Option<u8>; 128], legal: u128, s: &str) -> Result<Vec<C::Word>, Error> where C::Word: Send {
+    use rayon::prelude::*;
+    let piece_chars = PAR_CHUNK_WORDS * C::CHARS;
+    // Split on chars, not bytes, so that every piece but the last is a whole number of words.
+    let starts: Vec<usize> = s.char_indices().step_by(piece_chars).map(|(i, _)| i).collect();
+    let ends = starts.iter().skip(1).copied().chain([s.len()]);
+    let pieces: Vec<&str> = starts.iter().zip(ends).map(|(&start, end)| &s[start..end]).collect();
+    let encoded: Vec<Result<Vec<C::Word>, Error>> = pieces.par_iter().enumerate().map(|(n, piece)| {
+        encode_with::<C>(encode_table, legal, piece).map_err(|e| match e {
+            Error::IllegalChar { char, pos } => Error::IllegalChar { char, pos: n * piece_chars + pos },
+            e => e,
+        })
+    }).collect();
+    // The first piece with an error has the first error.
+    let mut out = Vec::with_capacity(s.len().div_ceil(C::CHARS));
+    for words in encoded {
+        out.extend(words?);
+    }
+    Ok(out)
+}
+
+#[cfg(feature = "rayon")]
+fn par_decode_with<C: Codec + ?Sized>(decode_table: &[char; 40], words: &[C::Word]) -> String where C::Word: Sync {
+    use rayon::prelude::*;
+    words.par_chunks(PAR_CHUNK_WORDS).map(|chunk| decode_bulk_with::<C>(decode_table, chunk)).collect::<Vec<String>>().concat()
+}
+
+fn encode_into_slice_with<C: Codec + ?Sized>(encode_table: &[Option<u8>; 128], s: &str, out: &mut [C::Word]) -> Result<usize, Error> {
+    let len = s.chars().count();
+    let count = len.div_ceil(C::CHARS);
+    if count > out.len() { return Err(Error::InputTooLong { len, max: out.len() * C::CHARS }) }
+    for (o, w) in out.iter_mut().zip(encode_words_with::<C>(encode_table, s)) {
+        *o = w?;
+    }
+    Ok(count)
+}
+
+// The fast path for big inputs: encode straight from the bytes (checked with all_legal()) instead of decoding and
+// looking up each char. Returns None if there's an illegal (or non-ASCII) character, or if the table can't encode
+// the padding spaces.
+fn encode_legal_with<C: Codec + ?Sized>(encode_table: &[Option<u8>; 128], legal: u128, bytes: &[u8]) -> Option<Vec<C::Word>> {
+    assert_packing::<C>();
+    if !all_legal(legal, bytes) { return None }
+
+    let space = encode_table[b' ' as usize]? as u64;
+    Some(bytes.chunks(C::CHARS).map(|chunk| {
+        // Everything was checked above, so the unwrap_or() never happens.
+        let w = chunk.iter().fold(0_u64, |w, &b| w * 40 + encode_table[b as usize].unwrap_or(0) as u64);
+        let w = (chunk.len()..C::CHARS).fold(w, |w, _| w * 40 + space);
+        C::Word::try_from(w).unwrap_or_else(|_| panic!("{} RADIX-50 characters don't fit in a {}", C::CHARS, core::any::type_name::<C::Word>()))
+    }).collect())
+}
+
+// Check that every byte is in the `legal` mask (from legal_mask()), 8 bytes at a time: one test of the high bits
+// rules out non-ASCII bytes in the whole word, and then the 8 mask lookups are or-ed together without branching.
+// Only the leftover bytes at the end are checked one at a time.
+fn all_legal(legal: u128, bytes: &[u8]) -> bool {
+    const HIGH_BITS: u64 = 0x8080_8080_8080_8080;
+    let mut chunks = bytes.chunks_exact(8);
+    for chunk in &mut chunks {
+        let word = u64::from_le_bytes(chunk.try_into().expect("chunks are 8 bytes"));
+        if word & HIGH_BITS != 0 { return false }
+        let missing = word.to_le_bytes().iter().fold(0, |missing, &b| missing | !(legal >> b) & 1);
+        if missing != 0 { return false }
+    }
+    chunks.remainder().iter().all(|&b| b < 0x80 && legal >> b & 1 == 1)
+}
+
+// Encode each CHARS character chunk of the string (the last one space padded), walking the chars just once.
+fn encode_words_with<'a, C: Codec + ?Sized>(encode_table: &'a [Option<u8>; 128], s: &'a str) -> impl Iterator<Item = Result<C::Word, Error>> + 'a {
+    let mut chars = s.chars();
+    let mut pos = 0;
+    core::iter::from_fn(move || {
+        if chars.as_str().is_empty() { return None }
+        let mut word_chars = chars.by_ref().take(C::CHARS);
+        let w = encode_chars_with::<C>(encode_table, &mut word_chars, pos);
+        word_chars.for_each(drop); // After an error, so the next word starts in the right place
+        pos += C::CHARS;
+        Some(w)
+    })
+}
+
+fn encode_lossy_with<C: Codec + ?Sized>(encode_table: &[Option<u8>; 128], legal: u128, s: &str) -> (Vec<C::Word>, Vec<Error>) {
+    let errors = validate_with(encode_table, s).err().unwrap_or_default();
+    let cleaned: String = s.chars().map(|c| if char_to_code(encode_table, c).is_some() { c } else { ' ' }).collect();
+    (encode_with::<C>(encode_table, legal, &cleaned).expect("illegal characters were replaced"), errors)
+}
+
+fn validate_with(encode_table: &[Option<u8>; 128], s: &str) -> Result<(), Vec<Error>> {
+    let errors: Vec<Error> = s.chars().enumerate()
+                              .filter_map(|(i, c)| radix50_from_char(encode_table, c, i + 1).err())
+                              .collect();
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+fn step_symbol<C: Codec + ?Sized>(words: &[C::Word], up: bool) -> Option<Vec<C::Word>> {
+    if !words.iter().all(|&w| C::is_valid_word(w)) { return None }
+    let mut out = words.to_vec();
+    for w in out.iter_mut().rev() {
+        let (value, wrapped) = match (up, (*w).into()) {
+            (true,  v) if v == C::MAX_WORD => (0, true),
+            (true,  v)                     => (v + 1, false),
+            (false, 0)                     => (C::MAX_WORD, true),
+            (false, v)                     => (v - 1, false),
+        };
+        *w = C::Word::try_from(value).ok().expect("valid words fit in the machine word");
+        if !wrapped { return Some(out) }
+    }
+    None
+}
+
+fn collate_with(encode_table: &[Option<u8>; 128], a: &str, b: &str) -> core::cmp::Ordering {
+    let key = |c: Option<char>| match c.map(|c| (c, char_to_code(encode_table, c))) {
+        None                  => 0, // Pad with spaces
+        Some((_, Some(code))) => code as u32,
+        Some((c, None))       => 40 + c as u32,
+    };
+    let (mut a, mut b) = (a.chars(), b.chars());
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return core::cmp::Ordering::Equal,
+            (ca, cb)     => match key(ca).cmp(&key(cb)) {
+                core::cmp::Ordering::Equal => continue,
+                ordering                  => return ordering,
+            }
+        }
+    }
+}
+
+fn encode_word_with<C: Codec + ?Sized>(encode_table: &[Option<u8>; 128], s: &str) -> Result<C::Word, Error> {
+    encode_chars_with::<C>(encode_table, &mut s.chars(), 0)
+}
+
+// Encode the next CHARS chars (space padded if they run out) into a word. `pos` is where they start in the string.
+fn encode_chars_with<C: Codec + ?Sized>(encode_table: &[Option<u8>; 128], it: &mut impl Iterator<Item = char>, pos: usize) -> Result<C::Word, Error> {
+    assert_packing::<C>();
+    let mut w = 0_u64;
+    for group in (0..C::CHARS).step_by(3) {
+        w = w * 40_u64.pow(3) + encode16(encode_table, it, pos + group)? as u64;
+    }
+    Ok(C::Word::try_from(w).unwrap_or_else(|_| panic!("{} RADIX-50 characters don't fit in a {}", C::CHARS, core::any::type_name::<C::Word>())))
+}
+
+fn decode_with<C: Codec + ?Sized, I>(decode_table: &[char; 40], words: I) -> String where I: IntoIterator, I::Item: Borrow<C::Word> {
+    words.into_iter().fold(String::new(), |mut s, w| { s.extend(decode_word_chars_with::<C>(decode_table, *w.borrow())); s })
+}
+
+fn decode_lossy_with<C: Codec + ?Sized, I>(decode_table: &[char; 40], words: I, marker: Option<char>) -> (String, Vec<Error>)
+where I: IntoIterator, I::Item: Borrow<C::Word>
+{
+    let mut errors = vec![];
+    let s = words.into_iter().enumerate().fold(String::new(), |mut s, (index, w)| {
+        if !C::is_valid_word(*w.borrow()) {
+            errors.push(Error::WordOutOfRange { word: (*w.borrow()).into(), index });
+            if let Some(marker) = marker {
+                s.extend(core::iter::repeat_n(marker, C::CHARS));
+                return s;
+            }
+        }
+        s.extend(decode_word_chars_with::<C>(decode_table, *w.borrow())); s
+    });
+    (s, errors)
+}
+
+fn decode_into_slice_with<'a, C: Codec + ?Sized, I>(decode_table: &[char; 40], words: I, out: &'a mut [u8]) -> Result<&'a str, Error>
+where I: IntoIterator, I::Item: Borrow<C::Word>
+{
+    // How many bytes a word decodes to (CHARS, unless the table has non-ASCII characters)
+    let word_len = |w: C::Word| decode_word_chars_with::<C>(decode_table, w).map(char::len_utf8).sum::<usize>();
+    let mut len = 0;
+    let mut words = words.into_iter();
+    while let Some(w) = words.next() {
+        let w = *w.borrow();
+        if len + word_len(w) > out.len() {
+            return Err(Error::InputTooLong { len: len + word_len(w) + words.map(|w| word_len(*w.borrow())).sum::<usize>(), max: out.len() })
+        }
+        for c in decode_word_chars_with::<C>(decode_table, w) {
+            len += c.encode_utf8(&mut out[len..]).len();
+        }
+    }
+    Ok(core::str::from_utf8(&out[..len]).expect("only whole chars were written"))
+}
+
+fn decode_fmt_with<C: Codec + ?Sized, I, W>(decode_table: &[char; 40], words: I, out: &mut W) -> core::fmt::Result
+where I: IntoIterator, I::Item: Borrow<C::Word>, W: core::fmt::Write + ?Sized
+{
+    for w in words {
+        for c in decode_word_chars_with::<C>(decode_table, *w.borrow()) {
+            out.write_char(c)?;
+        }
+    }
+    Ok(())
+}
+
+// The CHARS characters of a word, most significant first.
+fn decode_word_chars_with<C: Codec + ?Sized>(decode_table: &[char; 40], w: C::Word) -> impl Iterator<Item = char> + '_ {
+    assert_packing::<C>();
+    word_groups(w.into(), C::CHARS).flat_map(|group| decode16(decode_table, group))
+}
+
+// Words are packed and unpacked in 3 character groups, so a codec whose CHARS isn't a multiple of 3 would silently
+// mis-pack, as would one whose Word can't hold 40^CHARS-1. Every packing and unpacking function calls this, so
+// using a codec like that is a compile error.
+const fn assert_packing<C: Codec + ?Sized>() {
+    const {
+        assert!(C::CHARS > 0 && C::CHARS % 3 == 0, "Codec::CHARS must be a multiple of 3");
+        assert!(C::CHARS <= 12 && (core::mem::size_of::<C::Word>() >= 8
+                                   || (40_u64.pow(C::CHARS as u32) - 1) >> (8 * core::mem::size_of::<C::Word>()) == 0),
+                "Codec::Word is too small to hold Codec::CHARS characters");
+    }
+}
+
+// The 3 character groups of a `chars` character word (each one a 16 bit word's worth), most significant first.
+// The top group of a word bigger than the codec's MAX_WORD is truncated to 16 bits (not reduced mod 40^3), which is
+// how decode has always treated them.
+fn word_groups(w: u64, chars: usize) -> impl Iterator<Item = u16> {
+    let top = chars/3 - 1;
+    (0..chars/3).rev().map(move |group| match w / 40_u64.pow(3 * group as u32) {
+        g if group == top => g as u16,
+        g                 => (g % 40_u64.pow(3)) as u16,
+    })
+}
+
+fn decode_bulk_with<C: Codec + ?Sized>(decode_table: &[char; 40], words: &[C::Word]) -> String {
+    assert_packing::<C>();
+    let mut s = String::with_capacity(words.len() * C::CHARS);
+    let groups = words.iter().flat_map(|&w| word_groups(w.into(), C::CHARS));
+    #[cfg(feature = "simd")]
+    let groups = {
+        let mut groups = groups;
+        loop {
+            let mut batch = [0; 8];
+            let mut n = 0;
+            for (slot, group) in batch.iter_mut().zip(&mut groups) { *slot = group; n += 1 }
+            s.extend(decode16x8(batch)[..n].iter().flatten().map(|&c| decode_table[c as usize]));
+            if n < batch.len() { break groups }
+        }
+    };
+    // Without simd this is everything. With it, there's nothing left.
+    s.extend(groups.flat_map(|group| decode16(decode_table, group)));
+    s
+}
+
+fn decode_word_with<C: Codec + ?Sized>(decode_table: &[char; 40], w: C::Word) -> String {
+    let mut s = String::with_capacity(C::CHARS);
+    s.extend(decode_word_chars_with::<C>(decode_table, w));
+    s
+}
+
+fn encode16(encode_table: &[Option<u8>; 128], it: &mut impl Iterator<Item = char>, pos: usize) -> Result<u16, Error> {
+    let c = [radix50_from_char(encode_table, it.next().unwrap_or(' '), pos + 1)?,
+             radix50_from_char(encode_table, it.next().unwrap_or(' '), pos + 2)?,
+             radix50_from_char(encode_table, it.next().unwrap_or(' '), pos + 3)?];
+    Ok(c[0] as u16 * 40_u16.pow(2) +
+        c[1] as u16 * 40_u16.pow(1) +
+        c[2] as u16 * 40_u16.pow(0))
+}
+
+fn radix50_from_char(encode_table: &[Option<u8>; 128], c: char, pos: usize) -> Result<u8, Error> {
+    char_to_code(encode_table, c).ok_or(Error::IllegalChar { char: c, pos })
+}
+
+// Decode 3 characters onto the stack. Callers that need a string push them onto it (which also keeps this correct for
+// Codecs with non-ASCII tables).
+fn decode16(decode_table: &[char; 40], w: u16) -> [char; 3] {
+    #[cfg(feature = "decode-table")]
+    if let Some(codes) = DECODE16_CODES.get(w as usize) {
+        return codes.map(|c| decode_table[c as usize]);
+    }
+    [decode_table[(w / 40_u16.pow(2) % 40) as usize],
+     decode_table[(w / 40_u16.pow(1) % 40) as usize],
+     decode_table[(w / 40_u16.pow(0) % 40) as usize]]
+}
+
+// The character codes of 8 16 bit words at once. Dividing by 40 is a multiply and a shift, which is exact for
+// anything that fits in 16 bits.
+#[cfg(feature = "simd")]
+fn decode16x8(words: [u16; 8]) -> [[u8; 3]; 8] {
+    use wide::u32x8;
+    let forty = u32x8::splat(40);
+    let div40 = |x: u32x8| (x * u32x8::splat(52429)) >> 21;
+    let w  = u32x8::new(words.map(u32::from));
+    let q1 = div40(w);
+    let q2 = div40(q1);
+    let [c0, c1, c2] = [q2 - div40(q2) * forty, q1 - q2 * forty, w - q1 * forty].map(u32x8::to_array);
+    core::array::from_fn(|i| [c0[i] as u8, c1[i] as u8, c2[i] as u8])
+}
+
+// The three character codes of every valid 16 bit word (PDP-10 words are decoded as two of these), so decoding is
+// a lookup instead of a chain of divides. It's codes and not characters so that every character table can share it.
+#[cfg(feature = "decode-table")]
+static DECODE16_CODES: [[u8; 3]; 64000] = decode16_codes();
+
+#[cfg(feature = "decode-table")]
+const fn decode16_codes() -> [[u8; 3]; 64000] {
+    let mut out = [[0; 3]; 64000];
+    const_for!(w in 0..64000 => {
+        out[w] = [(w / 40_usize.pow(2) % 40) as u8, (w / 40 % 40) as u8, (w % 40) as u8];
+    });
+    out
+}
+
+/// A RADIX-50 encoding, for picking the codec at runtime.
+///
+/// See [`encode`] and [`decode`].
+#[cfg(all(feature = "pdp10", feature = "pdp11"))]
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum Encoding {
+    /// [PDP-10 RADIX-50][pdp10::RADIX50_DECODE]: 6 characters per 32 bit word
+    Pdp10,
+    /// [PDP-11 RADIX-50][pdp11::RADIX50_DECODE]: 3 characters per 16 bit word
+    Pdp11,
+}
+
+#[cfg(all(feature = "pdp10", feature = "pdp11"))]
+impl Encoding {
+    /// Every encoding, in a stable order.
+    pub const ALL: [Encoding; 2] = [Encoding::Pdp10, Encoding::Pdp11];
+
+    /// The canonical name of the encoding (what [`by_name`] accepts).
+    pub fn name(self) -> &'static str {
+        self.aliases()[0]
+    }
+
+    /// All the names [`by_name`] accepts for the encoding, canonical name first.
+    pub fn aliases(self) -> &'static [&'static str] {
+        match self {
+            Encoding::Pdp10 => &["pdp10", "pdp-10", "pdp6", "pdp-6", "decsystem-10", "decsystem-20"],
+            Encoding::Pdp11 => &["pdp11", "pdp-11", "vax"],
+        }
+    }
+
+    /// The number of characters encoded in each word.
+    pub fn chars_per_word(self) -> usize {
+        match self { Encoding::Pdp10 => pdp10::Pdp10::CHARS, Encoding::Pdp11 => pdp11::Pdp11::CHARS }
+    }
+
+    /// The largest valid word. See [`pdp10::MAX_WORD`] and [`pdp11::MAX_WORD`].
+    pub fn max_word(self) -> u64 {
+        match self { Encoding::Pdp10 => pdp10::MAX_WORD as u64, Encoding::Pdp11 => pdp11::MAX_WORD as u64 }
+    }
+
+    /// Iterate over the encoding's character set as `(code, char)` pairs. See [`pdp10::charset`] and
+    /// [`pdp11::charset`].
+    pub fn charset(self) -> impl Iterator<Item = (u8, char)> {
+        charset(match self { Encoding::Pdp10 => &pdp10::RADIX50_DECODE, Encoding::Pdp11 => &pdp11::RADIX50_DECODE })
+    }
+
+    /// The size of a word in bytes when it's packed into a byte stream.
+    pub fn word_bytes(self) -> usize {
+        match self { Encoding::Pdp10 => core::mem::size_of::<u32>(), Encoding::Pdp11 => core::mem::size_of::<u16>() }
+    }
+}
+
+/// Look up an [`Encoding`] by name.
+///
+/// The lookup is case insensitive and accepts the names in [`Encoding::aliases`]. This is for configuration
+/// driven tools that need to select an encoding from a string.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Encoding, by_name};
+/// assert_eq!(by_name("pdp11"), Some(Encoding::Pdp11));
+/// assert_eq!(by_name("PDP-10"), Some(Encoding::Pdp10));
+/// assert_eq!(by_name("VAX"), Some(Encoding::Pdp11));
+/// assert_eq!(by_name("ebcdic"), None);
+///
+/// for encoding in Encoding::ALL {
+///     assert_eq!(by_name(encoding.name()), Some(encoding));
+/// }
+/// ```
+#[cfg(all(feature = "pdp10", feature = "pdp11"))]
+pub fn by_name(name: &str) -> Option<Encoding> {
+    Encoding::ALL.into_iter().find(|e| e.aliases().iter().any(|alias| alias.eq_ignore_ascii_case(name)))
+}
+
+#[cfg(all(feature = "pdp10", feature = "pdp11"))]
+impl core::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Encode a string using an [`Encoding`] chosen at runtime.
+///
+/// This is the same as calling [`pdp10::encode`] or [`pdp11::encode`], except the words are always returned
+/// as [`u64`]s.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Encoding, encode};
+/// assert_eq!(encode(Encoding::Pdp10, "THIS IS A TEST").unwrap(), [3119342419, 2970305215, 3046400000]);
+/// assert_eq!(encode(Encoding::Pdp11, "THIS IS A TEST").unwrap(), [32329, 30409, 30401, 805, 31200]);
+/// ```
+#[cfg(all(feature = "pdp10", feature = "pdp11"))]
+pub fn encode(encoding: Encoding, s: &str) -> Result<Vec<u64>, Error> {
+    Ok(match encoding {
+        Encoding::Pdp10 => pdp10::encode(s)?.into_iter().map(u64::from).collect(),
+        Encoding::Pdp11 => pdp11::encode(s)?.into_iter().map(u64::from).collect(),
+    })
+}
+
+/// Decode words using an [`Encoding`] chosen at runtime.
+///
+/// This is the same as calling [`pdp10::decode`] or [`pdp11::decode`], except the words are given as
+/// [`u64`]s. It will return an [`Error::WordOutOfRange`] if a word is too big for the encoding's machine word.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Encoding, Error, decode};
+/// assert_eq!(decode(Encoding::Pdp10, [3119342419, 2970305215, 3046400000]).unwrap(), "THIS IS A TEST    ");
+/// assert_eq!(decode(Encoding::Pdp11, [32329, 30409, 30401, 805, 31200]).unwrap(), "THIS IS A TEST ");
+/// assert_eq!(decode(Encoding::Pdp11, [32329, 3119342419]), Err(Error::WordOutOfRange { word: 3119342419, index: 1 }));
+/// ```
+#[cfg(all(feature = "pdp10", feature = "pdp11"))]
+pub fn decode<I>(encoding: Encoding, words: I) -> Result<String, Error> where I: IntoIterator, I::Item: Borrow<u64> {
+    fn narrow<I, W>(words: I) -> Result<Vec<W>, Error> where I: IntoIterator, I::Item: Borrow<u64>, W: TryFrom<u64> {
+        words.into_iter().enumerate()
+            .map(|(index, w)| { let word = *w.borrow(); W::try_from(word).map_err(|_| Error::WordOutOfRange { word, index }) })
+            .collect()
+    }
+    Ok(match encoding {
+        Encoding::Pdp10 => pdp10::decode(narrow::<_, u32>(words)?),
+        Encoding::Pdp11 => pdp11::decode(narrow::<_, u16>(words)?),
+    })
+}
+
+/// Compare two strings in the collation order of an [`Encoding`] chosen at runtime. See [`pdp10::cmp`] and
+/// [`pdp11::cmp`].
+///
+/// # Examples
+/// ```
+/// # use std::cmp::Ordering;
+/// # use radix50::{cmp,Encoding};
+/// assert_eq!(cmp(Encoding::Pdp10, "A", "1"), Ordering::Greater);
+/// assert_eq!(cmp(Encoding::Pdp11, "A", "1"), Ordering::Less);
+/// ```
+#[cfg(all(feature = "pdp10", feature = "pdp11"))]
+pub fn cmp(encoding: Encoding, a: &str, b: &str) -> core::cmp::Ordering {
+    match encoding { Encoding::Pdp10 => pdp10::cmp(a, b), Encoding::Pdp11 => pdp11::cmp(a, b) }
+}
+
+/// Sort strings into the collation order of an [`Encoding`] chosen at runtime. See [`pdp10::sort`] and
+/// [`pdp11::sort`].
+///
+/// # Examples
+/// ```
+/// # use radix50::{sort,Encoding};
+/// let mut names = vec!["B".to_string(), "1".to_string(), "A".to_string()];
+/// sort(Encoding::Pdp11, &mut names);
+/// assert_eq!(names, ["A", "B", "1"]);
+/// ```
+#[cfg(all(feature = "pdp10", feature = "pdp11"))]
+pub fn sort<S: AsRef<str>>(encoding: Encoding, strings: &mut [S]) {
+    strings.sort_by(|a, b| cmp(encoding, a.as_ref(), b.as_ref()))
+}
+
+/// Byte order of multi-byte words in a byte stream.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum Endian {
+    /// Most significant byte first
+    Big,
+    /// Least significant byte first (the native PDP-11 order)
+    Little,
+    /// The PDP-11's order for 32 bit numbers ("PDP endian"): 16 bit halves most significant first, each one least
+    /// significant byte first, so 0x12345678 is stored as 34 12 78 56. For 16 bit words it's the same as
+    /// [`Endian::Little`].
+    Pdp,
+}
 
 /// RADIX-50 Encoding Errors
-#[derive(Debug,Clone,PartialEq)]
+///
+/// This is a small `Copy` type that never allocates, so it's cheap to return from hot paths and suitable for
+/// embedded targets. Anything that needs to carry more context (source snippets, lists of errors) is kept out of
+/// it, in separate types.
+///
+/// New variants may be added in future releases, so matches on it need a wildcard arm.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum Error {
     /// The given character (at `pos` offset (1-based) in the original string) isn't part of the valid
     /// RADIX-50 character set ([pdp-10][`pdp10::RADIX50_DECODE`]/[pdp-11][`pdp11::RADIX50_DECODE`])
-    IllegalChar { char: char, pos: usize }
+    IllegalChar { char: char, pos: usize },
+    /// A custom character table has a non-ASCII character at `pos` (1-based).
+    NonAsciiChar { char: char, pos: usize },
+    /// A custom character table has a character at `pos` (1-based) that already appeared earlier in the table.
+    DuplicateChar { char: char, pos: usize },
+    /// The word at `index` (0-based) is too big for the encoding's machine word, or too big to be a valid
+    /// RADIX-50 word.
+    WordOutOfRange { word: u64, index: usize },
+    /// The input was `len` characters long but only `max` characters fit.
+    InputTooLong { len: usize, max: usize },
+    /// A byte stream ended with `len` bytes that don't make up a whole word.
+    TrailingBytes { len: usize },
+    /// A record needed `needed` bytes but the input ended after `len`.
+    Truncated { len: usize, needed: usize },
+    /// The byte at `offset` (0-based) of a paper tape image has the wrong parity bit.
+    Parity { offset: usize },
 }
 
-impl std::error::Error for Error {
+// Keep Error tiny: it's returned by value everywhere.
+const _: () = assert!(core::mem::size_of::<Error>() <= 24);
+
+// core::error::Error (rather than std's re-export of it) so that it's there without std, too.
+impl core::error::Error for Error {
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Error::IllegalChar {char, pos} => write!(f, "Illegal character '{}' ({}) at position {}", char, *char as u32, pos),
+            Error::NonAsciiChar {char, pos} => write!(f, "Non-ASCII character '{}' ({}) at position {} of character table", char, *char as u32, pos),
+            Error::DuplicateChar {char, pos} => write!(f, "Duplicate character '{}' ({}) at position {} of character table", char, *char as u32, pos),
+            Error::WordOutOfRange {word, index} => write!(f, "Word {} ({}) is out of range", index, word),
+            Error::InputTooLong {len, max} => write!(f, "Input is {} characters long but only {} will fit", len, max),
+            Error::TrailingBytes {len} => write!(f, "{} trailing byte{} after the last whole word", len, if *len == 1 { "" } else { "s" }),
+            Error::Truncated {len, needed} => write!(f, "Record is short: it needs {} bytes but only {} are left", needed, len),
+            Error::Parity {offset} => write!(f, "Bad parity on byte {}", offset),
         }
     }
 }
 
+impl Error {
+    /// Render the error for a person, pointing at the problem in `input` (the string that was being encoded).
+    ///
+    /// For an [`Error::IllegalChar`] this is the [`Display`](std::fmt::Display) message, then the line of `input`
+    /// with the bad character, with a caret under it, then the characters that `C` can encode. Other errors just
+    /// get the message.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[cfg(feature = "pdp11")] {
+    /// # use radix50::{pdp11::{self, Pdp11}};
+    /// let input = "FIRST LINE\nSECOND_LINE";
+    /// let errors = pdp11::validate(input).unwrap_err(); // The newline is illegal too
+    /// assert_eq!(errors[1].render::<Pdp11>(input), "\
+    /// Illegal character '_' (95) at position 18 (line 2, column 7)
+    ///   |
+    /// 2 | SECOND_LINE
+    ///   |       ^
+    /// Valid characters are \" ABCDEFGHIJKLMNOPQRSTUVWXYZ$.%0123456789\"
+    /// ");
+    /// # }
+    /// ```
+    pub fn render<C: Codec>(&self, input: &str) -> String {
+        let Error::IllegalChar { pos, .. } = *self else { return format!("{}\n", self) };
+        let Some(line_start) = pos.checked_sub(1).and_then(|i| input.char_indices().nth(i)).map(|(i, _)| input[..i].rfind('\n').map_or(0, |nl| nl + 1)) else {
+            return format!("{}\n", self);
+        };
+        let line_number = input[..line_start].matches('\n').count() + 1;
+        let line = input[line_start..].split('\n').next().unwrap_or_default().trim_end_matches('\r');
+        let column = pos - input[..line_start].chars().count();
+        // Keep tabs in the caret line so the caret still lines up.
+        let indent: String = line.chars().take(column - 1).map(|c| if c == '\t' { '\t' } else { ' ' }).collect();
+        let gutter = " ".repeat(line_number.to_string().len());
+        format!("{self} (line {line_number}, column {column})\n{gutter} |\n{line_number} | {line}\n{gutter} | {indent}^\nValid characters are {:?}\n",
+                String::from_iter(C::DECODE))
+    }
+}
+
+// Labels are spans of bytes, but an Error's position counts chars, so it takes the source text to make one. That's
+// why the label comes from ErrorWithSource (see Error::with_source()).
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn core::fmt::Display + 'a>> {
+        Some(Box::new(match self {
+            Error::IllegalChar { .. }    => "radix50::illegal_char",
+            Error::NonAsciiChar { .. }   => "radix50::non_ascii_char",
+            Error::DuplicateChar { .. }  => "radix50::duplicate_char",
+            Error::WordOutOfRange { .. } => "radix50::word_out_of_range",
+            Error::InputTooLong { .. }   => "radix50::input_too_long",
+            Error::TrailingBytes { .. }  => "radix50::trailing_bytes",
+            Error::Truncated { .. }      => "radix50::truncated",
+            Error::Parity { .. }         => "radix50::parity",
+        }))
+    }
+}
+
+#[cfg(feature = "miette")]
+impl Error {
+    /// Attach the text that was being encoded (or the character table, for a table error), so that a
+    /// [miette](https://docs.rs/miette) report can label the offending character.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[cfg(feature = "pdp11")] {
+    /// # use radix50::pdp11;
+    /// # use miette::Diagnostic;
+    /// let input = "ÀB_C";
+    /// let error = pdp11::encode("AB_C").unwrap_err().with_source(input);
+    /// let label = error.labels().unwrap().next().unwrap();
+    /// assert_eq!(&input[label.offset()..][..label.len()], "_");
+    /// # }
+    /// ```
+    pub fn with_source(self, source: &str) -> ErrorWithSource { ErrorWithSource { error: self, source: source.into() } }
+}
+
+/// An [`Error`] with the text it's about, from [`Error::with_source`]. Its [`miette::Diagnostic`] impl has the text
+/// as its source code, and labels the offending character in it.
+#[cfg(feature = "miette")]
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct ErrorWithSource {
+    /// The error.
+    pub error: Error,
+    /// The text that `error`'s position is in.
+    pub source: String,
+}
+
+#[cfg(feature = "miette")]
+impl core::fmt::Display for ErrorWithSource {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { self.error.fmt(f) }
+}
+
+#[cfg(feature = "miette")]
+impl core::error::Error for ErrorWithSource {
+}
+
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for ErrorWithSource {
+    fn code<'a>(&'a self) -> Option<Box<dyn core::fmt::Display + 'a>> { self.error.code() }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> { Some(&self.source) }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        let (label, char, pos) = match self.error {
+            Error::IllegalChar { char, pos }   => ("not a RADIX-50 character", char, pos),
+            Error::NonAsciiChar { char, pos }  => ("not ASCII", char, pos),
+            Error::DuplicateChar { char, pos } => ("already in the table", char, pos),
+            Error::WordOutOfRange { .. } | Error::InputTooLong { .. } | Error::TrailingBytes { .. } | Error::Truncated { .. }
+                | Error::Parity { .. } => return None,
+        };
+        // Positions are 1-based chars, so position 0 (or one past the end of the text) has nothing to label
+        let (start, _) = self.source.char_indices().nth(pos.checked_sub(1)?)?;
+        Some(Box::new(core::iter::once(miette::LabeledSpan::at(start..start + char.len_utf8(), label))))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
     #[test]
     fn encode_words() {
         assert_eq!(pdp11::encode_word("999").expect("bad char"), 63999);
@@ -471,6 +2269,7 @@ mod tests {
         assert_eq!(pdp10::encode_word("SYMBOL").expect("bad char"), 0o466633472376_u64 as u32);
     }
 
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
     #[test]
     fn decode_words() {
         assert_eq!(pdp11::decode_word( 1683),"ABC");
@@ -514,6 +2313,90 @@ mod tests {
         assert_eq!(pdp11::decode_word(0o63440), "PT ");
     }
 
+    #[cfg(feature = "pdp11")]
+    #[test]
+    fn decode16_every_word() {
+        // Checks the decode-table lookup (when it's on) against the arithmetic
+        for w in 0..=u16::MAX {
+            let codes = [w / 1600 % 40, w / 40 % 40, w % 40];
+            assert_eq!(decode16(&pdp11::RADIX50_DECODE, w), codes.map(|c| pdp11::RADIX50_DECODE[c as usize]));
+        }
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn encode_fast_path() {
+        // The fast path has to agree with the char at a time path, including on where the first error is
+        let slow10 = |s: &str| encode_words_with::<pdp10::Pdp10>(&pdp10::Pdp10::ENCODE, s).collect::<Result<Vec<_>, _>>();
+        let slow11 = |s: &str| encode_words_with::<pdp11::Pdp11>(&pdp11::Pdp11::ENCODE, s).collect::<Result<Vec<_>, _>>();
+        for golden in testdata::CORPUS {
+            assert_eq!(pdp10::encode(golden.text), slow10(golden.text));
+            assert_eq!(pdp11::encode(golden.text), slow11(golden.text));
+        }
+        let text = "THIS IS A MUCH LONGER TEST$STRING.WITH%12345";
+        for pos in 0..text.len() {
+            for bad in ["a", "_", "\x7f", "é"] {
+                let s = format!("{}{}{}", &text[..pos], bad, &text[pos..]);
+                assert_eq!(pdp10::encode(&s), slow10(&s));
+                assert_eq!(pdp11::encode(&s), slow11(&s));
+            }
+            assert_eq!(pdp11::encode(&text[..pos]), slow11(&text[..pos]));
+        }
+    }
+
+    #[cfg(all(feature = "rayon", feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn parallel() {
+        for golden in testdata::CORPUS {
+            assert_eq!(pdp11::par_encode(golden.text), pdp11::encode(golden.text));
+            assert_eq!(pdp10::par_encode(golden.text), pdp10::encode(golden.text));
+            assert_eq!(pdp11::par_decode(golden.pdp11), pdp11::decode(golden.pdp11));
+        }
+        // Several pieces, with a multibyte char so the pieces' byte and char lengths differ
+        let mut text = "ABC.123$%".repeat(PAR_CHUNK_WORDS);
+        assert_eq!(pdp10::par_encode(&text), pdp10::encode(&text));
+        assert_eq!(pdp11::par_decode(&pdp11::encode(&text).unwrap()), text);
+        text.insert(10, 'é');
+        text.push('a');
+        assert_eq!(pdp11::par_encode(&text), Err(Error::IllegalChar { char: 'é', pos: 11 }));
+        text.remove(10);
+        assert_eq!(pdp11::par_encode(&text), Err(Error::IllegalChar { char: 'a', pos: text.len() }));
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn decode_bulk() {
+        for golden in testdata::CORPUS {
+            assert_eq!(pdp11::decode_bulk(golden.pdp11), pdp11::decode(golden.pdp11));
+            assert_eq!(pdp10::decode_bulk(golden.pdp10), pdp10::decode(golden.pdp10));
+        }
+        // Every word (including the invalid ones), and lengths that aren't a whole number of SIMD batches
+        let words: Vec<u16> = (0..=u16::MAX).collect();
+        for len in [0, 1, 7, 8, 9, words.len()] {
+            assert_eq!(pdp11::decode_bulk(&words[..len]), pdp11::decode(&words[..len]));
+        }
+        let words = [0, 1, 1157975016, 3676161683, 4095999999, 4096000000, u32::MAX];
+        assert_eq!(pdp10::decode_bulk(&words), pdp10::decode(words));
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn decode_word_chars() {
+        assert_eq!(pdp11::decode_word_chars( 1683), ['A', 'B', 'C']);
+        assert_eq!(pdp11::decode_word_chars(41040), ['Y', 'Z', ' ']);
+        assert_eq!(pdp11::decode_word_chars(63547), ['9', '.', '$']);
+        assert_eq!(pdp10::decode_word_chars(1157975016), ['A', 'B', 'C', 'D', 'E', 'F']);
+        assert_eq!(pdp10::decode_word_chars(1121214400), ['9', '.', '$', '%', ' ', ' ']);
+
+        for w in [0, 1683, 31221, 63999] {
+            assert_eq!(pdp11::decode_word_chars(w).iter().collect::<String>(), pdp11::decode_word(w));
+        }
+        for w in [0, 1157975016, 3676161683, 0o126633472376_u64 as u32] {
+            assert_eq!(pdp10::decode_word_chars(w).iter().collect::<String>(), pdp10::decode_word(w));
+        }
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
     #[test]
     fn encode_strings() {
         assert_eq!(pdp10::encode("THIS IS A TEST").expect("bad char"), [3119342419, 2970305215, 3046400000]);
@@ -524,11 +2407,452 @@ mod tests {
         assert_eq!(pdp11::encode("_HIS IS A TEST"), Err(Error::IllegalChar{ char: '_', pos:  1 }));
         assert_eq!(pdp11::encode("THIS _S A TEST"), Err(Error::IllegalChar{ char: '_', pos:  6 }));
         assert_eq!(pdp11::encode("THIS IS A TES_"), Err(Error::IllegalChar{ char: '_', pos: 14 }));
+        // Positions count chars, not bytes
+        assert_eq!(pdp11::encode("ÅBCDÉF"), Err(Error::IllegalChar{ char: 'Å', pos: 1 }));
+        assert_eq!(pdp11::encode("ABCDÉF"), Err(Error::IllegalChar{ char: 'É', pos: 5 }));
+        assert_eq!(pdp10::encode_into_slice("ABCDEFÅ", &mut [0; 2]), Err(Error::IllegalChar{ char: 'Å', pos: 7 }));
+        // A bad word doesn't throw off the ones after it
+        assert_eq!(encode_words_with::<pdp11::Pdp11>(&pdp11::Pdp11::ENCODE, "AÉCDEF").collect::<Vec<_>>(),
+                   [Err(Error::IllegalChar{ char: 'É', pos: 2 }), pdp11::encode_word("DEF")]);
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn runtime_encoding() {
+        assert_eq!(encode(Encoding::Pdp10, "THIS IS A TEST"), Ok(pdp10::encode("THIS IS A TEST").unwrap().into_iter().map(u64::from).collect()));
+        assert_eq!(encode(Encoding::Pdp11, "THIS IS A TEST"), Ok(pdp11::encode("THIS IS A TEST").unwrap().into_iter().map(u64::from).collect()));
+        assert_eq!(encode(Encoding::Pdp11, "THIS IS A TES_"), Err(Error::IllegalChar{ char: '_', pos: 14 }));
+        assert_eq!(decode(Encoding::Pdp10, [3119342419, 2970305215, 3046400000].iter()), Ok("THIS IS A TEST    ".to_string()));
+        assert_eq!(decode(Encoding::Pdp11, vec![32329, 30409, 30401, 805, 31200]), Ok("THIS IS A TEST ".to_string()));
+        assert_eq!(decode(Encoding::Pdp11, [65535, 65536]), Err(Error::WordOutOfRange{ word: 65536, index: 1 }));
+        assert_eq!(decode(Encoding::Pdp10, [1 << 32]), Err(Error::WordOutOfRange{ word: 1 << 32, index: 0 }));
+        assert_eq!(Encoding::Pdp10.chars_per_word(), 6);
+        assert_eq!(Encoding::Pdp11.chars_per_word(), 3);
+        assert_eq!(Encoding::Pdp10.word_bytes(), 4);
+        assert_eq!(Encoding::Pdp11.word_bytes(), 2);
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn charsets() {
+        assert_eq!(pdp10::charset().count(), 40);
+        assert_eq!(pdp11::charset().count(), 40);
+        for (code, c) in pdp10::charset() {
+            assert_eq!(pdp10::RADIX50_DECODE[code as usize], c);
+        }
+        for (code, c) in pdp11::charset() {
+            assert_eq!(pdp11::RADIX50_DECODE[code as usize], c);
+            assert_eq!(pdp11::encode_word(&c.to_string()), Ok(code as u16 * 1600));
+        }
+        for c in 0..128_u8 {
+            assert_eq!(pdp10::Pdp10::LEGAL >> c & 1 == 1, pdp10::char_to_code(c as char).is_some());
+            assert_eq!(pdp11::Pdp11::LEGAL >> c & 1 == 1, pdp11::char_to_code(c as char).is_some());
+        }
+        assert!(Encoding::Pdp10.charset().eq(pdp10::charset()));
+        assert!(Encoding::Pdp11.charset().eq(pdp11::charset()));
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn legal_fast_path() {
+        // The 8 byte at a time check agrees with checking each byte, wherever the bad byte is
+        let scalar = |legal: u128, bytes: &[u8]| bytes.iter().all(|&b| b < 0x80 && legal >> b & 1 == 1);
+        for len in 0..27 {
+            let good = vec![b'A'; len];
+            assert!(all_legal(pdp11::Pdp11::LEGAL, &good));
+            for at in 0..len {
+                for bad in [b'_', b'a', 0, 0x7f, 0x80, 0xc3, 0xff] {
+                    let mut bytes = good.clone();
+                    bytes[at] = bad;
+                    assert_eq!(all_legal(pdp11::Pdp11::LEGAL, &bytes), scalar(pdp11::Pdp11::LEGAL, &bytes));
+                    assert_eq!(all_legal(pdp10::Pdp10::LEGAL, &bytes), scalar(pdp10::Pdp10::LEGAL, &bytes));
+                }
+            }
+        }
+        // And the fast path encodes the same as the char by char path
+        for golden in testdata::CORPUS {
+            let slow = |s: &str| encode_words_with::<pdp11::Pdp11>(&pdp11::Pdp11::ENCODE, s).collect::<Result<Vec<_>, _>>();
+            assert_eq!(encode_legal_with::<pdp11::Pdp11>(&pdp11::Pdp11::ENCODE, pdp11::Pdp11::LEGAL, golden.text.as_bytes()),
+                       slow(golden.text).ok());
+        }
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn char_codes() {
+        for code in 0..40 {
+            assert_eq!(pdp10::char_to_code(pdp10::code_to_char(code).unwrap()), Some(code));
+            assert_eq!(pdp11::char_to_code(pdp11::code_to_char(code).unwrap()), Some(code));
+        }
+        for code in 40..=255 {
+            assert_eq!(pdp10::code_to_char(code), None);
+            assert_eq!(pdp11::code_to_char(code), None);
+        }
+        for c in ['_', 'a', '\0', '\u{7f}', '\u{80}', 'é', '💾'] {
+            assert_eq!(pdp10::char_to_code(c), None);
+            assert_eq!(pdp11::char_to_code(c), None);
+        }
+        assert_eq!(pdp10::char_to_code('0'), Some(1));
+        assert_eq!(pdp11::char_to_code('0'), Some(30));
+        assert_eq!(pdp11::Pdp11::char_to_code('$'), Some(27));
+        assert_eq!(pdp10::Pdp10::code_to_char(38), Some('$'));
+        const CODE: Option<u8> = pdp11::char_to_code('Z');
+        assert_eq!(CODE, Some(26));
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn encoding_names() {
+        assert_eq!(by_name("pdp10"),        Some(Encoding::Pdp10));
+        assert_eq!(by_name("DECsystem-20"), Some(Encoding::Pdp10));
+        assert_eq!(by_name("Pdp-11"),       Some(Encoding::Pdp11));
+        assert_eq!(by_name("pdp 11"),       None);
+        assert_eq!(by_name(""),             None);
+        assert_eq!(Encoding::Pdp10.to_string(), "pdp10");
+        assert_eq!(Encoding::Pdp11.to_string(), "pdp11");
+        for encoding in Encoding::ALL {
+            for alias in encoding.aliases() {
+                assert_eq!(by_name(alias), Some(encoding));
+            }
+        }
+    }
+
+    #[cfg(feature = "pdp10")]
+    #[test]
+    fn custom_codec() {
+        // 9 characters in a u64, PDP-10 style
+        struct Wide;
+        impl Codec for Wide {
+            type Word = u64;
+            const CHARS: usize = 9;
+            const DECODE: [char; 40] = pdp10::RADIX50_DECODE;
+        }
+        assert_eq!(Wide::encode_word("ABCDEFGHI").expect("bad char"), 1157975016 * 64000 + 27939);
+        assert_eq!(Wide::decode_word(1157975016 * 64000 + 27939), "ABCDEFGHI");
+        assert_eq!(Wide::encode("THIS IS A TEST"), Ok(vec![Wide::encode_word("THIS IS A").unwrap(), Wide::encode_word(" TEST").unwrap()]));
+        assert_eq!(Wide::encode("THIS IS_A TEST"), Err(Error::IllegalChar{ char: '_', pos: 8 }));
+        assert_eq!(Wide::decode(Wide::encode("THIS IS A TEST").unwrap()), "THIS IS A TEST    ");
+    }
+
+    #[cfg(feature = "pdp11")]
+    #[test]
+    fn non_ascii_table() {
+        // The PDP-11 table with an 'Ä' where the '%' goes
+        struct Umlaut;
+        impl Codec for Umlaut {
+            type Word = u16;
+            const CHARS: usize = 3;
+            const DECODE: [char; 40] = { let mut t = pdp11::RADIX50_DECODE; t[29] = 'Ä'; t };
+        }
+        let a_umlaut_b = 1600 + 29 * 40 + 2;
+        assert_eq!(Umlaut::decode([a_umlaut_b]), "AÄB");
+        assert_eq!(Umlaut::decode_into_slice([a_umlaut_b], &mut [0; 4]), Ok("AÄB"));
+        assert_eq!(Umlaut::decode_into_slice([a_umlaut_b], &mut [0; 3]), Err(Error::InputTooLong { len: 4, max: 3 }));
+        assert_eq!(Umlaut::decode_into_slice([1683, a_umlaut_b], &mut [0; 5]), Err(Error::InputTooLong { len: 7, max: 5 }));
+        assert_eq!(Umlaut::decode_inline::<7, _>([a_umlaut_b, 1683]).unwrap(), "AÄBABC");
+        assert_eq!(Umlaut::decode_exact([a_umlaut_b], 1), "AÄ");
+        assert_eq!(Umlaut::decode_exact([a_umlaut_b], 2), "A");
+        assert_eq!(Umlaut::decode_exact([a_umlaut_b], 4), "");
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn permutations() {
+        assert!(is_permutation(&pdp11::RADIX50_DECODE, &pdp11::RADIX50_DECODE));
+        assert!(is_permutation(&pdp11::RADIX50_DECODE, &pdp10::RADIX50_DECODE));
+        assert!(is_permutation(&pdp10::RADIX50_DECODE, &pdp11::RADIX50_DECODE));
+
+        let mut swapped = pdp11::RADIX50_DECODE;
+        swapped.swap(27, 28);
+        assert!(is_permutation(&swapped, &pdp11::RADIX50_DECODE));
+
+        let mut foreign = pdp11::RADIX50_DECODE;
+        foreign[0] = '_';
+        assert!(!is_permutation(&foreign, &pdp11::RADIX50_DECODE));
+        assert!(!is_permutation(&pdp11::RADIX50_DECODE, &foreign));
+
+        let mut duplicate = pdp11::RADIX50_DECODE;
+        duplicate[1] = 'B';
+        assert!(!is_permutation(&duplicate, &pdp11::RADIX50_DECODE));
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn max_words() {
+        assert_eq!(pdp10::MAX_WORD as u64, 40_u64.pow(6) - 1);
+        assert_eq!(pdp11::MAX_WORD as u64, 40_u64.pow(3) - 1);
+        assert_eq!(pdp10::decode_word(pdp10::MAX_WORD), "%%%%%%");
+        assert_eq!(pdp11::decode_word(pdp11::MAX_WORD), "999");
+        assert_eq!(pdp10::Pdp10::MAX_WORD, pdp10::MAX_WORD as u64);
+        assert_eq!(pdp11::Pdp11::MAX_WORD, pdp11::MAX_WORD as u64);
+        assert_eq!(pdp10::CHARS_PER_WORD, Encoding::Pdp10.chars_per_word());
+        assert_eq!(pdp11::CHARS_PER_WORD, Encoding::Pdp11.chars_per_word());
+        assert_eq!(Encoding::Pdp10.max_word(), 4_095_999_999);
+        assert_eq!(Encoding::Pdp11.max_word(), 63_999);
+        assert!(pdp11::is_valid_word(63999) && !pdp11::is_valid_word(64000) && !pdp11::is_valid_word(u16::MAX));
+        assert!(pdp10::is_valid_word(4095999999) && !pdp10::is_valid_word(4096000000) && !pdp10::is_valid_word(u32::MAX));
+        // Out of range words decode with their top 3 characters' worth truncated to 16 bits
+        assert_eq!(pdp10::decode_word(u32::MAX), " %BXLE");
+        assert_eq!(pdp10::decode([u32::MAX, 4096000000, 64000 * 65536 + 5]), " %BXLE           4");
+        assert_eq!(pdp10::decode_word_chars(u32::MAX).iter().collect::<String>(), " %BXLE");
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn decode_checked_words() {
+        assert_eq!(pdp11::decode_checked([0, 63999]), Ok("   999".to_string()));
+        assert_eq!(pdp11::decode_checked([0, 64000]), Err(Error::WordOutOfRange{ word: 64000, index: 1 }));
+        // It stops at the first bad word
+        let words = [64000, 0].into_iter().chain(core::iter::from_fn(|| -> Option<u16> { panic!("read past the bad word") }));
+        assert_eq!(pdp11::decode_checked(words), Err(Error::WordOutOfRange{ word: 64000, index: 0 }));
+        assert_eq!(pdp10::decode_checked([0, 4095999999]), Ok("      %%%%%%".to_string()));
+        assert_eq!(pdp10::decode_checked([4096000000, 0]), Err(Error::WordOutOfRange{ word: 4096000000, index: 0 }));
+        assert_eq!(pdp11::decode_checked(core::iter::empty::<u16>()), Ok("".to_string()));
+        for golden in testdata::CORPUS {
+            assert_eq!(pdp10::decode_checked(golden.pdp10), Ok(pdp10::decode(golden.pdp10)));
+            assert_eq!(pdp11::decode_checked(golden.pdp11), Ok(pdp11::decode(golden.pdp11)));
+        }
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn decode_lossy_words() {
+        assert_eq!(pdp11::decode_lossy([65535, 1683, 64000], Some('?')),
+                   ("???ABC???".to_string(), vec![Error::WordOutOfRange{ word: 65535, index: 0 }, Error::WordOutOfRange{ word: 64000, index: 2 }]));
+        assert_eq!(pdp11::decode_lossy([65535, 1683], None),
+                   (pdp11::decode([65535, 1683]), vec![Error::WordOutOfRange{ word: 65535, index: 0 }]));
+        assert_eq!(pdp10::decode_lossy([u32::MAX], Some('#')), ("######".to_string(), vec![Error::WordOutOfRange{ word: u32::MAX as u64, index: 0 }]));
+    }
+
+    #[cfg(feature = "pdp11")]
+    #[test]
+    fn symbols() {
+        for name in ["", "A", "R0", "$DSW", "PRINT", "FOOBAR", " X"] {
+            let words = pdp11::encode_symbol(name).unwrap();
+            assert_eq!(words[..], pdp11::encode(&format!("{:6}", name)).unwrap()[..]);
+            assert_eq!(pdp11::decode_symbol(&words), name);
+        }
+        assert_eq!(pdp11::encode_symbol("AB_"), Err(Error::IllegalChar{ char: '_', pos: 3 }));
+        assert_eq!(pdp11::encode_symbol("ABCD_"), Err(Error::IllegalChar{ char: '_', pos: 5 }));
+        assert_eq!(pdp11::encode_symbol("ABCDEFG_"), Err(Error::InputTooLong{ len: 8, max: 6 }));
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn collation() {
+        use core::cmp::Ordering::*;
+        assert_eq!(pdp11::cmp("", ""), Equal);
+        assert_eq!(pdp11::cmp("", "   "), Equal);
+        assert_eq!(pdp11::cmp("", "A"), Less);
+        assert_eq!(pdp11::cmp("A B", "A"), Greater);
+        assert_eq!(pdp11::cmp("A", "a"), Less);
+        assert_eq!(pdp11::cmp("a", "b"), Less);
+        assert_eq!(pdp11::cmp("9", "_"), Less);
+        // Collation order is word order
+        for a in testdata::CORPUS {
+            for b in testdata::CORPUS {
+                let pad = |s: &str, n| format!("{:1$}", s, n);
+                let len = a.text.len().max(b.text.len());
+                assert_eq!(pdp11::cmp(a.text, b.text), pdp11::encode(&pad(a.text, len)).unwrap().cmp(&pdp11::encode(&pad(b.text, len)).unwrap()));
+                assert_eq!(pdp10::cmp(a.text, b.text), pdp10::encode(&pad(a.text, len)).unwrap().cmp(&pdp10::encode(&pad(b.text, len)).unwrap()));
+            }
+        }
+        let mut strings = ["ZZ", "A", "$", "%", ".", "0", " ", "A_"];
+        pdp11::sort(&mut strings);
+        assert_eq!(strings, [" ", "A", "A_", "ZZ", "$", ".", "%", "0"]);
+        pdp10::sort(&mut strings);
+        assert_eq!(strings, [" ", "0", "A", "A_", "ZZ", ".", "$", "%"]);
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn symbol_steps() {
+        assert_eq!(pdp11::next_symbol(&[0]), Some(vec![1]));
+        assert_eq!(pdp11::next_symbol(&[0, 63999]), Some(vec![1, 0]));
+        assert_eq!(pdp11::prev_symbol(&[1, 0]), Some(vec![0, 63999]));
+        assert_eq!(pdp11::next_symbol(&[63999, 63999]), None);
+        assert_eq!(pdp11::next_symbol(&[64000]), None);
+        assert_eq!(pdp11::prev_symbol(&[0, 64000]), None);
+        assert_eq!(pdp11::next_symbol(&[]), None);
+        assert_eq!(pdp10::next_symbol(&[0, 4095999999]), Some(vec![1, 0]));
+        assert_eq!(pdp10::prev_symbol(&[1, 0]), Some(vec![0, 4095999999]));
+        let mut words = pdp11::encode("A99 98").unwrap();
+        let mut seen = vec![];
+        for _ in 0..4 {
+            seen.push(pdp11::decode(&words));
+            words = pdp11::next_symbol(&words).unwrap();
+        }
+        assert_eq!(seen, ["A99 98", "A99 99", "A99A  ", "A99A A"]);
+        for _ in 0..4 { words = pdp11::prev_symbol(&words).unwrap() }
+        assert_eq!(pdp11::decode(&words), "A99 98");
+        let mut words = pdp11::encode("A99999").unwrap();
+        words = pdp11::next_symbol(&words).unwrap();
+        assert_eq!(pdp11::decode(&words), "B     ");
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn validate_strings() {
+        assert_eq!(pdp10::validate(""), Ok(()));
+        assert_eq!(pdp11::validate("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 .$%"), Ok(()));
+        assert_eq!(pdp10::validate("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 .$%"), Ok(()));
+        assert_eq!(pdp11::validate("_A_"), Err(vec![Error::IllegalChar{ char: '_', pos: 1 }, Error::IllegalChar{ char: '_', pos: 3 }]));
+        assert_eq!(pdp10::validate("ÅB\n"), Err(vec![Error::IllegalChar{ char: 'Å', pos: 1 }, Error::IllegalChar{ char: '\n', pos: 3 }]));
+
+        // The first error is always the one encode() reports
+        for s in ["THIS IS A TES_", "_HIS IS A TEST", "THIS_IS_A_TEST", "tHIS IS A TEST"] {
+            assert_eq!(pdp10::validate(s).unwrap_err()[0], pdp10::encode(s).unwrap_err());
+            assert_eq!(pdp11::validate(s).unwrap_err()[0], pdp11::encode(s).unwrap_err());
+        }
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn encode_lossy_strings() {
+        assert_eq!(pdp10::encode_lossy(""), (vec![], vec![]));
+        assert_eq!(pdp11::encode_lossy("_"), (vec![0], vec![Error::IllegalChar{ char: '_', pos: 1 }]));
+        assert_eq!(pdp11::encode_lossy("ÅBC\n"), (pdp11::encode(" BC ").unwrap(),
+                                                 vec![Error::IllegalChar{ char: 'Å', pos: 1 }, Error::IllegalChar{ char: '\n', pos: 4 }]));
+        assert_eq!(pdp10::encode_lossy("this is a test"), (pdp10::encode("              ").unwrap(),
+                                                           pdp10::validate("this is a test").unwrap_err()));
+        for golden in testdata::CORPUS {
+            assert_eq!(pdp10::encode_lossy(golden.text), (golden.pdp10.to_vec(), vec![]));
+            assert_eq!(pdp11::encode_lossy(golden.text), (golden.pdp11.to_vec(), vec![]));
+        }
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn exact_round_trip() {
+        for s in ["", "A", "AB", "ABC", "ABCD", "A  ", "  A", "THIS IS A TEST", "      "] {
+            let (words, pad) = pdp10::encode_exact(s).unwrap();
+            assert_eq!((pad + s.len()) % pdp10::CHARS_PER_WORD, 0, "{:?}", s);
+            assert_eq!(pdp10::decode_exact(&words, pad), s);
+            let (words, pad) = pdp11::encode_exact(s).unwrap();
+            assert_eq!((pad + s.len()) % pdp11::CHARS_PER_WORD, 0, "{:?}", s);
+            assert_eq!(pdp11::decode_exact(&words, pad), s);
+        }
+        assert_eq!(pdp11::encode_exact("a"), Err(Error::IllegalChar { char: 'a', pos: 1 }));
+        assert_eq!(pdp11::decode_exact([pdp11::encode_word("ABC").unwrap()], 5), "");
     }
 
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
     #[test]
     fn decode_strings() {
-        assert_eq!(pdp10::decode(&[3119342419, 2970305215, 3046400000]), "THIS IS A TEST    ");
-        assert_eq!(pdp11::decode(&[32329, 30409, 30401, 805, 31200]), "THIS IS A TEST ");
+        assert_eq!(pdp10::decode([3119342419, 2970305215, 3046400000]), "THIS IS A TEST    ");
+        assert_eq!(pdp11::decode([32329, 30409, 30401, 805, 31200]), "THIS IS A TEST ");
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn decode_iterators() {
+        let pdp10_words = vec![3119342419_u32, 2970305215, 3046400000];
+        let pdp11_words = vec![32329_u16, 30409, 30401, 805, 31200];
+        assert_eq!(pdp10::decode(&pdp10_words),             "THIS IS A TEST    ");
+        assert_eq!(pdp10::decode(pdp10_words.iter()),      "THIS IS A TEST    ");
+        assert_eq!(pdp10::decode(pdp10_words.clone()),     "THIS IS A TEST    ");
+        assert_eq!(pdp11::decode(&pdp11_words),             "THIS IS A TEST ");
+        assert_eq!(pdp11::decode(pdp11_words.iter()),      "THIS IS A TEST ");
+        assert_eq!(pdp11::decode(pdp11_words.clone()),     "THIS IS A TEST ");
+        assert_eq!(pdp11::decode(pdp11_words.iter().copied().take(3)), "THIS IS A");
+        assert_eq!(pdp11::decode(core::iter::empty::<u16>()), "");
+    }
+
+    #[cfg(all(feature = "miette", feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn miette_diagnostic() {
+        use miette::Diagnostic;
+        let e = pdp11::encode("AB_CD").unwrap_err();
+        assert_eq!(e.code().unwrap().to_string(), "radix50::illegal_char");
+        assert!(e.labels().is_none());
+        let e = e.with_source("AB_CD");
+        assert_eq!(e.code().unwrap().to_string(), "radix50::illegal_char");
+        assert_eq!(e.to_string(), "Illegal character '_' (95) at position 3");
+        let labels = e.labels().unwrap().collect::<Vec<_>>();
+        assert_eq!((labels[0].offset(), labels[0].len()), (2, 1));
+        assert_eq!(labels[0].label(), Some("not a RADIX-50 character"));
+        let e = pdp10::encode("ABÀ").unwrap_err().with_source("ABÀ");
+        assert_eq!(e.labels().unwrap().map(|l| (l.offset(), l.len())).collect::<Vec<_>>(), [(2, 2)]);
+        // Spans are in bytes, so non-ASCII text before the bad character moves them
+        let errors = pdp10::validate("ÀÀ_").unwrap_err();
+        assert_eq!(errors[2].with_source("ÀÀ_").labels().unwrap().map(|l| (l.offset(), l.len())).collect::<Vec<_>>(), [(4, 1)]);
+        assert!(Error::IllegalChar { char: '_', pos: 0 }.with_source("A_").labels().is_none());
+        assert!(Error::IllegalChar { char: '_', pos: 3 }.with_source("A_").labels().is_none());
+        assert!(Error::InputTooLong { len: 7, max: 6 }.with_source("ABCDEFG").labels().is_none());
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn render_errors() {
+        let input = "A\tB_C";
+        assert_eq!(pdp10::encode(input).unwrap_err().render::<pdp10::Pdp10>(input),
+                   "Illegal character '\t' (9) at position 2 (line 1, column 2)\n  |\n1 | A\tB_C\n  |  ^\nValid characters are \" 0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ.$%\"\n");
+        let errors = pdp11::validate(input).unwrap_err();
+        assert_eq!(errors[1].render::<pdp11::Pdp11>(input).lines().nth(3), Some("  |  \t ^"));
+        assert_eq!(Error::IllegalChar { char: '_', pos: 99 }.render::<pdp11::Pdp11>(input), "Illegal character '_' (95) at position 99\n");
+        assert_eq!(Error::IllegalChar { char: '_', pos: 0 }.render::<pdp11::Pdp11>(input), "Illegal character '_' (95) at position 0\n");
+        assert_eq!(Error::TrailingBytes { len: 2 }.render::<pdp11::Pdp11>(input), "2 trailing bytes after the last whole word\n");
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn fixed_capacity() {
+        for golden in testdata::CORPUS {
+            let mut words = [0; 32];
+            let count = pdp11::encode_into_slice(golden.text, &mut words).unwrap();
+            assert_eq!(&words[..count], golden.pdp11);
+            let mut buf = [0; 64];
+            assert_eq!(pdp11::decode_into_slice(golden.pdp11, &mut buf), Ok(&*pdp11::decode(golden.pdp11)));
+            let mut words = [0; 16];
+            let count = pdp10::encode_into_slice(golden.text, &mut words).unwrap();
+            assert_eq!(&words[..count], golden.pdp10);
+            assert_eq!(pdp10::decode_into_slice(golden.pdp10, &mut buf), Ok(&*pdp10::decode(golden.pdp10)));
+        }
+        let mut words = [0; 2];
+        assert_eq!(pdp11::encode_into_slice("AB_", &mut words), Err(Error::IllegalChar { char: '_', pos: 3 }));
+        assert_eq!(pdp11::encode_into_slice("", &mut []), Ok(0));
+        assert_eq!(pdp11::decode_into_slice([1683, 1683, 1683], &mut [0; 5]), Err(Error::InputTooLong { len: 9, max: 5 }));
+        assert_eq!(pdp11::decode_word_into(u16::MAX, &mut [0; 3]), pdp11::decode_word(u16::MAX));
+    }
+
+    #[cfg(all(feature = "arrayvec", feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn arrayvec() {
+        assert_eq!(pdp11::Pdp11::encode_arrayvec::<5>("THIS IS A TEST").unwrap().as_slice(), [32329, 30409, 30401, 805, 31200]);
+        assert_eq!(pdp11::Pdp11::encode_arrayvec::<4>("THIS IS A TEST"), Err(Error::InputTooLong { len: 14, max: 12 }));
+        assert_eq!(pdp10::Pdp10::decode_arrayvec::<18, _>([3119342419_u32, 2970305215, 3046400000]).unwrap().as_str(), "THIS IS A TEST    ");
+        assert_eq!(pdp10::Pdp10::decode_arrayvec::<8, _>([3119342419_u32, 2970305215, 3046400000]), Err(Error::InputTooLong { len: 18, max: 8 }));
+    }
+
+    #[cfg(all(feature = "heapless", feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn heapless() {
+        assert_eq!(pdp11::Pdp11::encode_heapless::<5>("THIS IS A TEST").unwrap(), [32329, 30409, 30401, 805, 31200]);
+        assert_eq!(pdp11::Pdp11::encode_heapless::<4>("THIS IS A TEST"), Err(Error::InputTooLong { len: 14, max: 12 }));
+        assert_eq!(pdp10::Pdp10::decode_heapless::<18, _>([3119342419_u32, 2970305215, 3046400000]).unwrap(), "THIS IS A TEST    ");
+        assert_eq!(pdp10::Pdp10::decode_heapless::<8, _>([3119342419_u32, 2970305215, 3046400000]), Err(Error::InputTooLong { len: 18, max: 8 }));
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn decode_into_fmt() {
+        struct Fixed { buf: [u8; 8], len: usize }
+        impl core::fmt::Write for Fixed {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                let end = self.len + s.len();
+                self.buf.get_mut(self.len..end).ok_or(core::fmt::Error)?.copy_from_slice(s.as_bytes());
+                self.len = end;
+                Ok(())
+            }
+        }
+        let mut out = Fixed { buf: [0; 8], len: 0 };
+        assert_eq!(pdp11::decode_fmt([1683, 31400], &mut out), Ok(()));
+        assert_eq!(&out.buf[..out.len], b"ABCSY ");
+        assert_eq!(pdp11::decode_fmt([1683, 31400], &mut out), Err(core::fmt::Error));
+        for golden in testdata::CORPUS {
+            let mut s = String::new();
+            pdp10::decode_fmt(golden.pdp10, &mut s).unwrap();
+            assert_eq!(s, pdp10::decode(golden.pdp10));
+        }
     }
 }
@@ -0,0 +1,290 @@
+// PDP-10 relocatable object files
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Reading the symbols out of TOPS-10 relocatable object files (`.REL` files, and libraries of them).
+//!
+//! A `.REL` file is a sequence of LINK-10 blocks. Each starts with a header word holding the block type in the
+//! left half and a word count in the right. In the original block types (below 0o1000) every group of up to 18
+//! data words is preceded by a relocation word which the count doesn't include. A [`block_type::END`] block
+//! finishes each module, and a library is just modules one after another.
+//!
+//! Names are [`symbol10`] words: a [PDP-10 RADIX-50][pdp10] name with 4 bits of flags, which say what kind of
+//! [`Symbol`] it is. Words are `u64`s (only the low 36 bits are used).
+//!
+//! [pdp10]: crate::pdp10::RADIX50_DECODE
+//!
+//! # Examples
+//! ```
+//! # use radix50::{symbol10, rel10::{self, block_type}};
+//! # let name = symbol10::encode_symbol("MAIN", 0).unwrap();
+//! # let start = symbol10::encode_symbol("START", 1).unwrap();
+//! # let words = [0o6000001, 0, name, 0o2000002, 0o5, start, 0o140, 0o5000001, 0, 0o200];
+//! for module in rel10::modules(&words)? {
+//!     println!("{}:", module.name.as_deref().unwrap_or("?"));
+//!     for sym in module.symbols {
+//!         println!("  {:6} {:?} {:012o}", sym.name, sym.kind(), sym.value);
+//!     }
+//! }
+//! # assert_eq!(rel10::modules(&words)?[0].symbols[0].name, "START");
+//! # Ok::<(), rel10::RelError>(())
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use crate::symbol10::{self, SymbolFlags};
+
+/// Block type numbers.
+pub mod block_type {
+    /// Code and data to load.
+    pub const CODE: u32 = 0o1;
+    /// Symbol definitions and requests (see [`symbols`](super::symbols)).
+    pub const SYMBOLS: u32 = 0o2;
+    /// The module has a high segment.
+    pub const HISEG: u32 = 0o3;
+    /// The names of the module's entry points, for library searches.
+    pub const ENTRY: u32 = 0o4;
+    /// The end of a module.
+    pub const END: u32 = 0o5;
+    /// The module's name.
+    pub const NAME: u32 = 0o6;
+    /// The module's start address.
+    pub const START: u32 = 0o7;
+    /// Internal requests (fixups).
+    pub const INTERNAL_REQUEST: u32 = 0o10;
+    /// Polish fixups.
+    pub const POLISH: u32 = 0o11;
+    /// A library's index of entry points.
+    pub const INDEX: u32 = 0o14;
+    /// Other files to load.
+    pub const REQUEST_LOAD: u32 = 0o16;
+    /// Other libraries to search.
+    pub const REQUEST_LIBRARY: u32 = 0o17;
+    /// Common blocks.
+    pub const COMMON: u32 = 0o20;
+}
+
+/// How many data words follow each relocation word.
+pub const RELOCATION_GROUP: usize = 18;
+
+/// A block, with any relocation words removed.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Block {
+    /// The block type (see [`block_type`]).
+    pub typ: u32,
+    /// The block's data words.
+    pub data: Vec<u64>,
+}
+
+/// Errors from reading `.REL` files.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+#[non_exhaustive]
+pub enum RelError {
+    /// The block starting at word `offset` runs past the end of the file.
+    Truncated { offset: usize },
+}
+
+impl core::error::Error for RelError {
+}
+
+impl fmt::Display for RelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelError::Truncated { offset } => write!(f, "Truncated block at word {}", offset),
+        }
+    }
+}
+
+/// Iterate over the blocks in a `.REL` file. Iteration stops after the first error.
+///
+/// # Examples
+/// ```
+/// # use radix50::rel10::{self, Block, RelError};
+/// let words = [0o5000002, 0, 0o200, 0o400, 0o1000003, 0, 1];
+/// let mut blocks = rel10::blocks(&words);
+/// assert_eq!(blocks.next(), Some(Ok(Block { typ: 0o5, data: vec![0o200, 0o400] })));
+/// assert_eq!(blocks.next(), Some(Err(RelError::Truncated { offset: 4 })));
+/// assert_eq!(blocks.next(), None);
+/// ```
+pub fn blocks(words: &[u64]) -> impl Iterator<Item = Result<Block, RelError>> + '_ {
+    let mut at = 0;
+    let mut failed = false;
+    core::iter::from_fn(move || {
+        if failed || at >= words.len() { return None }
+        let offset = at;
+        let (typ, count) = ((words[at] >> 18) as u32 & 0o777777, (words[at] & 0o777777) as usize);
+        let len = if typ < 0o1000 { count + count.div_ceil(RELOCATION_GROUP) } else { count };
+        let Some(body) = words.get(at + 1..at + 1 + len) else {
+            failed = true;
+            return Some(Err(RelError::Truncated { offset }));
+        };
+        at += 1 + len;
+        let data = if typ < 0o1000 { body.chunks(RELOCATION_GROUP + 1).flat_map(|group| &group[1..]).copied().collect() }
+                   else            { body.to_vec() };
+        Some(Ok(Block { typ, data }))
+    })
+}
+
+/// What a [`Symbol`] is, from its flags.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+#[non_exhaustive]
+pub enum SymbolKind {
+    /// The name of a program (flags 0). These only show up in DDT's symbol tables, where each program's name
+    /// follows its local symbols.
+    ProgramName,
+    /// A global symbol defined in this module (flags 0o1, or 0o11 if it's suppressed to DDT).
+    GlobalDefinition,
+    /// A local symbol defined in this module (flags 0o2, or 0o12 if it's suppressed to DDT).
+    LocalDefinition,
+    /// The name of a block, in a block structured language (flags 0o3).
+    BlockName,
+    /// A request for a global symbol defined elsewhere (flags 0o14).
+    GlobalRequest,
+    /// Some other flags.
+    Unknown(u8),
+}
+
+/// A symbol from a [`block_type::SYMBOLS`] block (or from a symbol table with the same layout).
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+pub struct Symbol {
+    /// The name, with its padding trimmed.
+    pub name: String,
+    /// The 4 flag bits from the name's [`symbol10`] word.
+    pub flags: u8,
+    /// The value. For a [`SymbolKind::GlobalRequest`] it's the address of the chain of places to fix up.
+    pub value: u64,
+}
+
+impl Symbol {
+    /// What kind of symbol this is.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::rel10::{Symbol, SymbolKind};
+    /// let sym = Symbol { name: "START".into(), flags: 0o1, value: 0o140 };
+    /// assert_eq!(sym.kind(), SymbolKind::GlobalDefinition);
+    /// assert_eq!(Symbol { flags: 0o14, ..sym }.kind(), SymbolKind::GlobalRequest);
+    /// ```
+    pub fn kind(&self) -> SymbolKind {
+        match self.flags {
+            0o0        => SymbolKind::ProgramName,
+            0o1 | 0o11 => SymbolKind::GlobalDefinition,
+            0o2 | 0o12 => SymbolKind::LocalDefinition,
+            0o3        => SymbolKind::BlockName,
+            0o14       => SymbolKind::GlobalRequest,
+            f          => SymbolKind::Unknown(f),
+        }
+    }
+
+    /// Whether a definition is suppressed to DDT (DDT won't use it when printing addresses).
+    pub fn is_suppressed(&self) -> bool { matches!(self.flags, 0o11 | 0o12) }
+
+    /// The flags, with their [standard meanings](SymbolFlags).
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{rel10::Symbol, symbol10::SymbolFlags};
+    /// let sym = Symbol { name: "LOOP".into(), flags: 0o12, value: 0o142 };
+    /// assert_eq!(sym.symbol_flags(), SymbolFlags::LOCAL | SymbolFlags::SUPPRESSED);
+    /// ```
+    pub fn symbol_flags(&self) -> SymbolFlags { SymbolFlags::from_bits_truncate(self.flags) }
+}
+
+/// Iterate over the symbols in a [`block_type::SYMBOLS`] block's data: pairs of a [`symbol10`] name word and a
+/// value word. Any odd word at the end is ignored.
+pub fn symbols(data: &[u64]) -> impl Iterator<Item = Symbol> + '_ {
+    data.chunks_exact(2).map(|pair| {
+        let (flags, name) = symbol10::decode_symbol(pair[0]);
+        Symbol { name, flags, value: pair[1] & 0o777777777777 }
+    })
+}
+
+/// What's in one module of a `.REL` file.
+#[derive(Debug,Clone,PartialEq,Eq,Default)]
+pub struct Module {
+    /// The module name, from its [`block_type::NAME`] block.
+    pub name: Option<String>,
+    /// The entry point names, from its [`block_type::ENTRY`] blocks.
+    pub entries: Vec<String>,
+    /// The symbols from its [`block_type::SYMBOLS`] blocks, in order.
+    pub symbols: Vec<Symbol>,
+}
+
+/// Inventory the modules in a `.REL` file or library. A module ends at a [`block_type::END`] block; anything after
+/// the last one (other than padding) is returned as a final module.
+///
+/// # Examples
+/// ```
+/// # use radix50::{symbol10, rel10::{self, SymbolKind}};
+/// let words = [0o4000001, 0, symbol10::encode_symbol("SIN", 0).unwrap(),
+///              0o2000002, 0, symbol10::encode_symbol("SIN", 0o1).unwrap(), 0o400,
+///              0o5000001, 0, 0o410,
+///              0o4000002, 0, symbol10::encode_symbol("COS", 0).unwrap(), symbol10::encode_symbol("COSD", 0).unwrap(),
+///              0o5000001, 0, 0o420];
+/// let modules = rel10::modules(&words).unwrap();
+/// assert_eq!(modules.len(), 2);
+/// assert_eq!(modules[0].symbols[0].kind(), SymbolKind::GlobalDefinition);
+/// assert_eq!(modules[1].entries, ["COS", "COSD"]);
+/// ```
+pub fn modules(words: &[u64]) -> Result<Vec<Module>, RelError> {
+    let mut modules = Vec::new();
+    let mut module = Module::default();
+    for block in blocks(words) {
+        let block = block?;
+        match block.typ {
+            block_type::NAME    => module.name = block.data.first().map(|&w| symbol10::decode_symbol(w).1),
+            block_type::ENTRY   => module.entries.extend(block.data.iter().map(|&w| symbol10::decode_symbol(w).1)),
+            block_type::SYMBOLS => module.symbols.extend(symbols(&block.data)),
+            block_type::END     => modules.push(core::mem::take(&mut module)),
+            _                   => {},
+        }
+    }
+    if module != Module::default() { modules.push(module) }
+    Ok(modules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn relocation_words() {
+        // 40 data words need 3 relocation words.
+        let mut words = vec![0o1000000 | 40];
+        for n in 0..40u64 {
+            if n % 18 == 0 { words.push(0o252525252525) }
+            words.push(n);
+        }
+        words.extend([0, 0]); // padding: type 0 blocks with no data
+        words.extend([0o1070000002, 7, 8]); // a new style block: no relocation words
+        let blocks: Vec<Block> = blocks(&words).map(Result::unwrap).collect();
+        assert_eq!(blocks[0], Block { typ: 1, data: (0..40).collect() });
+        assert_eq!(blocks[1], Block { typ: 0, data: vec![] });
+        assert_eq!(blocks[3], Block { typ: 0o1070, data: vec![7, 8] });
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(modules(&words), Ok(vec![]));
+    }
+
+    #[test]
+    fn symbol_kinds() {
+        let mut data = Vec::new();
+        for (name, flags) in [("A", 0o1), ("B", 0o11), ("C.L", 0o2), ("%D", 0o12), ("BLK", 0o3), ("EXT", 0o14), ("Q", 0o7)] {
+            data.extend([symbol10::encode_symbol(name, flags).unwrap(), 1 << 35 | 0o123]);
+        }
+        let syms: Vec<Symbol> = symbols(&data).collect();
+        assert_eq!(syms.iter().map(Symbol::kind).collect::<Vec<_>>(),
+                   [SymbolKind::GlobalDefinition, SymbolKind::GlobalDefinition, SymbolKind::LocalDefinition,
+                    SymbolKind::LocalDefinition, SymbolKind::BlockName, SymbolKind::GlobalRequest, SymbolKind::Unknown(0o7)]);
+        assert_eq!(syms.iter().map(Symbol::is_suppressed).collect::<Vec<_>>(), [false, true, false, true, false, false, false]);
+        assert_eq!((syms[2].name.as_str(), syms[2].value), ("C.L", 1 << 35 | 0o123));
+
+        let mut words = vec![0o6000001, 0, symbol10::encode_symbol("MOD", 0).unwrap(), 0o2000000 | data.len() as u64, 0];
+        words.extend(&data);
+        let modules = modules(&words).unwrap();
+        assert_eq!(modules[0].name.as_deref(), Some("MOD"));
+        assert_eq!(modules[0].symbols, syms);
+    }
+}
@@ -0,0 +1,92 @@
+// Streaming results out of the scanner and format walkers
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Streaming results to a consumer as they're found, instead of collecting them all first.
+//!
+//! A [`ReportSink`] gets each entry (a string the [scanner](crate::scan) found, a [GSD
+//! entry](crate::obj11::GsdEntry), a tape record…) as soon as it turns up, and the error if the walk fails. Vecs
+//! are sinks that collect everything, and anything else (a database writer, a GUI list, a progress counter) can
+//! implement the trait itself. [`drain`] feeds any of the walkers that are iterators (like
+//! [`tap::items`](crate::tap::items)) to a sink, and the ones that aren't have `_into` versions that take a sink.
+//!
+//! # Examples
+//! ```
+//! # use radix50::{report::{self, ReportSink}, tap::{self, Item, TapError}};
+//! struct Progress { records: usize, bytes: usize }
+//! impl<'a> ReportSink<Item<'a>, TapError> for Progress {
+//!     fn on_entry(&mut self, item: Item<'a>) {
+//!         if let Item::Record(data) | Item::BadRecord(data) = item {
+//!             self.records += 1;
+//!             self.bytes += data.len();
+//!             println!("{} records, {} bytes", self.records, self.bytes);
+//!         }
+//!     }
+//!     fn on_error(&mut self, error: TapError) { println!("{}", error) }
+//! }
+//! # let image = [4, 0, 0, 0, 1, 2, 3, 4, 4, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 5, 6, 2, 0, 0, 0];
+//! let mut progress = Progress { records: 0, bytes: 0 };
+//! report::drain(tap::items(&image), &mut progress);
+//! assert_eq!((progress.records, progress.bytes), (2, 6));
+//! ```
+
+use alloc::vec::Vec;
+use core::convert::Infallible;
+
+/// Something that takes the results of a scan or a file walk as they're found.
+///
+/// `T` is what's found and `E` is the walk's error type. Walks that can't fail use [`Infallible`].
+pub trait ReportSink<T, E = Infallible> {
+    /// Called with each entry, in the order the walk finds them.
+    fn on_entry(&mut self, entry: T);
+    /// Called if the walk fails. Walks stop at their first error, so nothing comes after this.
+    fn on_error(&mut self, error: E);
+}
+
+/// Collects the entries of a walk that can't fail.
+impl<T> ReportSink<T> for Vec<T> {
+    fn on_entry(&mut self, entry: T) { self.push(entry) }
+    fn on_error(&mut self, error: Infallible) { match error {} }
+}
+
+/// Collects the entries and the error (if there is one), in order. `collect()`ing the Vec into a `Result<Vec<T>,
+/// E>` gets the same thing the non-streaming functions return.
+impl<T, E> ReportSink<T, E> for Vec<Result<T, E>> {
+    fn on_entry(&mut self, entry: T) { self.push(Ok(entry)) }
+    fn on_error(&mut self, error: E) { self.push(Err(error)) }
+}
+
+impl<T, E, S: ReportSink<T, E> + ?Sized> ReportSink<T, E> for &mut S {
+    fn on_entry(&mut self, entry: T) { (**self).on_entry(entry) }
+    fn on_error(&mut self, error: E) { (**self).on_error(error) }
+}
+
+/// Feed the results of an iterator (like [`obj11::records`](crate::obj11::records) or
+/// [`tap::items`](crate::tap::items)) to `sink`. Stops after the first error.
+pub fn drain<T, E, I, S>(items: I, sink: &mut S) where I: IntoIterator<Item = Result<T, E>>, S: ReportSink<T, E> + ?Sized {
+    for item in items {
+        match item {
+            Ok(entry)  => sink.on_entry(entry),
+            Err(error) => return sink.on_error(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn drain_stops_at_error() {
+        let mut all: Vec<Result<u8, &str>> = Vec::new();
+        drain([Ok(1), Ok(2), Err("bad"), Ok(3)], &mut all);
+        assert_eq!(all, [Ok(1), Ok(2), Err("bad")]);
+        assert_eq!(all.into_iter().collect::<Result<Vec<_>, _>>(), Err("bad"));
+
+        let mut entries: Vec<char> = Vec::new();
+        drain(vec![Ok::<_, Infallible>('A'), Ok('B')], &mut &mut entries);
+        assert_eq!(entries, ['A', 'B']);
+    }
+}
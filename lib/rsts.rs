@@ -0,0 +1,119 @@
+// RSTS/E file names and PPNs
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! RSTS/E file names and project-programmer numbers (PPNs).
+//!
+//! RSTS/E directory entries store file names the same 6.3 way as [RT-11](crate::rt11): 2 [PDP-11
+//! RADIX-50][pdp11] words for the name and 1 for the extension. Accounts are identified by a [`Ppn`], a
+//! `[project,programmer]` pair that's packed into one word and written in decimal.
+//!
+//! [pdp11]: crate::pdp11::RADIX50_DECODE
+//!
+//! # Examples
+//! ```
+//! # use radix50::rsts::{self, Ppn};
+//! let ppn: Ppn = "[1,2]".parse().unwrap();
+//! assert_eq!(ppn, Ppn::LIBRARY);
+//! assert_eq!(u16::from(ppn), 0x0102);
+//! assert_eq!(format!("{ppn}{}", rsts::decode_filename(&[25976, 0, 30462])), "[1,2]PIP.SAV");
+//! ```
+
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{Error, dos11::parse_pair, parse::ParseError, rt11};
+
+/// Encode a RSTS/E file name (`NAME.EXT`) into 3 words: 2 for the name and 1 for the extension.
+///
+/// The rules are the same as for [`rt11::encode_filename`]: the extension is optional, only letters and digits are
+/// allowed, and lower case letters are upper cased.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,rsts::encode_filename};
+/// assert_eq!(encode_filename("PIP.SAV"), Ok([25976, 0, 30462]));
+/// assert_eq!(encode_filename("$PIP.SAV"), Err(Error::IllegalChar { char: '$', pos: 1 }));
+/// ```
+pub fn encode_filename(name: &str) -> Result<[u16; 3], Error> { rt11::encode_filename(name) }
+
+/// Decode 3 words into a RSTS/E file name, trimming the padding from each part (see [`rt11::decode_filename`]).
+///
+/// # Examples
+/// ```
+/// # use radix50::rsts::decode_filename;
+/// assert_eq!(decode_filename(&[25976, 0, 30462]), "PIP.SAV");
+/// ```
+pub fn decode_filename(words: &[u16; 3]) -> String { rt11::decode_filename(words) }
+
+/// A RSTS/E project-programmer number: an account's project and programmer numbers, stored as one word (project in
+/// the high byte).
+///
+/// It displays as `[project,programmer]` in decimal, and parses from the same (the brackets are optional). `$` also
+/// parses, as the system library account ([`Ppn::LIBRARY`]). Parsing returns a [`ParseError::InvalidDigit`] for
+/// anything out of place, a [`ParseError::Empty`] if either number is missing, and a [`ParseError::Overflow`] if
+/// either is bigger than 255.
+///
+/// # Examples
+/// ```
+/// # use radix50::{rsts::Ppn, parse::ParseError};
+/// let ppn = Ppn { project: 100, programmer: 27 };
+/// assert_eq!(ppn.to_string(), "[100,27]");
+/// assert_eq!("100,27".parse(), Ok(ppn));
+/// assert_eq!(Ppn::from(0x641b), ppn);
+/// assert_eq!("$".parse(), Ok(Ppn::LIBRARY));
+///
+/// assert_eq!("[1,A]".parse::<Ppn>(), Err(ParseError::InvalidDigit { char: 'A', pos: 4 }));
+/// assert_eq!("[256,1]".parse::<Ppn>(), Err(ParseError::Overflow));
+/// ```
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,Default,PartialOrd,Ord)]
+pub struct Ppn {
+    /// The project number (the high byte).
+    pub project: u8,
+    /// The programmer number within the project (the low byte).
+    pub programmer: u8,
+}
+
+impl Ppn {
+    /// The system library account, `[1,2]` (`$` on the command line).
+    pub const LIBRARY: Ppn = Ppn { project: 1, programmer: 2 };
+    /// The system manager's account, `[1,1]`. It owns the master file directory.
+    pub const MFD: Ppn = Ppn { project: 1, programmer: 1 };
+}
+
+impl From<u16> for Ppn { fn from(w: u16) -> Self { Ppn { project: (w >> 8) as u8, programmer: w as u8 } } }
+impl From<Ppn> for u16 { fn from(ppn: Ppn) -> Self { (ppn.project as u16) << 8 | ppn.programmer as u16 } }
+
+impl fmt::Display for Ppn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "[{},{}]", self.project, self.programmer) }
+}
+
+impl FromStr for Ppn {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "$" { return Ok(Ppn::LIBRARY) }
+        let [project, programmer] = parse_pair(s, 10)?;
+        Ok(Ppn { project, programmer })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn ppn() {
+        for w in [0, 0x0101, 0xffff, 0x1000] {
+            let ppn = Ppn::from(w);
+            assert_eq!(u16::from(ppn), w);
+            assert_eq!(ppn.to_string().parse(), Ok(ppn));
+        }
+        assert_eq!("[255,255]".parse(), Ok(Ppn { project: 255, programmer: 255 }));
+        assert_eq!("[1,1]".parse(), Ok(Ppn::MFD));
+        assert_eq!("[1,".parse::<Ppn>(), Err(ParseError::Empty));
+        assert_eq!("$$".parse::<Ppn>(), Err(ParseError::InvalidDigit { char: '$', pos: 1 }));
+    }
+}
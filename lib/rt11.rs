@@ -0,0 +1,482 @@
+// RT-11 file names
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! RT-11 file names: a 6 character name and a 3 character extension, stored as 3 [PDP-11 RADIX-50][pdp11] words.
+//!
+//! This is how the RT-11 directory (and plenty of other PDP-11 software) stores file names: 2 words for the name
+//! and 1 for the extension, each space padded. Only letters and digits are allowed in either part (lower case
+//! letters are upper cased, like the RT-11 command line does), and the two parts are separated by a single `.`.
+//!
+//! [pdp11]: crate::pdp11::RADIX50_DECODE
+//!
+//! # Examples
+//! ```
+//! # use radix50::rt11;
+//! let words = rt11::encode_filename("RT11SJ.SYS").unwrap();
+//! assert_eq!(words, [29631, 50370, 31419]);
+//! assert_eq!(rt11::decode_filename(&words), "RT11SJ.SYS");
+//! ```
+//!
+//! With the `std` feature, [`Volume`] lists the files on an RT-11 disk image (and renames and protects them).
+
+use alloc::{format, string::String};
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use crate::{Error, pdp11};
+
+/// How many characters fit in the name part of a file name.
+pub const NAME_CHARS: usize = 6;
+
+/// How many characters fit in the extension part of a file name.
+pub const EXT_CHARS: usize = 3;
+
+/// Encode an RT-11 file name (`NAME.EXT`) into 3 words: 2 for the name and 1 for the extension.
+///
+/// The extension (and its `.`) are optional. Either part may be shorter than its field, in which case it's space
+/// padded. Lower case letters are upper cased.
+///
+/// It returns an [`Error::IllegalChar`] (with `pos` counting from 1 in `name`) for anything that isn't a letter or
+/// digit, including a second `.`, and an [`Error::InputTooLong`] if the name is longer than [`NAME_CHARS`] or the
+/// extension is longer than [`EXT_CHARS`].
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,rt11::encode_filename};
+/// assert_eq!(encode_filename("SWAP.SYS"), Ok([31321, 25600, 31419]));
+/// assert_eq!(encode_filename("pip.sav"), Ok([25976, 0, 30462]));
+/// assert_eq!(encode_filename("A"), encode_filename("A."));
+/// assert_eq!(encode_filename("A.B.C"), Err(Error::IllegalChar { char: '.', pos: 4 }));
+/// assert_eq!(encode_filename("A$B.MAC"), Err(Error::IllegalChar { char: '$', pos: 2 }));
+/// assert_eq!(encode_filename("TOOLONG.MAC"), Err(Error::InputTooLong { len: 7, max: 6 }));
+/// assert_eq!(encode_filename("FILE.TEXT"), Err(Error::InputTooLong { len: 4, max: 3 }));
+/// ```
+pub fn encode_filename(name: &str) -> Result<[u16; 3], Error> {
+    let (base, ext) = split_filename(name, NAME_CHARS, EXT_CHARS)?;
+    let [n1, n2] = pdp11::encode_symbol(&base)?;
+    Ok([n1, n2, pdp11::encode_word(&ext)?])
+}
+
+// Check and split a `NAME.EXT` file name, upper casing it. Other PDP-11 file systems use the same rules with
+// different field sizes.
+pub(crate) fn split_filename(name: &str, name_chars: usize, ext_chars: usize) -> Result<(String, String), Error> {
+    let mut dot = false;
+    for (i, c) in name.chars().enumerate() {
+        match c {
+            '.' if !dot                    => dot = true,
+            _ if c.is_ascii_alphanumeric() => {},
+            _                              => return Err(Error::IllegalChar { char: c, pos: i + 1 }),
+        }
+    }
+    let name = name.to_ascii_uppercase();
+    let (base, ext) = name.split_once('.').unwrap_or((&name, ""));
+    if base.len() > name_chars { return Err(Error::InputTooLong { len: base.len(), max: name_chars }) }
+    if ext.len() > ext_chars { return Err(Error::InputTooLong { len: ext.len(), max: ext_chars }) }
+    Ok((base.into(), ext.into()))
+}
+
+/// Decode 3 words into an RT-11 file name, trimming the padding from each part.
+///
+/// The `.` is left off if the extension is blank, so the result always encodes back to the same words (as long
+/// as they were a valid file name to start with).
+///
+/// # Examples
+/// ```
+/// # use radix50::rt11::decode_filename;
+/// assert_eq!(decode_filename(&[31321, 25600, 31419]), "SWAP.SYS");
+/// assert_eq!(decode_filename(&[1600, 0, 0]), "A");
+/// assert_eq!(decode_filename(&[0, 0, 31419]), ".SYS");
+/// ```
+pub fn decode_filename(words: &[u16; 3]) -> String {
+    let name = pdp11::decode_symbol(&[words[0], words[1]]);
+    let ext = pdp11::decode_word(words[2]);
+    match ext.trim_end_matches(' ') {
+        ""  => name,
+        ext => format!("{name}.{ext}"),
+    }
+}
+
+/// The size of a disk block, in bytes.
+pub const BLOCK_SIZE: usize = 512;
+
+/// Directory entry status bits.
+pub mod status {
+    /// A tentative file (still open for writing).
+    pub const TENTATIVE: u16 = 0o400;
+    /// An empty area (free space).
+    pub const EMPTY: u16 = 0o1000;
+    /// A permanent file.
+    pub const PERMANENT: u16 = 0o2000;
+    /// The end of the directory segment.
+    pub const END_OF_SEGMENT: u16 = 0o4000;
+    /// The file is read only.
+    pub const READ_ONLY: u16 = 0o40000;
+    /// The file is protected from deletion.
+    pub const PROTECTED: u16 = 0o100000;
+}
+
+/// An entry in an RT-11 directory segment: a file, a tentative file, or an empty area.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct DirEntry {
+    /// The [`status`] bits.
+    pub status: u16,
+    /// The file name: 2 words of name and 1 of extension (see [`decode_filename`]).
+    pub name: [u16; 3],
+    /// The length, in blocks.
+    pub length: u16,
+    /// The first block. This isn't stored in the entry; it comes from the segment's starting block plus the
+    /// lengths of the entries before it.
+    pub start: u32,
+    /// The raw creation date (see [`DirEntry::date`]).
+    pub date_word: u16,
+}
+
+impl DirEntry {
+    /// The file name as `NAME.EXT` (see [`decode_filename`]).
+    pub fn filename(&self) -> String { decode_filename(&self.name) }
+
+    /// Whether this is a permanent file (as opposed to a tentative file or free space).
+    pub fn is_permanent(&self) -> bool { self.status & status::PERMANENT != 0 }
+
+    /// Whether the file is protected from deletion.
+    pub fn is_protected(&self) -> bool { self.status & status::PROTECTED != 0 }
+
+    /// The creation date as `(year, month, day)`, or `None` if there isn't one. The year includes the "age" bits
+    /// (the top 2) that RT-11 V5.5 added to get past 2003.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::rt11::DirEntry;
+    /// let entry = DirEntry { status: 0o2000, name: [1600, 0, 0], length: 1, start: 20, date_word: 0o26241 };
+    /// assert_eq!(entry.date(), Some((1973, 11, 5)));
+    /// assert_eq!(DirEntry { date_word: 0, ..entry }.date(), None);
+    /// ```
+    pub fn date(&self) -> Option<(u16, u8, u8)> {
+        let w = self.date_word;
+        let (month, day) = ((w >> 10 & 0o17) as u8, (w >> 5 & 0o37) as u8);
+        (month != 0 && day != 0).then_some((1972 + (w & 0o37) + (w >> 14) * 32, month, day))
+    }
+}
+
+/// A directory segment: its header and entries.
+#[cfg(feature = "std")]
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Segment {
+    /// How many segments the directory has room for.
+    pub total: u16,
+    /// The next segment in the chain (1-based), or 0 if this is the last.
+    pub next: u16,
+    /// The highest segment in use (only kept up to date in the first segment).
+    pub highest: u16,
+    /// Extra bytes at the end of each entry.
+    pub extra_bytes: u16,
+    /// The block where the segment's first file starts.
+    pub start: u16,
+    /// The entries, not including the end of segment marker.
+    pub entries: Vec<DirEntry>,
+}
+
+#[cfg(feature = "std")]
+impl Segment {
+    /// The size of a directory segment, in bytes.
+    pub const SIZE: usize = 2 * BLOCK_SIZE;
+
+    /// Parse a directory segment. It returns an [`io::ErrorKind::InvalidData`] error if an entry runs off the end
+    /// of the segment before the end of segment marker.
+    pub fn parse(bytes: &[u8; Segment::SIZE]) -> io::Result<Segment> {
+        let words: Vec<u16> = bytes.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])).collect();
+        let [total, next, highest, extra_bytes, start] = [words[0], words[1], words[2], words[3], words[4]];
+        let entry_words = 7 + extra_bytes as usize / 2;
+        let mut entries = Vec::new();
+        let mut block = start as u32;
+        let mut at = 5;
+        loop {
+            let Some(w) = words.get(at..at + entry_words) else {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "RT-11 directory segment has no end marker"))
+            };
+            if w[0] & status::END_OF_SEGMENT != 0 { break }
+            entries.push(DirEntry { status: w[0], name: [w[1], w[2], w[3]], length: w[4], start: block, date_word: w[6] });
+            block += w[4] as u32;
+            at += entry_words;
+        }
+        Ok(Segment { total, next, highest, extra_bytes, start, entries })
+    }
+
+    /// Write a directory segment: the header, the entries, and an end of segment marker, with zeros after it. The
+    /// extra bytes at the end of each entry are written as zeros, and so is each entry's channel and job word.
+    ///
+    /// It returns an [`io::ErrorKind::InvalidInput`] error if the entries (and the marker) don't fit.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::rt11::{self, DirEntry, Segment, status};
+    /// let file = DirEntry { status: status::PERMANENT, name: rt11::encode_filename("PIP.SAV")?, length: 5, start: 14, date_word: 0 };
+    /// let segment = Segment { total: 1, next: 0, highest: 1, extra_bytes: 0, start: 14, entries: vec![file] };
+    /// let bytes = segment.write()?;
+    /// assert_eq!(bytes[10..12], status::PERMANENT.to_le_bytes());
+    /// assert_eq!(Segment::parse(&bytes)?, segment);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn write(&self) -> io::Result<[u8; Segment::SIZE]> {
+        let entry_words = 7 + self.extra_bytes as usize / 2;
+        // The end of segment marker needs room for a whole entry
+        if 5 + (self.entries.len() + 1) * entry_words > Segment::SIZE / 2 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Too many entries for an RT-11 directory segment"))
+        }
+        let mut words = vec![self.total, self.next, self.highest, self.extra_bytes, self.start];
+        for e in &self.entries {
+            words.extend([e.status, e.name[0], e.name[1], e.name[2], e.length, 0, e.date_word]);
+            words.resize(words.len() + entry_words - 7, 0);
+        }
+        words.push(status::END_OF_SEGMENT);
+        let mut bytes = [0; Segment::SIZE];
+        for (w, b) in words.iter().zip(bytes.chunks_exact_mut(2)) { b.copy_from_slice(&w.to_le_bytes()) }
+        Ok(bytes)
+    }
+}
+
+/// An RT-11 disk image (a `.DSK` file, say).
+///
+/// [`open`](Volume::open) reads the whole directory, following the chain of segments.
+///
+/// # Examples
+/// ```no_run
+/// # use radix50::rt11::Volume;
+/// let volume = Volume::open(std::fs::File::open("rt11.dsk")?)?;
+/// for file in volume.files() {
+///     println!("{:10} {:5} {:6}", file.filename(), file.length, file.start);
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct Volume<R> {
+    reader: R,
+    entries: Vec<DirEntry>,
+    // Where each entry is in the image, in bytes
+    offsets: Vec<u64>,
+}
+
+#[cfg(feature = "std")]
+impl<R: Read + Seek> Volume<R> {
+    // Where the home block keeps the first directory segment's block number, and the default if it's 0.
+    const HOME_BLOCK: u64 = 1;
+    const DIRECTORY_OFFSET: usize = 0o724;
+    const DEFAULT_DIRECTORY: u16 = 6;
+
+    /// Read the directory from an RT-11 volume.
+    ///
+    /// It returns an [`io::ErrorKind::InvalidData`] error if the segment chain is broken (it loops, or points past
+    /// the number of segments the directory has).
+    pub fn open(mut reader: R) -> io::Result<Self> {
+        let mut home = [0; BLOCK_SIZE];
+        reader.seek(SeekFrom::Start(Self::HOME_BLOCK * BLOCK_SIZE as u64))?;
+        reader.read_exact(&mut home)?;
+        let first = match u16::from_le_bytes([home[Self::DIRECTORY_OFFSET], home[Self::DIRECTORY_OFFSET + 1]]) {
+            0     => Self::DEFAULT_DIRECTORY,
+            block => block,
+        };
+        let (mut entries, mut offsets) = (Vec::new(), Vec::new());
+        let (mut n, mut seen, mut total) = (1, 0, 1);
+        while n != 0 {
+            if n > total || seen == total {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, format!("RT-11 directory segment chain is broken at segment {n}")))
+            }
+            let mut bytes = [0; Segment::SIZE];
+            let at = (first as u64 + (n as u64 - 1) * 2) * BLOCK_SIZE as u64;
+            reader.seek(SeekFrom::Start(at))?;
+            reader.read_exact(&mut bytes)?;
+            let segment = Segment::parse(&bytes)?;
+            if seen == 0 { total = segment.total }
+            seen += 1;
+            n = segment.next;
+            let entry_bytes = 14 + segment.extra_bytes as u64;
+            offsets.extend((0..segment.entries.len() as u64).map(|i| at + 10 + i * entry_bytes));
+            entries.extend(segment.entries);
+        }
+        Ok(Volume { reader, entries, offsets })
+    }
+
+    /// Every directory entry, including tentative files and empty areas, in directory order.
+    pub fn entries(&self) -> &[DirEntry] { &self.entries }
+
+    /// The permanent files, in directory order.
+    pub fn files(&self) -> impl Iterator<Item = &DirEntry> { self.entries.iter().filter(|e| e.is_permanent()) }
+
+    /// Read a file's contents.
+    pub fn read(&mut self, entry: &DirEntry) -> io::Result<Vec<u8>> {
+        let mut data = vec![0; entry.length as usize * BLOCK_SIZE];
+        self.reader.seek(SeekFrom::Start(entry.start as u64 * BLOCK_SIZE as u64))?;
+        self.reader.read_exact(&mut data)?;
+        Ok(data)
+    }
+
+    /// Give back the reader.
+    pub fn into_inner(self) -> R { self.reader }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read + Write + Seek> Volume<R> {
+    /// Rename a permanent file.
+    ///
+    /// It returns an [`io::ErrorKind::NotFound`] error if there's no file called `old`, an
+    /// [`io::ErrorKind::AlreadyExists`] error if there's already a file called `new`, and an
+    /// [`io::ErrorKind::InvalidInput`] error if either isn't a valid file name (see [`encode_filename`]).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use radix50::rt11::Volume;
+    /// let mut volume = Volume::open(std::fs::File::options().read(true).write(true).open("rt11.dsk")?)?;
+    /// volume.rename("RT11SJ.SYS", "RT11OL.SYS")?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn rename(&mut self, old: &str, new: &str) -> io::Result<()> {
+        let index = self.find(old)?;
+        let name = encode_filename(new).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        if self.files().any(|f| f.name == name) {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{new} already exists")))
+        }
+        self.entries[index].name = name;
+        self.write_entry(index)
+    }
+
+    /// Protect a permanent file from deletion, or stop protecting it.
+    ///
+    /// It returns the same errors as [`rename`](Volume::rename) if there's no file called `name`.
+    pub fn set_protected(&mut self, name: &str, protected: bool) -> io::Result<()> {
+        let index = self.find(name)?;
+        let entry = &mut self.entries[index];
+        entry.status = if protected { entry.status | status::PROTECTED } else { entry.status & !status::PROTECTED };
+        self.write_entry(index)
+    }
+
+    fn find(&self, name: &str) -> io::Result<usize> {
+        let words = encode_filename(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        self.entries.iter().position(|e| e.is_permanent() && e.name == words)
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{name} not found")))
+    }
+
+    // Write the entry's status and name words back to its spot in the directory.
+    fn write_entry(&mut self, index: usize) -> io::Result<()> {
+        let entry = &self.entries[index];
+        let words = [entry.status, entry.name[0], entry.name[1], entry.name[2]];
+        self.reader.seek(SeekFrom::Start(self.offsets[index]))?;
+        self.reader.write_all(&words.map(u16::to_le_bytes).concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for name in ["A", "ABCDEF.XYZ", "123.4", ".SYS", "", "DIR.", "x1.y"] {
+            let words = encode_filename(name).unwrap();
+            let expected = name.trim_end_matches('.').to_ascii_uppercase();
+            assert_eq!(decode_filename(&words), expected);
+            assert_eq!(encode_filename(&expected), Ok(words));
+        }
+        assert_eq!(encode_filename("DK.SAV"), Ok([crate::consts::DK, 0, crate::consts::SAV]));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(encode_filename("A B"), Err(Error::IllegalChar { char: ' ', pos: 2 }));
+        assert_eq!(encode_filename("A..B"), Err(Error::IllegalChar { char: '.', pos: 3 }));
+        assert_eq!(encode_filename("%.MAC"), Err(Error::IllegalChar { char: '%', pos: 1 }));
+        assert_eq!(encode_filename("FILÉ"), Err(Error::IllegalChar { char: 'É', pos: 4 }));
+        assert_eq!(encode_filename("ABCDEF.ABCD"), Err(Error::InputTooLong { len: 4, max: 3 }));
+        assert_eq!(encode_filename("ABCDEFG"), Err(Error::InputTooLong { len: 7, max: 6 }));
+    }
+
+    #[cfg(feature = "std")]
+    type Files<'a> = &'a [(u16, &'a str, u16)]; // (status, name, length)
+
+    // Build a volume with a 4 segment directory at block 6. Segment n's files start at block 100+n*50.
+    #[cfg(feature = "std")]
+    fn image(segments: &[(u16, u16, Files)]) -> Vec<u8> {
+        let mut image = vec![0; 300 * BLOCK_SIZE];
+        for &(n, next, files) in segments {
+            let mut words = vec![4, next, 3, 0, 100 + n * 50];
+            for &(status, name, length) in files {
+                words.push(status);
+                words.extend(encode_filename(name).unwrap());
+                words.extend([length, 0, 0o26241]);
+            }
+            words.push(status::END_OF_SEGMENT);
+            let at = (6 + (n as usize - 1) * 2) * BLOCK_SIZE;
+            for (w, b) in words.iter().zip(image[at..].chunks_exact_mut(2)) { b.copy_from_slice(&w.to_le_bytes()) }
+        }
+        image
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn volume() {
+        use std::io::Cursor;
+        let disk = image(&[(1, 3, &[(status::PERMANENT, "SWAP.SYS", 25), (status::EMPTY, "", 10), (status::PERMANENT | status::PROTECTED, "PIP.SAV", 5)]),
+                            (3, 2, &[(status::TENTATIVE, "OPEN.DAT", 2), (status::PERMANENT, "DIR.SAV", 7)]),
+                            (2, 0, &[(status::PERMANENT, "LAST.TXT", 1)])]);
+        let mut volume = Volume::open(Cursor::new(&disk)).unwrap();
+        assert_eq!(volume.entries().len(), 6);
+        let files: Vec<(String, u32, u16)> = volume.files().map(|f| (f.filename(), f.start, f.length)).collect();
+        assert_eq!(files, [("SWAP.SYS".into(), 150, 25), ("PIP.SAV".into(), 185, 5), ("DIR.SAV".into(), 252, 7), ("LAST.TXT".into(), 200, 1)]);
+        let dir = *volume.files().nth(2).unwrap();
+        assert_eq!(volume.read(&dir).unwrap().len(), 7 * BLOCK_SIZE);
+
+        let looped = image(&[(1, 2, &[]), (2, 1, &[])]);
+        assert_eq!(Volume::open(Cursor::new(&looped)).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let past_end = image(&[(1, 5, &[])]);
+        assert_eq!(Volume::open(Cursor::new(&past_end)).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let mut unterminated = image(&[(1, 0, &[])]);
+        unterminated[(6 * BLOCK_SIZE)..(8 * BLOCK_SIZE)][10..].fill(0o1);
+        assert_eq!(Volume::open(Cursor::new(&unterminated)).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn modify() {
+        use std::io::Cursor;
+        let disk = image(&[(1, 2, &[(status::PERMANENT, "SWAP.SYS", 25), (status::EMPTY, "", 10)]),
+                            (2, 0, &[(status::TENTATIVE, "OPEN.DAT", 2), (status::PERMANENT, "DIR.SAV", 7)])]);
+        let mut volume = Volume::open(Cursor::new(disk)).unwrap();
+        volume.rename("dir.sav", "DUP.SAV").unwrap();
+        volume.set_protected("SWAP.SYS", true).unwrap();
+        assert_eq!(volume.rename("DIR.SAV", "X").unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert_eq!(volume.rename("OPEN.DAT", "X").unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert_eq!(volume.rename("DUP.SAV", "SWAP.SYS").unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(volume.rename("DUP.SAV", "BAD NAME").unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(volume.set_protected("NONE", true).unwrap_err().kind(), io::ErrorKind::NotFound);
+
+        let mut volume = Volume::open(volume.into_inner()).unwrap();
+        let files: Vec<(String, bool, u32)> = volume.files().map(|f| (f.filename(), f.is_protected(), f.start)).collect();
+        assert_eq!(files, [("SWAP.SYS".into(), true, 150), ("DUP.SAV".into(), false, 202)]);
+        volume.set_protected("SWAP.SYS", false).unwrap();
+        let volume = Volume::open(volume.into_inner()).unwrap();
+        assert!(!volume.files().next().unwrap().is_protected());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_segment() {
+        let disk = image(&[(1, 0, &[(status::PERMANENT, "SWAP.SYS", 25), (status::EMPTY, "", 10)])]);
+        let bytes: &[u8; Segment::SIZE] = disk[6 * BLOCK_SIZE..8 * BLOCK_SIZE].try_into().unwrap();
+        let mut segment = Segment::parse(bytes).unwrap();
+        assert_eq!(segment.write().unwrap(), *bytes);
+
+        segment.extra_bytes = 4;
+        let written = segment.write().unwrap();
+        assert_eq!(written[10 + 14..10 + 18], [0; 4]);
+        assert_eq!(Segment::parse(&written).unwrap(), segment);
+
+        segment.entries = vec![segment.entries[0]; 56];
+        assert_eq!(segment.write().unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        segment.entries.pop();
+        assert_eq!(Segment::parse(&segment.write().unwrap()).unwrap().entries.len(), 55);
+    }
+}
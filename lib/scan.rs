@@ -0,0 +1,195 @@
+// Finding plausible RADIX-50 text in binary data
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Finding RADIX-50 text in binary data: the RADIX-50 equivalent of `strings(1)`.
+//!
+//! Nearly every 16 bit value is a valid PDP-11 RADIX-50 word, so "decodes without error" isn't much of a filter.
+//! [`scan`] looks for runs of words that read like the names DEC software stores in RADIX-50 (symbols, file
+//! names, device names): a run has no spaces inside it (only trailing padding), starts with a letter, `$` or `.`,
+//! is at least half letters, and (if it has more than a few letters) has a vowel. It's a heuristic, so expect some
+//! noise from binary data.
+//!
+//! [`scan_into`] streams what it finds to a [`ReportSink`] instead, for inputs too big to collect the results of.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{Codec, Encoding, Endian, pdp10::Pdp10, pdp11::Pdp11, report::ReportSink};
+
+/// Options for [`scan`].
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Options {
+    /// The shortest text (not counting padding) worth reporting.
+    pub min_chars: usize,
+    /// Which encodings to try.
+    pub pdp10: bool,
+    pub pdp11: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self { Options { min_chars: 4, pdp10: true, pdp11: true } }
+}
+
+/// A run of plausible RADIX-50 text found by [`scan`].
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Found {
+    /// Byte offset of the first word.
+    pub offset: usize,
+    /// How the words were decoded.
+    pub encoding: Encoding,
+    pub endian: Endian,
+    /// How many words the text spans.
+    pub words: usize,
+    /// The decoded text, without its trailing space padding.
+    pub text: String,
+}
+
+/// Find runs of plausible RADIX-50 text in `bytes`.
+///
+/// Every alignment (odd addresses included) is tried in each byte order (PDP-11 words in big and little endian,
+/// PDP-10 words in PDP endian too), with each encoding `options` asks for.
+/// The results are in offset order. The same bytes can look plausible in more than one interpretation, in which
+/// case each one is reported.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Endian, scan::{self, Options}};
+/// let dump = [0xff, 0x73, 0xa9, 0xc0, 0x8f, 0xff, 0xff];
+/// let found = scan::scan(&dump, Options { pdp10: false, ..Options::default() });
+/// for f in &found {
+///     println!("{:6} {} {:?} {}", f.offset, f.encoding, f.endian, f.text);
+/// }
+/// assert!(found.iter().any(|f| f.offset == 1 && f.endian == Endian::Little && f.text == "$DSW"));
+/// ```
+pub fn scan(bytes: &[u8], options: Options) -> Vec<Found> {
+    let mut found: Vec<Found> = Vec::new();
+    scan_into(bytes, options, &mut found);
+    found.sort_by_key(|f| f.offset);
+    found
+}
+
+/// Like [`scan`], but hands each run to `sink` as soon as it's found instead of collecting them.
+///
+/// The runs come in offset order for each interpretation (encoding, byte order, and alignment) in turn, not in
+/// offset order overall.
+///
+/// # Examples
+/// ```
+/// # use radix50::{report::ReportSink, scan::{self, Found, Options}};
+/// # let dump = [0xff, 0x73, 0xa9, 0xc0, 0x8f, 0xff, 0xff];
+/// struct Print;
+/// impl ReportSink<Found> for Print {
+///     fn on_entry(&mut self, f: Found) { println!("{:6} {} {:?} {}", f.offset, f.encoding, f.endian, f.text) }
+///     fn on_error(&mut self, error: core::convert::Infallible) { match error {} }
+/// }
+/// scan::scan_into(&dump, Options::default(), &mut Print);
+///
+/// let mut found: Vec<Found> = Vec::new();
+/// scan::scan_into(&dump, Options::default(), &mut found);
+/// found.sort_by_key(|f| f.offset);
+/// assert_eq!(found, scan::scan(&dump, Options::default()));
+/// ```
+pub fn scan_into<S: ReportSink<Found> + ?Sized>(bytes: &[u8], options: Options, sink: &mut S) {
+    for endian in [Endian::Little, Endian::Big, Endian::Pdp] {
+        // PDP endian 16 bit words are just little endian ones
+        if options.pdp11 && endian != Endian::Pdp { scan_with::<Pdp11, S>(bytes, Encoding::Pdp11, endian, options.min_chars, sink) }
+        if options.pdp10 { scan_with::<Pdp10, S>(bytes, Encoding::Pdp10, endian, options.min_chars, sink) }
+    }
+}
+
+fn scan_with<C: Codec, S: ReportSink<Found> + ?Sized>(bytes: &[u8], encoding: Encoding, endian: Endian, min_chars: usize, found: &mut S) {
+    let size = encoding.word_bytes();
+    for align in 0..size.min(bytes.len()) {
+        let mut run: Option<(usize, String)> = None; // (first word's offset, text so far)
+        let words = crate::bytes::words(&bytes[align..], size, endian);
+        for (n, w) in words.enumerate() {
+            let offset = align + n * size;
+            let text = C::Word::try_from(w).ok().filter(|&w| C::is_valid_word(w)).map(C::decode_word);
+            let text = text.filter(|t| !t.starts_with(' ')); // Also rules out 0
+            let Some(text) = text else {
+                report(run.take(), encoding, endian, min_chars, found);
+                continue
+            };
+            let padded = text.ends_with(' ');
+            if text.trim_end().contains(' ') {
+                // It can't continue the run or start a new one
+                report(run.take(), encoding, endian, min_chars, found);
+                continue
+            }
+            run.get_or_insert((offset, String::new())).1.push_str(&text);
+            if padded { report(run.take(), encoding, endian, min_chars, found) }
+        }
+        report(run, encoding, endian, min_chars, found);
+    }
+}
+
+fn report<S: ReportSink<Found> + ?Sized>(run: Option<(usize, String)>, encoding: Encoding, endian: Endian, min_chars: usize, found: &mut S) {
+    let Some((offset, text)) = run else { return };
+    let words = text.len() / encoding.chars_per_word();
+    let text = text.trim_end();
+    if plausible(text, min_chars) {
+        found.on_entry(Found { offset, encoding, endian, words, text: text.into() });
+    }
+}
+
+fn plausible(text: &str, min_chars: usize) -> bool {
+    let letters = text.chars().filter(char::is_ascii_alphabetic).count();
+    text.len() >= min_chars
+        && text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '$' || c == '.')
+        && letters * 2 >= text.len()
+        && (letters < 4 || text.contains(['A', 'E', 'I', 'O', 'U', 'Y']))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pdp10, pdp11};
+
+    fn le16(words: &[u16]) -> Vec<u8> { words.iter().flat_map(|w| w.to_le_bytes()).collect() }
+
+    #[test]
+    fn finds_names() {
+        let mut dump = vec![0xff; 3];
+        dump.extend(le16(&pdp11::encode("PRINTF").unwrap()));
+        dump.extend([0, 0, 0xff]);
+        dump.extend(le16(&pdp11::encode("SWAP").unwrap()));
+        dump.extend([0xff, 0xff]);
+        // Other alignments and byte orders can have plausible looking noise, so just look for the real ones
+        let found = scan(&dump, Options { pdp10: false, ..Options::default() });
+        assert!(found.contains(&Found { offset: 3,  encoding: Encoding::Pdp11, endian: Endian::Little, words: 2, text: "PRINTF".into() }));
+        assert!(found.contains(&Found { offset: 10, encoding: Encoding::Pdp11, endian: Endian::Little, words: 2, text: "SWAP".into() }));
+        assert!(found.windows(2).all(|f| f[0].offset <= f[1].offset));
+
+        let mut dump = vec![0xff; 2];
+        dump.extend(pdp10::encode("FORTRAN").unwrap().iter().flat_map(|w| w.to_be_bytes()));
+        let found = scan(&dump, Options { pdp11: false, ..Options::default() });
+        assert!(found.contains(&Found { offset: 2, encoding: Encoding::Pdp10, endian: Endian::Big, words: 2, text: "FORTRAN".into() }));
+        assert!(found.iter().all(|f| f.encoding == Encoding::Pdp10));
+
+        // PDP-10 words stored by a PDP-11 as 32 bit numbers
+        let mut dump = vec![0xff; 3];
+        for w in pdp10::encode("FORTRAN").unwrap() { crate::bytes::write_word(w as u64, 4, Endian::Pdp, &mut dump) }
+        let found = scan(&dump, Options::default());
+        assert!(found.contains(&Found { offset: 3, encoding: Encoding::Pdp10, endian: Endian::Pdp, words: 2, text: "FORTRAN".into() }));
+        assert!(found.iter().all(|f| f.endian != Endian::Pdp || f.encoding == Encoding::Pdp10));
+    }
+
+    #[test]
+    fn rejects_implausible() {
+        assert!(!plausible("ABC", 4));
+        assert!(!plausible("1ABC", 4));
+        assert!(!plausible("A123", 4));
+        assert!(!plausible("BCDFG", 4));
+        assert!(!plausible("DSW", 4));
+        assert!(plausible("$DSW", 4));
+        assert!(plausible("SY0", 3));
+        assert!(plausible(".MAIN.", 4));
+        // Spaces inside a word break the run
+        assert_eq!(scan(&le16(&pdp11::encode("ABC D EFGHIJ").unwrap()), Options { pdp10: false, ..Options::default() })
+                       .iter().filter(|f| f.endian == Endian::Little && f.offset % 2 == 0).map(|f| f.text.as_str()).collect::<Vec<_>>(),
+                   ["EFGHIJ"]);
+        assert_eq!(scan(&[], Options::default()), []);
+        assert_eq!(scan(&[1], Options::default()), []);
+    }
+}
@@ -0,0 +1,77 @@
+// Finding RADIX-50 encoded names in binary data
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Searching binary data (memory dumps, disk images, object files) for RADIX-50 encoded names.
+
+use alloc::vec::Vec;
+
+use crate::{Encoding, Endian, Error};
+
+/// Find every place in `haystack` where `name` appears, RADIX-50 encoded with `encoding` and stored with
+/// `endian` byte order.
+///
+/// Returns the byte offsets of the matches, in order. Every offset is checked, not just the word aligned ones, so
+/// names at odd addresses still turn up. PDP-10 words are matched as `encoding.word_bytes()` byte words; names in
+/// 36 bit words packed into bytes (see [`Packing`](crate::bytes::Packing)) aren't found.
+///
+/// The name is space padded to a whole number of words, the way [`encode`](crate::encode) pads it, so pad it
+/// yourself to match a longer field: PDP-11 symbols are 6 characters (`"R0    "`, not `"R0"`, which would also
+/// match the start of `"R0A   "`). It returns an [`Error::IllegalChar`] if `name` can't be encoded.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Encoding, Endian, search::find_symbol};
+/// let dump = [0x00, 0x73, 0xa9, 0xc0, 0x8f, 0x00, 0x73, 0xa9, 0xc0, 0x8f];
+/// assert_eq!(find_symbol(&dump, "$DSW", Encoding::Pdp11, Endian::Little).unwrap(), [1, 6]);
+/// assert_eq!(find_symbol(&dump, "$DSW", Encoding::Pdp11, Endian::Big).unwrap(), []);
+/// ```
+pub fn find_symbol(haystack: &[u8], name: &str, encoding: Encoding, endian: Endian) -> Result<Vec<usize>, Error> {
+    let size = encoding.word_bytes();
+    let mut needle = Vec::new();
+    for w in crate::encode(encoding, name)? { crate::bytes::write_word(w, size, endian, &mut needle) }
+    if needle.is_empty() { return Ok(Vec::new()) }
+    Ok(haystack.windows(needle.len())
+               .enumerate()
+               .filter(|(_, window)| *window == needle.as_slice())
+               .map(|(offset, _)| offset)
+               .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pdp10, pdp11};
+
+    #[test]
+    fn finds_both_alignments() {
+        let [a, b] = pdp11::encode_symbol("PRINTF").unwrap();
+        let mut dump = vec![0xff; 3];
+        dump.extend(a.to_le_bytes());
+        dump.extend(b.to_le_bytes());
+        dump.extend([0xff; 1]);
+        dump.extend(a.to_le_bytes());
+        dump.extend(b.to_le_bytes());
+        assert_eq!(find_symbol(&dump, "PRINTF", Encoding::Pdp11, Endian::Little), Ok(vec![3, 8]));
+        assert_eq!(find_symbol(&dump, "PRI", Encoding::Pdp11, Endian::Little), Ok(vec![3, 8]));
+        assert_eq!(find_symbol(&dump, "PRINTF", Encoding::Pdp11, Endian::Big), Ok(vec![]));
+
+        let mut dump = vec![0; 2];
+        dump.extend(pdp10::encode_word("FOOBAR").unwrap().to_be_bytes());
+        assert_eq!(find_symbol(&dump, "FOOBAR", Encoding::Pdp10, Endian::Big), Ok(vec![2]));
+        assert_eq!(find_symbol(&dump, "FOOBAR", Encoding::Pdp10, Endian::Little), Ok(vec![]));
+        let w = pdp10::encode_word("FOOBAR").unwrap();
+        dump.extend([(w >> 16) as u8, (w >> 24) as u8, w as u8, (w >> 8) as u8]);
+        assert_eq!(find_symbol(&dump, "FOOBAR", Encoding::Pdp10, Endian::Pdp), Ok(vec![6]));
+    }
+
+    #[test]
+    fn edge_cases() {
+        assert_eq!(find_symbol(&[1, 2, 3], "", Encoding::Pdp11, Endian::Little), Ok(vec![]));
+        assert_eq!(find_symbol(&[1], "ABC", Encoding::Pdp11, Endian::Little), Ok(vec![]));
+        assert_eq!(find_symbol(&[0, 0, 0], "", Encoding::Pdp11, Endian::Little), Ok(vec![]));
+        assert_eq!(find_symbol(&[0, 0, 0], "   ", Encoding::Pdp11, Endian::Little), Ok(vec![0, 1]));
+        assert_eq!(find_symbol(&[], "a", Encoding::Pdp11, Endian::Little), Err(Error::IllegalChar { char: 'a', pos: 1 }));
+    }
+}
@@ -0,0 +1,239 @@
+// DEC SIXBIT encoding/decoding
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! DEC SIXBIT: 6 characters per 36 bit word.
+//!
+//! SIXBIT is RADIX-50's sibling. TOPS-10 and TOPS-20 use it for file names, device names, and user names (and
+//! plenty of PDP-11 software uses it too). Each character is 6 bits: its ASCII code minus 32, so the character
+//! set is the 64 ASCII characters from space to `_` (no lower case). The words are returned as `u64`s (only the
+//! low 36 bits are used).
+//!
+//! The functions mirror the ones in [`pdp10`](crate::pdp10), so switching between the encodings is easy.
+//!
+//! # Examples
+//! ```
+//! # use radix50::sixbit;
+//! assert_eq!(sixbit::encode("DSK").unwrap(), [0o446353000000]);
+//! assert_eq!(sixbit::decode([0o446353000000]), "DSK   ");
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::borrow::Borrow;
+
+use crate::Error;
+
+/// How many characters are encoded in each 36 bit word.
+pub const CHARS_PER_WORD: usize = 6;
+
+/// The largest valid SIXBIT word (2<sup>36</sup>-1, which decodes to `"______"`).
+pub const MAX_WORD: u64 = (1 << 36) - 1;
+
+/// Check whether a u64 is a valid SIXBIT word (that is, it fits in 36 bits).
+///
+/// # Examples
+/// ```
+/// # use radix50::sixbit::{is_valid_word,MAX_WORD};
+/// assert!(is_valid_word(0));
+/// assert!(is_valid_word(MAX_WORD));
+/// assert!(!is_valid_word(MAX_WORD + 1));
+/// ```
+pub const fn is_valid_word(w: u64) -> bool { w <= MAX_WORD }
+
+/// Iterate over the SIXBIT character set as `(code, char)` pairs, in code order.
+///
+/// # Examples
+/// ```
+/// # use radix50::sixbit::charset;
+/// let mut it = charset();
+/// assert_eq!(it.next(), Some((0, ' ')));
+/// assert_eq!(it.next(), Some((1, '!')));
+/// assert_eq!(it.last(), Some((63, '_')));
+/// ```
+pub fn charset() -> impl Iterator<Item = (u8, char)> { (0..64).map(|code| (code, (code + 32) as char)) }
+
+/// Look up the SIXBIT code for a single character.
+///
+/// Returns `None` if the character isn't part of the character set.
+///
+/// # Examples
+/// ```
+/// # use radix50::sixbit::char_to_code;
+/// assert_eq!(char_to_code('A'), Some(0o41));
+/// assert_eq!(char_to_code(' '), Some(0));
+/// assert_eq!(char_to_code('a'), None);
+/// ```
+pub const fn char_to_code(c: char) -> Option<u8> {
+    match c { ' '..='_' => Some(c as u8 - 32), _ => None }
+}
+
+/// Look up the character for a single SIXBIT code.
+///
+/// Returns `None` if the code isn't in the range 0-63.
+///
+/// # Examples
+/// ```
+/// # use radix50::sixbit::code_to_char;
+/// assert_eq!(code_to_char(0o41), Some('A'));
+/// assert_eq!(code_to_char(64), None);
+/// ```
+pub const fn code_to_char(code: u8) -> Option<char> {
+    if code < 64 { Some((code + 32) as char) } else { None }
+}
+
+/// Encode a string into SIXBIT, space padding it to a multiple of 6 characters.
+///
+/// It will return an [`Error::IllegalChar`] if any of the characters are not in the character set (see
+/// [`charset`]).
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,sixbit::encode};
+/// assert_eq!(encode("THIS IS A TEST").unwrap(), [0o645051630051, 0o630041006445, 0o636400000000]);
+/// assert_eq!(encode("lower"), Err(Error::IllegalChar { char: 'l', pos: 1 }));
+/// assert_eq!(encode("").unwrap(), []);
+/// ```
+pub fn encode(s: &str) -> Result<Vec<u64>, Error> {
+    let mut chars = s.chars();
+    let mut out = Vec::with_capacity(s.len().div_ceil(CHARS_PER_WORD));
+    while !chars.as_str().is_empty() {
+        out.push(encode_chars(&mut chars, out.len() * CHARS_PER_WORD)?);
+    }
+    Ok(out)
+}
+
+/// Encode 6 characters into a SIXBIT word. Missing characters are assumed to be spaces.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,sixbit::encode_word};
+/// assert_eq!(encode_word("ABCDEF").unwrap(), 0o414243444546);
+/// assert_eq!(encode_word("SYS").unwrap(), encode_word("SYS   ").unwrap());
+/// assert_eq!(encode_word("AB~"), Err(Error::IllegalChar { char: '~', pos: 3 }));
+/// ```
+pub fn encode_word(s: &str) -> Result<u64, Error> { encode_chars(&mut s.chars(), 0) }
+
+/// Encode a string into SIXBIT, encoding illegal characters as spaces and returning an [`Error::IllegalChar`]
+/// for each one along with the words.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,sixbit::{encode,encode_lossy}};
+/// let (words, errors) = encode_lossy("Dsk:");
+/// assert_eq!(words, encode("D  :").unwrap());
+/// assert_eq!(errors, [Error::IllegalChar { char: 's', pos: 2 }, Error::IllegalChar { char: 'k', pos: 3 }]);
+/// ```
+pub fn encode_lossy(s: &str) -> (Vec<u64>, Vec<Error>) {
+    let errors = validate(s).err().unwrap_or_default();
+    let cleaned: String = s.chars().map(|c| if char_to_code(c).is_some() { c } else { ' ' }).collect();
+    (encode(&cleaned).expect("illegal characters were replaced"), errors)
+}
+
+/// Check a string, returning an [`Error::IllegalChar`] for every character that isn't in the SIXBIT character
+/// set.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,sixbit::validate};
+/// assert_eq!(validate("DSK:[1,2]"), Ok(()));
+/// assert_eq!(validate("a{"), Err(vec![Error::IllegalChar { char: 'a', pos: 1 }, Error::IllegalChar { char: '{', pos: 2 }]));
+/// ```
+pub fn validate(s: &str) -> Result<(), Vec<Error>> {
+    let errors: Vec<Error> = s.chars().enumerate()
+                              .filter(|(_, c)| char_to_code(*c).is_none())
+                              .map(|(i, char)| Error::IllegalChar { char, pos: i + 1 })
+                              .collect();
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+/// Decode a sequence of SIXBIT words into a string. Bits above the low 36 are ignored.
+///
+/// # Examples
+/// ```
+/// # use radix50::sixbit::decode;
+/// assert_eq!(decode([0o645051630051, 0o630041006445, 0o636400000000]), "THIS IS A TEST    ");
+/// ```
+pub fn decode<I>(words: I) -> String where I: IntoIterator, I::Item: Borrow<u64> {
+    words.into_iter().fold(String::new(), |mut s, w| { s.extend(decode_word_chars(*w.borrow())); s })
+}
+
+/// Decode a sequence of SIXBIT words into a string, checking that each word fits in 36 bits.
+///
+/// [`decode`] ignores the extra bits, but this returns an [`Error::WordOutOfRange`] for the first word that has
+/// any.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,sixbit::decode_checked};
+/// assert_eq!(decode_checked([0o446353000000]), Ok("DSK   ".to_string()));
+/// assert_eq!(decode_checked([0o446353000000, 1 << 36]), Err(Error::WordOutOfRange { word: 1 << 36, index: 1 }));
+/// ```
+pub fn decode_checked<I>(words: I) -> Result<String, Error> where I: IntoIterator, I::Item: Borrow<u64> {
+    let mut s = String::new();
+    for (index, w) in words.into_iter().enumerate() {
+        let w = *w.borrow();
+        if !is_valid_word(w) { return Err(Error::WordOutOfRange { word: w, index }) }
+        s.extend(decode_word_chars(w));
+    }
+    Ok(s)
+}
+
+/// Decode a SIXBIT word into a 6 character string. Bits above the low 36 are ignored.
+///
+/// # Examples
+/// ```
+/// # use radix50::sixbit::decode_word;
+/// assert_eq!(decode_word(0o414243444546), "ABCDEF");
+/// assert_eq!(decode_word(0o637163000000), "SYS   ");
+/// ```
+pub fn decode_word(w: u64) -> String { decode_word_chars(w).iter().collect() }
+
+/// Decode a SIXBIT word into its 6 characters, without allocating. Bits above the low 36 are ignored.
+///
+/// # Examples
+/// ```
+/// # use radix50::sixbit::decode_word_chars;
+/// assert_eq!(decode_word_chars(0o446353000000), ['D', 'S', 'K', ' ', ' ', ' ']);
+/// ```
+pub fn decode_word_chars(w: u64) -> [char; 6] {
+    core::array::from_fn(|i| ((w >> (6 * (CHARS_PER_WORD - 1 - i)) & 0o77) as u8 + 32) as char)
+}
+
+// Encode the next 6 chars (space padded if they run out). `pos` is where they start in the string.
+fn encode_chars(chars: &mut core::str::Chars, pos: usize) -> Result<u64, Error> {
+    let mut w = 0;
+    for i in 0..CHARS_PER_WORD {
+        let c = chars.next().unwrap_or(' ');
+        w = w << 6 | char_to_code(c).ok_or(Error::IllegalChar { char: c, pos: pos + i + 1 })? as u64;
+    }
+    Ok(w)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let all: String = charset().map(|(_, c)| c).collect();
+        assert_eq!(all.len(), 64);
+        assert_eq!(decode(encode(&all).unwrap()), all.clone() + "  ");
+        for (code, c) in charset() {
+            assert_eq!(char_to_code(c), Some(code));
+            assert_eq!(code_to_char(code), Some(c));
+        }
+        assert_eq!(encode("DSK:FOO.TXT[1,2]").unwrap().len(), 3);
+        assert_eq!(decode_word(MAX_WORD), "______");
+        assert_eq!(decode_word(MAX_WORD + 1), "      ");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(encode("ABCDEFGh"), Err(Error::IllegalChar { char: 'h', pos: 8 }));
+        assert_eq!(encode("ABCDEFGHé"), Err(Error::IllegalChar { char: 'é', pos: 9 }));
+        assert_eq!(encode_word("\t"), Err(Error::IllegalChar { char: '\t', pos: 1 }));
+        assert_eq!(char_to_code('`'), None);
+        assert_eq!(char_to_code('\x1f'), None);
+    }
+}
@@ -0,0 +1,254 @@
+// RSX-11 and RT-11 symbol table files
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Reading and writing symbol definition (`.STB`) files.
+//!
+//! The RSX-11 task builder and the RT-11 linker can write out the global symbols of a linked program as an
+//! `.STB` file, so that debuggers and other programs can find them. It's an [object module](crate::obj11) with
+//! nothing but a GSD: the program's name, its ident, and a definition for each global symbol, with [PDP-11
+//! RADIX-50][pdp11] names. RT-11 writes it as formatted binary records, RSX-11 as [variable length
+//! records](crate::obj11::variable_records). [`SymbolTable::parse`] reads either, and [`SymbolTable::write`] writes
+//! either, so cross tools can hand their symbols to the debuggers on the real (or emulated) machine.
+//!
+//! [pdp11]: crate::pdp11::RADIX50_DECODE
+//!
+//! # Examples
+//! ```
+//! # use radix50::{obj11::{self, GsdEntry, GsdKind, flags}, stb::SymbolTable};
+//! # let mut file = Vec::new();
+//! # obj11::write_gsd(&[GsdEntry::new("HELLO", GsdKind::ModuleName, 0, 0)?,
+//! #                    GsdEntry::new("START", GsdKind::GlobalSymbol, flags::GLOBAL_DEFINITION | flags::GLOBAL_RELOCATABLE, 0o1000)?],
+//! #                  &mut file);
+//! let stb = SymbolTable::parse(&file).unwrap();
+//! for sym in &stb.symbols {
+//!     println!("{:6} {:06o}{}", sym.name, sym.value, if sym.is_relocatable() { "-R" } else { "" });
+//! }
+//! assert_eq!(stb.name.unwrap().to_string(), "HELLO");
+//! assert_eq!(stb.get("START").unwrap().value, 0o1000);
+//! # Ok::<(), radix50::Error>(())
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::{Error, Symbol6, obj11::{self, GsdEntry, GsdKind, ObjError, RecordType, flags}};
+
+/// A global symbol defined in a symbol table file.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct Symbol {
+    pub name: Symbol6,
+    pub value: u16,
+    /// The GSD flags (see [`obj11::flags`]).
+    pub flags: u8,
+}
+
+impl Symbol {
+    /// Make a symbol, encoding `name` (which can be up to 6 characters).
+    pub fn new(name: &str, value: u16, flags: u8) -> Result<Symbol, Error> {
+        Ok(Symbol { name: Symbol6::encode(name)?, value, flags })
+    }
+
+    /// Whether the symbol is relocatable (an address in the program) rather than absolute (a constant).
+    pub fn is_relocatable(&self) -> bool { self.flags & flags::GLOBAL_RELOCATABLE != 0 }
+}
+
+/// How a symbol table file's records are framed.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum Format {
+    /// Formatted binary records, like the RT-11 linker writes.
+    Rt11,
+    /// Variable length records, like the RSX-11 task builder writes.
+    Rsx11,
+}
+
+/// The contents of a symbol table file.
+#[derive(Debug,Clone,PartialEq,Eq,Default)]
+pub struct SymbolTable {
+    /// The program (task) name, from the module name entry.
+    pub name: Option<Symbol6>,
+    /// The program's version, from the ident entry.
+    pub ident: Option<Symbol6>,
+    /// The defined global symbols, in file order.
+    pub symbols: Vec<Symbol>,
+}
+
+impl SymbolTable {
+    /// Parse a symbol table file, in either formatted binary (RT-11) or variable length record (RSX-11) form. Global
+    /// symbol references and other kinds of GSD entries are skipped. Formatted binary files can start with zero
+    /// bytes of padding (which RT-11 media often have).
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{obj11::ObjError, stb::SymbolTable};
+    /// // An RSX-11 file with a GSD record defining absolute symbol `A` as 5, and an end of GSD record:
+    /// let file = [10, 0, 1, 0, 0x40, 6, 0, 0, 8, 4, 5, 0, 2, 0, 2, 0];
+    /// let stb = SymbolTable::parse(&file).unwrap();
+    /// assert_eq!(stb.symbols[0].name.to_string(), "A");
+    /// assert_eq!(stb.symbols[0].value, 5);
+    /// assert!(!stb.symbols[0].is_relocatable());
+    ///
+    /// assert_eq!(SymbolTable::parse(&file[..14]), Err(ObjError::Truncated { offset: 12 }));
+    /// ```
+    pub fn parse(bytes: &[u8]) -> Result<SymbolTable, ObjError> {
+        let formatted_binary = bytes.iter().position(|&b| b != 0).is_some_and(|start| bytes[start..].starts_with(&[1, 0]));
+        let records: Vec<&[u8]> = if formatted_binary { obj11::records(bytes).collect::<Result<_, _>>()? }
+                                  else                { obj11::variable_records(bytes).collect::<Result<_, _>>()? };
+        let mut stb = SymbolTable::default();
+        for data in records {
+            if data.len() < 2 || RecordType::from(u16::from_le_bytes([data[0], data[1]])) != RecordType::Gsd { continue }
+            for entry in obj11::gsd_entries(data) {
+                match entry.kind {
+                    GsdKind::ModuleName                            => stb.name  = Some(entry.name),
+                    GsdKind::Ident                                 => stb.ident = Some(entry.name),
+                    GsdKind::GlobalSymbol if entry.is_definition() => stb.symbols.push(Symbol { name: entry.name,
+                                                                                                value: entry.value,
+                                                                                                flags: entry.flags }),
+                    _                                              => {},
+                }
+            }
+        }
+        Ok(stb)
+    }
+
+    /// Write the symbol table as a symbol table file: a GSD with the name, the ident, and a global symbol
+    /// definition for each symbol (in order), then an end of GSD record and an end of module record.
+    ///
+    /// Each symbol's flags are written with [`flags::GLOBAL_DEFINITION`] set, since that's what the file is for. The
+    /// name and ident are left out if they're `None`, though debuggers generally expect the file to have a name.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{obj11::flags, stb::{Format, Symbol, SymbolTable}};
+    /// let stb = SymbolTable { name: Some("HELLO".parse()?), ident: None,
+    ///                         symbols: vec![Symbol::new("START", 0o1000, flags::GLOBAL_RELOCATABLE)?,
+    ///                                       Symbol::new("$DSW", 0o46, 0)?] };
+    /// let file = stb.write(Format::Rsx11);
+    /// let read = SymbolTable::parse(&file).unwrap();
+    /// assert_eq!(read.name, stb.name);
+    /// assert_eq!(read.get("START").unwrap().value, 0o1000);
+    /// assert!(read.get("START").unwrap().is_relocatable());
+    /// # Ok::<(), radix50::Error>(())
+    /// ```
+    pub fn write(&self, format: Format) -> Vec<u8> {
+        let write_record = match format {
+            Format::Rt11  => obj11::write_record,
+            Format::Rsx11 => obj11::write_variable_record,
+        };
+        let entries: Vec<GsdEntry> =
+            self.name.map(|name| GsdEntry { name, flags: 0, kind: GsdKind::ModuleName, value: 0 }).into_iter()
+                .chain(self.ident.map(|name| GsdEntry { name, flags: 0, kind: GsdKind::Ident, value: 0 }))
+                .chain(self.symbols.iter().map(|s| GsdEntry { name: s.name, flags: s.flags | flags::GLOBAL_DEFINITION,
+                                                              kind: GsdKind::GlobalSymbol, value: s.value }))
+                .collect();
+        let mut out = Vec::new();
+        for chunk in entries.chunks(obj11::MAX_GSD_ENTRIES) {
+            let payload: Vec<u8> = chunk.iter().flat_map(GsdEntry::to_bytes).collect();
+            write_record(RecordType::Gsd, &payload, &mut out);
+        }
+        write_record(RecordType::EndGsd, &[], &mut out);
+        write_record(RecordType::EndMod, &[], &mut out);
+        out
+    }
+
+    /// Look up a symbol by name. Returns `None` if it isn't defined (or isn't a valid symbol name).
+    pub fn get(&self, name: &str) -> Option<&Symbol> {
+        let name = Symbol6::encode(name).ok()?;
+        self.symbols.iter().find(|s| s.name == name)
+    }
+
+    /// Find the symbol at or before `address`, and how far past it `address` is, for annotating crash dumps and
+    /// disassembly (`$DSW+4`). Returns `None` if every symbol is above `address`.
+    ///
+    /// Absolute symbols count too, since the task builder's symbol tables have final addresses (which aren't
+    /// relocatable any more). When several symbols have the same value, the first one in the file wins.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::{Symbol6, obj11::flags, stb::{Symbol, SymbolTable}};
+    /// let sym = |name: &str, value| Symbol { name: name.parse().unwrap(), value, flags: flags::GLOBAL_DEFINITION };
+    /// let stb = SymbolTable { symbols: vec![sym("START", 0o1000), sym("$DSW", 0o46)], ..Default::default() };
+    /// assert_eq!(stb.symbolicate(0o1004), Some(("START".parse::<Symbol6>()?, 4)));
+    /// assert_eq!(stb.symbolicate(0o46), Some(("$DSW".parse::<Symbol6>()?, 0)));
+    /// assert_eq!(stb.symbolicate(0o40), None);
+    /// # Ok::<(), radix50::Error>(())
+    /// ```
+    pub fn symbolicate(&self, address: u16) -> Option<(Symbol6, u16)> {
+        let sym = self.symbols.iter().rev().filter(|s| s.value <= address).max_by_key(|s| s.value)?;
+        Some((sym.name, address - sym.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{format, vec};
+
+    #[test]
+    fn both_framings() {
+        let entries = [GsdEntry::new("MYTASK", GsdKind::ModuleName, 0, 0).unwrap(),
+                       GsdEntry::new("V01.02", GsdKind::Ident, 0, 0).unwrap(),
+                       GsdEntry::new("$DSW", GsdKind::GlobalSymbol, flags::GLOBAL_DEFINITION, 0o46).unwrap(),
+                       GsdEntry::new("EXTERN", GsdKind::GlobalSymbol, 0, 0).unwrap(),
+                       GsdEntry::new(". BLK.", GsdKind::Psect, flags::PSECT_RELOCATABLE, 0o200).unwrap(),
+                       GsdEntry::new("MAIN", GsdKind::GlobalSymbol, flags::GLOBAL_DEFINITION | flags::GLOBAL_RELOCATABLE, 0o1204).unwrap()];
+        let mut fb = Vec::new();
+        obj11::write_gsd(&entries, &mut fb);
+        obj11::write_record(RecordType::EndMod, &[], &mut fb);
+
+        let mut rsx = Vec::new();
+        for data in obj11::records(&fb) {
+            let data = data.unwrap();
+            rsx.extend((data.len() as u16).to_le_bytes());
+            rsx.extend(data);
+        }
+        let padded = [&[0; 6][..], &fb].concat();
+        for file in [fb, rsx, padded] {
+            let stb = SymbolTable::parse(&file).unwrap();
+            assert_eq!(stb.name, Some("MYTASK".parse().unwrap()));
+            assert_eq!(stb.ident, Some("V01.02".parse().unwrap()));
+            assert_eq!(stb.symbols, [Symbol { name: "$DSW".parse().unwrap(), value: 0o46, flags: 0o10 },
+                                     Symbol { name: "MAIN".parse().unwrap(), value: 0o1204, flags: 0o50 }]);
+            assert!(stb.get("MAIN").unwrap().is_relocatable());
+            assert_eq!(stb.get("EXTERN"), None);
+            assert_eq!(stb.get("TOOLONGNAME"), None);
+        }
+        assert_eq!(SymbolTable::parse(&[]), Ok(SymbolTable::default()));
+    }
+
+    #[test]
+    fn symbolicate() {
+        let sym = |name: &str, value| Symbol { name: name.parse().unwrap(), value, flags: flags::GLOBAL_DEFINITION };
+        let stb = SymbolTable { symbols: vec![sym("B", 0o2000), sym("A", 0o1000), sym("A2", 0o1000), sym("C", 0o177776)],
+                                ..Default::default() };
+        let name = |s: &str| s.parse::<Symbol6>().unwrap();
+        assert_eq!(stb.symbolicate(0o777), None);
+        assert_eq!(stb.symbolicate(0o1000), Some((name("A"), 0)));
+        assert_eq!(stb.symbolicate(0o1777), Some((name("A"), 0o777)));
+        assert_eq!(stb.symbolicate(0o2002), Some((name("B"), 2)));
+        assert_eq!(stb.symbolicate(0o177777), Some((name("C"), 1)));
+        assert_eq!(SymbolTable::default().symbolicate(0o1000), None);
+    }
+
+    #[test]
+    fn write() {
+        let mut stb = SymbolTable { name: Some("MYTASK".parse().unwrap()), ident: Some("V01.02".parse().unwrap()),
+                                    symbols: (0..20).map(|n| Symbol::new(&format!("SYM{}", n), n * 2, flags::GLOBAL_RELOCATABLE).unwrap()).collect() };
+        let fb = stb.write(Format::Rt11);
+        let rsx = stb.write(Format::Rsx11);
+        for s in &mut stb.symbols { s.flags |= flags::GLOBAL_DEFINITION }
+        for file in [&fb, &rsx] {
+            assert_eq!(SymbolTable::parse(file), Ok(stb.clone()));
+        }
+        let types = |records: Vec<&[u8]>| records.iter().map(|r| RecordType::from(u16::from_le_bytes([r[0], r[1]]))).collect::<Vec<_>>();
+        let expected = [RecordType::Gsd, RecordType::Gsd, RecordType::EndGsd, RecordType::EndMod];
+        assert_eq!(types(obj11::records(&fb).collect::<Result<_, _>>().unwrap()), expected);
+        assert_eq!(types(obj11::variable_records(&rsx).collect::<Result<_, _>>().unwrap()), expected);
+        assert_eq!(rsx[..4], [2 + 8 * 15, 0, 1, 0]);
+
+        let empty = SymbolTable::default();
+        assert_eq!(empty.write(Format::Rsx11), [2, 0, 2, 0, 2, 0, 6, 0]);
+        assert_eq!(SymbolTable::parse(&empty.write(Format::Rt11)), Ok(empty));
+        assert_eq!(Symbol::new("TOOLONG", 0, 0), Err(Error::InputTooLong { len: 7, max: 6 }));
+    }
+}
@@ -0,0 +1,178 @@
+// An owned string that is known to be valid RADIX-50 text
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+use alloc::{string::String, vec::Vec};
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+use core::str::FromStr;
+
+use crate::{Codec, DefaultCodec, Error};
+
+/// An owned string that only contains characters from a [`Codec`]'s character set, along with its encoded words.
+///
+/// The text is validated when the `Rad50String` is constructed, so code that takes one never has to deal with
+/// encoding errors. It derefs to [`str`], and [`as_words`](Rad50String::as_words) gets the encoded words (which
+/// are computed once, up front).
+///
+/// # Examples
+/// ```
+/// # #[cfg(all(feature = "pdp10", feature = "pdp11"))] {
+/// # use radix50::{Rad50String, Error, pdp10::Pdp10, pdp11::Pdp11};
+/// let s: Rad50String = "THIS IS A TEST".parse().unwrap();
+/// assert_eq!(s.as_words(), [32329, 30409, 30401, 805, 31200]);
+/// assert_eq!(s.len(), 14);
+/// assert!(s.starts_with("THIS"));
+///
+/// assert_eq!(Rad50String::<Pdp11>::new("this"), Err(Error::IllegalChar { char: 't', pos: 1 }));
+///
+/// let s = Rad50String::<Pdp10>::from_words(&[3119342419, 2970305215, 3046400000]).unwrap();
+/// assert_eq!(s, "THIS IS A TEST    ");
+/// # }
+/// ```
+pub struct Rad50String<C: Codec = DefaultCodec> {
+    text: String,
+    words: Vec<C::Word>,
+}
+
+impl<C: Codec> Rad50String<C> {
+    /// Create a `Rad50String` from some text, returning the first [`Error`] if it can't be encoded.
+    pub fn new(s: impl Into<String>) -> Result<Self, Error> {
+        let text = s.into();
+        let words = C::encode(&text)?;
+        Ok(Rad50String { text, words })
+    }
+
+    /// Create a `Rad50String` by decoding some words. It will return an [`Error::WordOutOfRange`] if a word isn't
+    /// a valid RADIX-50 word.
+    ///
+    /// The text will be a multiple of [`CHARS`](Codec::CHARS) characters long, including any padding spaces.
+    pub fn from_words<I>(words: I) -> Result<Self, Error> where I: IntoIterator, I::Item: Borrow<C::Word> {
+        let words: Vec<C::Word> = words.into_iter().map(|w| *w.borrow()).collect();
+        Ok(Rad50String { text: C::decode_checked(&words)?, words })
+    }
+
+    /// The encoded words. The last word is space padded if the text isn't a multiple of [`CHARS`](Codec::CHARS)
+    /// characters long.
+    pub fn as_words(&self) -> &[C::Word] { &self.words }
+
+    /// The text, as a `&str`.
+    pub fn as_str(&self) -> &str { &self.text }
+
+    /// Unwrap the text.
+    pub fn into_string(self) -> String { self.text }
+
+    /// Unwrap the encoded words.
+    pub fn into_words(self) -> Vec<C::Word> { self.words }
+}
+
+impl<C: Codec> Deref for Rad50String<C> {
+    type Target = str;
+    fn deref(&self) -> &str { &self.text }
+}
+
+impl<C: Codec> AsRef<str> for Rad50String<C> { fn as_ref(&self) -> &str { &self.text } }
+impl<C: Codec> Borrow<str> for Rad50String<C> { fn borrow(&self) -> &str { &self.text } }
+
+impl<C: Codec> FromStr for Rad50String<C> {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> { Self::new(s) }
+}
+
+impl<C: Codec> TryFrom<String> for Rad50String<C> {
+    type Error = Error;
+    fn try_from(s: String) -> Result<Self, Self::Error> { Self::new(s) }
+}
+
+impl<C: Codec> TryFrom<&str> for Rad50String<C> {
+    type Error = Error;
+    fn try_from(s: &str) -> Result<Self, Self::Error> { Self::new(s) }
+}
+
+impl<C: Codec> From<Rad50String<C>> for String { fn from(s: Rad50String<C>) -> Self { s.text } }
+
+impl<C: Codec> fmt::Display for Rad50String<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.pad(&self.text) }
+}
+
+impl<C: Codec> fmt::Debug for Rad50String<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { fmt::Debug::fmt(&self.text, f) }
+}
+
+// Not derived because derive would want C (a marker type) to implement these, and not C::Word. The words are
+// determined by the text, so only the text needs comparing.
+impl<C: Codec> Clone     for Rad50String<C> { fn clone(&self) -> Self { Rad50String { text: self.text.clone(), words: self.words.clone() } } }
+impl<C: Codec> PartialEq for Rad50String<C> { fn eq(&self, other: &Self) -> bool { self.text == other.text } }
+impl<C: Codec> Eq        for Rad50String<C> {}
+impl<C: Codec> Hash      for Rad50String<C> { fn hash<H: Hasher>(&self, state: &mut H) { self.text.hash(state) } }
+
+impl<C: Codec> PartialEq<str>  for Rad50String<C> { fn eq(&self, other: &str)  -> bool { self.text == other } }
+impl<C: Codec> PartialEq<&str> for Rad50String<C> { fn eq(&self, other: &&str) -> bool { self.text == *other } }
+
+// Only generates text from the codec's character set.
+#[cfg(feature = "arbitrary")]
+impl<'a, C: Codec> arbitrary::Arbitrary<'a> for Rad50String<C> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let text = u.arbitrary_iter::<u8>()?.map(|b| b.map(|b| C::DECODE[b as usize % C::DECODE.len()])).collect::<arbitrary::Result<String>>()?;
+        Self::new(text).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    use crate::{pdp10::{self, Pdp10}, pdp11::{self, Pdp11}, testdata};
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn construction() {
+        for golden in testdata::CORPUS {
+            let s = Rad50String::<Pdp11>::new(golden.text).unwrap();
+            assert_eq!(s.as_words(), golden.pdp11);
+            assert_eq!(&*s, golden.text);
+            let s = Rad50String::<Pdp10>::new(golden.text).unwrap();
+            assert_eq!(s.as_words(), golden.pdp10);
+            assert_eq!(s.as_str(), golden.text);
+        }
+        assert_eq!(Rad50String::<Pdp11>::new(""), Ok(Rad50String::<Pdp11>::new(String::new()).unwrap()));
+        assert_eq!(Rad50String::<Pdp11>::try_from("A_B"), Err(Error::IllegalChar { char: '_', pos: 2 }));
+        assert_eq!(Rad50String::<Pdp10>::try_from("ÀB".to_string()), Err(Error::IllegalChar { char: 'À', pos: 1 }));
+    }
+
+    #[cfg(all(feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn from_words() {
+        let s = Rad50String::<Pdp11>::from_words([1683, 31400]).unwrap();
+        assert_eq!(s, "ABCSY ");
+        assert_eq!(s.clone().into_words(), [1683, 31400]);
+        assert_eq!(String::from(s), "ABCSY ");
+        assert_eq!(Rad50String::<Pdp11>::from_words([1683, 64000]), Err(Error::WordOutOfRange { word: 64000, index: 1 }));
+        assert_eq!(Rad50String::<Pdp10>::from_words(pdp10::encode("FOOBAR").unwrap()).unwrap(), "FOOBAR");
+        assert_eq!(Rad50String::<Pdp11>::from_words(pdp11::encode("FOOBAR").unwrap()).unwrap().as_words(),
+                   pdp11::encode("FOOBAR").unwrap());
+    }
+
+    #[cfg(all(feature = "arbitrary", feature = "pdp10", feature = "pdp11"))]
+    #[test]
+    fn arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+        let bytes = (0..=255).collect::<Vec<u8>>();
+        for chunk in bytes.chunks(37) {
+            let s = Rad50String::<Pdp11>::arbitrary(&mut Unstructured::new(chunk)).unwrap();
+            assert_eq!(pdp11::decode(s.as_words()).trim_end(), s.trim_end());
+            let s = Rad50String::<Pdp10>::arbitrary(&mut Unstructured::new(chunk)).unwrap();
+            assert_eq!(pdp10::decode(s.as_words()).trim_end(), s.trim_end());
+        }
+    }
+
+    #[test]
+    fn formatting() {
+        let s: Rad50String = "ABC".parse().unwrap();
+        assert_eq!(format!("[{:<5}]", s), "[ABC  ]");
+        assert_eq!(format!("{:?}", s), r#""ABC""#);
+    }
+}
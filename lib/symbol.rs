@@ -0,0 +1,190 @@
+// 6 character PDP-11 symbol names, stored in 2 words
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{Error, pdp11};
+
+/// A PDP-11 symbol name: up to 6 RADIX-50 characters packed into 2 words.
+///
+/// This is how MACRO-11, the linkers, and the RSX and RT-11 file systems store symbols and names. Short names are
+/// padded with trailing spaces, so `"R0"` and `"R0    "` are the same symbol. [`Display`](fmt::Display) shows the
+/// name without the padding.
+///
+/// Symbols order by RADIX-50 collation order (which is also the numeric order of the raw words).
+///
+/// With the `bytemuck` or `zerocopy` features, slices of symbols can be reinterpreted directly from (native
+/// endian) bytes.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Symbol6, Error};
+/// let sym: Symbol6 = "$DSW".parse().unwrap();
+/// assert_eq!(sym.words(), [43379, 36800]);
+/// assert_eq!(sym.to_string(), "$DSW");
+/// assert_eq!(sym, "$DSW  ".parse().unwrap());
+/// assert_eq!(Symbol6::from([43379, 36800]), sym);
+///
+/// assert_eq!("TOOLONG".parse::<Symbol6>(), Err(Error::InputTooLong { len: 7, max: 6 }));
+/// ```
+#[derive(Clone,Copy,PartialEq,Eq,Hash,Default,PartialOrd,Ord)]
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::Pod, bytemuck::Zeroable))]
+#[cfg_attr(feature = "zerocopy", derive(zerocopy::FromBytes, zerocopy::IntoBytes, zerocopy::Immutable, zerocopy::KnownLayout))]
+#[repr(transparent)]
+pub struct Symbol6([u16; 2]);
+
+impl Symbol6 {
+    /// Wrap a raw word pair. The words aren't checked (see [`pdp11::is_valid_word`]).
+    pub const fn new(words: [u16; 2]) -> Self { Symbol6(words) }
+
+    /// Encode a name of up to 6 characters. Returns an [`Error::InputTooLong`] if it's longer than that. This is
+    /// the same as [`str::parse`].
+    pub fn encode(s: &str) -> Result<Self, Error> { pdp11::encode_symbol(s).map(Symbol6) }
+
+    /// The raw words.
+    pub const fn words(self) -> [u16; 2] { self.0 }
+
+    /// The symbol after this one in collation order (see [`pdp11::next_symbol`]).
+    pub fn next(self) -> Option<Self> { pdp11::next_symbol(&self.0).map(|w| Symbol6([w[0], w[1]])) }
+
+    /// The symbol before this one in collation order (see [`pdp11::prev_symbol`]).
+    pub fn prev(self) -> Option<Self> { pdp11::prev_symbol(&self.0).map(|w| Symbol6([w[0], w[1]])) }
+
+    /// Decode the name, without trimming the padding (so it's always 6 characters).
+    pub fn decode(self) -> String { pdp11::decode(self.0) }
+}
+
+impl fmt::Display for Symbol6 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.pad(&pdp11::decode_symbol(&self.0)) }
+}
+
+impl fmt::Debug for Symbol6 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "Symbol6({:?})", self.decode()) }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Symbol6 {
+    fn format(&self, f: defmt::Formatter<'_>) { defmt::write!(f, "Symbol6({=str})", self.decode().as_str()) }
+}
+
+impl FromStr for Symbol6 {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> { Self::encode(s) }
+}
+
+impl From<[u16; 2]> for Symbol6 { fn from(words: [u16; 2]) -> Self { Symbol6(words) } }
+impl From<Symbol6> for [u16; 2] { fn from(sym: Symbol6) -> Self { sym.0 } }
+
+// Only generates symbols made of valid words.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Symbol6 {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Symbol6([u.int_in_range(0..=pdp11::MAX_WORD)?, u.int_in_range(0..=pdp11::MAX_WORD)?]))
+    }
+}
+
+// Read and write as 2 words, so symbol fields can be embedded in binrw structure definitions.
+#[cfg(feature = "binrw")]
+impl binrw::BinRead for Symbol6 {
+    type Args<'a> = ();
+    fn read_options<R: std::io::Read + std::io::Seek>(reader: &mut R, endian: binrw::Endian, _: ()) -> binrw::BinResult<Self> {
+        <[u16; 2]>::read_options(reader, endian, ()).map(Symbol6)
+    }
+}
+
+#[cfg(feature = "binrw")]
+impl binrw::BinWrite for Symbol6 {
+    type Args<'a> = ();
+    fn write_options<W: std::io::Write + std::io::Seek>(&self, writer: &mut W, endian: binrw::Endian, _: ()) -> binrw::BinResult<()> {
+        self.0.write_options(writer, endian, ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding() {
+        assert_eq!(Symbol6::encode(""),       Ok(Symbol6([0, 0])));
+        assert_eq!(Symbol6::encode("R0"),     Ok(Symbol6([pdp11::encode_word("R0").unwrap(), 0])));
+        assert_eq!(Symbol6::encode("PRINT"),  Ok(Symbol6(pdp11::encode("PRINT").unwrap().try_into().unwrap())));
+        assert_eq!(Symbol6::encode("FOOBAR"), Ok(Symbol6(pdp11::encode("FOOBAR").unwrap().try_into().unwrap())));
+        assert_eq!(Symbol6::encode("FOOBARB"), Err(Error::InputTooLong { len: 7, max: 6 }));
+        assert_eq!(Symbol6::encode("FOO_"),   Err(Error::IllegalChar { char: '_', pos: 4 }));
+    }
+
+    #[test]
+    fn padding() {
+        assert_eq!("AB".parse::<Symbol6>(), "AB    ".parse::<Symbol6>());
+        assert_ne!("AB".parse::<Symbol6>(), " AB".parse::<Symbol6>());
+        let sym: Symbol6 = " AB".parse().unwrap();
+        assert_eq!(sym.to_string(), " AB");
+        assert_eq!(sym.decode(), " AB   ");
+        assert_eq!(format!("[{:>6}]", Symbol6::encode("AB").unwrap()), "[    AB]");
+        assert_eq!(format!("{:?}", Symbol6::encode("AB").unwrap()), r#"Symbol6("AB    ")"#);
+        assert_eq!(Symbol6::default().to_string(), "");
+    }
+
+    #[test]
+    fn ordering() {
+        let mut syms: Vec<Symbol6> = ["FOO", "A", "$A", "AAAAAB", "AAAAAA", "9"].iter().map(|s| s.parse().unwrap()).collect();
+        syms.sort();
+        assert_eq!(syms.iter().map(|s| s.to_string()).collect::<Vec<_>>(), ["A", "AAAAAA", "AAAAAB", "FOO", "$A", "9"]);
+        assert_eq!(Symbol6::encode("AAAAAA").unwrap().next(), Symbol6::encode("AAAAAB").ok());
+        assert_eq!(Symbol6::encode("AAAAAB").unwrap().prev(), Symbol6::encode("AAAAAA").ok());
+        assert_eq!(Symbol6::encode("").unwrap().prev(), None);
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+        let bytes = (0..=255).rev().collect::<Vec<u8>>();
+        let mut u = Unstructured::new(&bytes);
+        while !u.is_empty() {
+            let sym = Symbol6::arbitrary(&mut u).unwrap();
+            assert!(sym.words().iter().all(|&w| pdp11::is_valid_word(w)));
+            assert_eq!(sym.decode().parse::<Symbol6>(), Ok(sym));
+        }
+    }
+
+    #[cfg(feature = "binrw")]
+    #[test]
+    fn binrw() {
+        use binrw::{binrw, BinReaderExt, BinWriterExt, io::Cursor};
+
+        #[binrw]
+        #[brw(little)]
+        #[derive(Debug, PartialEq)]
+        struct Entry { name: Symbol6, value: u16 }
+
+        let entry = Entry { name: "$DSW".parse().unwrap(), value: 0o46 };
+        let mut cursor = Cursor::new(vec![]);
+        cursor.write_le(&entry).unwrap();
+        assert_eq!(cursor.get_ref(), &[0x73, 0xa9, 0xc0, 0x8f, 0x26, 0x00]);
+        cursor.set_position(0);
+        assert_eq!(cursor.read_le::<Entry>().unwrap(), entry);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn bytemuck() {
+        let words = [43379_u16, 36800, 30000, 0];
+        let syms: &[Symbol6] = bytemuck::cast_slice(bytemuck::cast_slice::<u16, u8>(&words));
+        assert_eq!(syms.iter().map(|s| s.to_string()).collect::<Vec<_>>(), ["$DSW", "R0"]);
+    }
+
+    #[cfg(feature = "zerocopy")]
+    #[test]
+    fn zerocopy() {
+        use zerocopy::FromBytes;
+        let bytes = [43379_u16, 36800, 30000, 0].iter().flat_map(|w| w.to_ne_bytes()).collect::<Vec<_>>();
+        let syms = <[Symbol6]>::ref_from_bytes(&bytes).unwrap();
+        assert_eq!(syms.iter().map(|s| s.to_string()).collect::<Vec<_>>(), ["$DSW", "R0"]);
+    }
+}
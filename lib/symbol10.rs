@@ -0,0 +1,298 @@
+// PDP-10 symbol words
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! PDP-10 symbol words: a RADIX-50 name with 4 flag bits, in a 36 bit word.
+//!
+//! The PDP-6 and PDP-10 symbol tables (DDT, MIDAS, MACRO, LINK) store each symbol's name in the low 32 bits of a
+//! 36 bit word, as a [`pdp10`] RADIX-50 word, and use the top 4 bits as flags. DEC and MIT called these words
+//! SQUOZE, after the IBM 709 SHARE encoding they borrowed the layout from. Only the PDP-10 flavor is here: the
+//! character set is [`pdp10::RADIX50_DECODE`], and the low 32 bits of a symbol word are exactly a [`pdp10`]
+//! RADIX-50 word. (The IBM 709's own SQUOZE character set and ordering aren't implemented.) Words are `u64`s (only
+//! the low 36 bits are used).
+//!
+//! What the flag bits mean is up to the program that wrote them. DEC's PDP-10 symbol tables use them to mark
+//! global, local, and half-killed symbols: [`SymbolFlags`] has their meanings. To move a name to another RADIX-50
+//! flavor, decode it and re-encode it.
+//!
+//! # Examples
+//! ```
+//! # use radix50::{pdp10, symbol10};
+//! let w = symbol10::encode_symbol("START", 0o04).unwrap();
+//! assert_eq!(w, 0o226547750660);
+//! assert_eq!(symbol10::decode_symbol(w), (0o04, "START".to_string()));
+//! assert_eq!(symbol10::to_parts(w), (0o04, pdp10::encode_word("START").unwrap()));
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::{borrow::Borrow, fmt, ops};
+
+use crate::{Error, pdp10};
+
+/// How many characters are encoded in each 36 bit word.
+pub const CHARS_PER_WORD: usize = 6;
+
+/// The largest flags value (the flags are the top 4 bits of the 36 bit word).
+pub const MAX_FLAGS: u8 = 0o17;
+
+/// The largest valid symbol word: all the flags set, with a [`pdp10::MAX_WORD`] name.
+pub const MAX_WORD: u64 = (MAX_FLAGS as u64) << 32 | pdp10::MAX_WORD as u64;
+
+/// Check whether a u64 is a valid symbol word (it fits in 36 bits and its name is a valid RADIX-50 word).
+///
+/// # Examples
+/// ```
+/// # use radix50::symbol10::{is_valid_word,MAX_WORD};
+/// assert!(is_valid_word(0));
+/// assert!(is_valid_word(MAX_WORD));
+/// assert!(!is_valid_word(0o37777777777));
+/// assert!(!is_valid_word(1 << 36));
+/// ```
+pub const fn is_valid_word(w: u64) -> bool { w >> 36 == 0 && pdp10::is_valid_word(w as u32) }
+
+/// Build a symbol word from its flags and its [`pdp10`] RADIX-50 name word. Only the low 4 bits of `flags` are
+/// used.
+///
+/// # Examples
+/// ```
+/// # use radix50::{pdp10, symbol10::from_parts};
+/// assert_eq!(from_parts(0o04, pdp10::encode_word("START").unwrap()), 0o226547750660);
+/// ```
+pub const fn from_parts(flags: u8, name: u32) -> u64 { ((flags & MAX_FLAGS) as u64) << 32 | name as u64 }
+
+/// Split a symbol word into its flags and its [`pdp10`] RADIX-50 name word. Bits above the low 36 are ignored.
+///
+/// # Examples
+/// ```
+/// # use radix50::{pdp10, symbol10::to_parts};
+/// let (flags, name) = to_parts(0o226547750660);
+/// assert_eq!(flags, 0o04);
+/// assert_eq!(pdp10::decode_word(name), "START ");
+/// ```
+pub const fn to_parts(w: u64) -> (u8, u32) { ((w >> 32) as u8 & MAX_FLAGS, w as u32) }
+
+/// Encode a symbol name of up to 6 characters, with `flags` in the top 4 bits (only the low 4 bits of `flags`
+/// are used).
+///
+/// Short names are space padded. Names longer than 6 characters return an [`Error::InputTooLong`], and characters
+/// outside the character set return an [`Error::IllegalChar`].
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,symbol10::encode_symbol};
+/// assert_eq!(encode_symbol("PAT", 0), Ok(0o24036776000));
+/// assert_eq!(encode_symbol("LOOP1", 0o04), Ok(0o221214116320));
+/// assert_eq!(encode_symbol("LOOP123", 0), Err(Error::InputTooLong { len: 7, max: 6 }));
+/// assert_eq!(encode_symbol("A-B", 0), Err(Error::IllegalChar { char: '-', pos: 2 }));
+/// ```
+pub fn encode_symbol(s: &str, flags: u8) -> Result<u64, Error> {
+    let len = s.chars().count();
+    if len > CHARS_PER_WORD { return Err(Error::InputTooLong { len, max: CHARS_PER_WORD }) }
+    Ok(from_parts(flags, pdp10::encode_word(s)?))
+}
+
+/// Decode a symbol word into its flags and its name, trimming the name's trailing space padding. Bits above the
+/// low 36 are ignored.
+///
+/// # Examples
+/// ```
+/// # use radix50::symbol10::decode_symbol;
+/// assert_eq!(decode_symbol(0o221214116320), (0o04, "LOOP1".to_string()));
+/// assert_eq!(decode_symbol(0), (0, "".to_string()));
+/// ```
+pub fn decode_symbol(w: u64) -> (u8, String) {
+    let (flags, name) = to_parts(w);
+    let mut s = pdp10::decode_word(name);
+    s.truncate(s.trim_end_matches(' ').len());
+    (flags, s)
+}
+
+/// Encode a string into symbol words with no flags set, space padding it to a multiple of 6 characters.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,symbol10::encode};
+/// assert_eq!(encode("ABCDEFPAT").unwrap(), [1157975016, 2692480000]);
+/// assert_eq!(encode("abc"), Err(Error::IllegalChar { char: 'a', pos: 1 }));
+/// ```
+pub fn encode(s: &str) -> Result<Vec<u64>, Error> { Ok(pdp10::encode(s)?.into_iter().map(u64::from).collect()) }
+
+/// Decode a sequence of symbol words into a string, ignoring their flags.
+///
+/// # Examples
+/// ```
+/// # use radix50::symbol10::decode;
+/// assert_eq!(decode([0o210501245750, 0o24036776000]), "ABCDEFPAT   ");
+/// ```
+pub fn decode<I>(words: I) -> String where I: IntoIterator, I::Item: Borrow<u64> {
+    pdp10::decode(words.into_iter().map(|w| to_parts(*w.borrow()).1))
+}
+
+/// Decode a sequence of symbol words into a string, ignoring their flags but checking that each word is valid
+/// (see [`is_valid_word`]). It returns an [`Error::WordOutOfRange`] for the first one that isn't.
+///
+/// # Examples
+/// ```
+/// # use radix50::{Error,symbol10::decode_checked};
+/// assert_eq!(decode_checked([0o210501245750]), Ok("ABCDEF".to_string()));
+/// assert_eq!(decode_checked([0o210501245750, 1 << 36]), Err(Error::WordOutOfRange { word: 1 << 36, index: 1 }));
+/// ```
+pub fn decode_checked<I>(words: I) -> Result<String, Error> where I: IntoIterator, I::Item: Borrow<u64> {
+    let mut s = String::new();
+    for (index, w) in words.into_iter().enumerate() {
+        let w = *w.borrow();
+        if !is_valid_word(w) { return Err(Error::WordOutOfRange { word: w, index }) }
+        s.extend(pdp10::decode_word_chars(to_parts(w).1));
+    }
+    Ok(s)
+}
+
+/// The flag bits of the symbols in DEC's PDP-10 symbol tables: the ones MACRO-10 and the compilers write to `.REL`
+/// files (see [`rel10`](crate::rel10)), and LINK-10 leaves for DDT (see [`ddt`](crate::ddt) and
+/// [`exe10`](crate::exe10)).
+///
+/// DEC's manuals write the flags as the top 6 bits of the word, in octal, so [`GLOBAL`](Self::GLOBAL) is their "04"
+/// and [`LOCAL`](Self::LOCAL) is "10". Some combinations have their own meanings: no flags at all is a program name,
+/// `GLOBAL | LOCAL` is a block name, and `DELETED | SUPPRESSED` is a request for a global symbol defined elsewhere.
+///
+/// # Examples
+/// ```
+/// # use radix50::symbol10::SymbolFlags;
+/// let w = (SymbolFlags::GLOBAL | SymbolFlags::SUPPRESSED).encode("START")?;
+/// assert_eq!(w, 0o466547750660);
+/// let (flags, name) = SymbolFlags::decode(w);
+/// assert!(flags.contains(SymbolFlags::SUPPRESSED));
+/// assert_eq!((flags.bits(), name.as_str()), (0o11, "START"));
+/// assert_eq!(format!("{:?}", flags), "SymbolFlags(GLOBAL | SUPPRESSED)");
+/// # Ok::<(), radix50::Error>(())
+/// ```
+#[derive(Clone,Copy,PartialEq,Eq,Hash,Default)]
+pub struct SymbolFlags(u8);
+
+impl SymbolFlags {
+    /// A global symbol (DEC's "04").
+    pub const GLOBAL: SymbolFlags = SymbolFlags(0o01);
+    /// A local symbol (DEC's "10").
+    pub const LOCAL: SymbolFlags = SymbolFlags(0o02);
+    /// DDT won't accept the symbol as input: it's been deleted (DEC's "20").
+    pub const DELETED: SymbolFlags = SymbolFlags(0o04);
+    /// DDT won't use the symbol in its output: it's suppressed, or "half-killed" (DEC's "40").
+    pub const SUPPRESSED: SymbolFlags = SymbolFlags(0o10);
+
+    const NAMES: [(&'static str, SymbolFlags); 4] = [("GLOBAL", Self::GLOBAL), ("LOCAL", Self::LOCAL), ("DELETED", Self::DELETED),
+                                                     ("SUPPRESSED", Self::SUPPRESSED)];
+
+    /// No flags.
+    pub const fn empty() -> Self { SymbolFlags(0) }
+
+    /// All the flags.
+    pub const fn all() -> Self { SymbolFlags(MAX_FLAGS) }
+
+    /// The raw bits.
+    pub const fn bits(self) -> u8 { self.0 }
+
+    /// Make flags from raw bits. Returns `None` if any bits other than the low 4 are set.
+    pub const fn from_bits(bits: u8) -> Option<Self> { if bits & !MAX_FLAGS == 0 { Some(SymbolFlags(bits)) } else { None } }
+
+    /// Make flags from raw bits, ignoring any bits other than the low 4.
+    pub const fn from_bits_truncate(bits: u8) -> Self { SymbolFlags(bits & MAX_FLAGS) }
+
+    /// Whether no flags are set.
+    pub const fn is_empty(self) -> bool { self.0 == 0 }
+
+    /// Whether all the flags in `other` are set.
+    pub const fn contains(self, other: SymbolFlags) -> bool { self.0 & other.0 == other.0 }
+
+    /// Split a symbol word into its flags and its name (see [`decode_symbol`]).
+    pub fn decode(w: u64) -> (SymbolFlags, String) {
+        let (flags, name) = decode_symbol(w);
+        (SymbolFlags(flags), name)
+    }
+
+    /// Encode a symbol name with these flags (see [`encode_symbol`]).
+    pub fn encode(self, name: &str) -> Result<u64, Error> { encode_symbol(name, self.0) }
+}
+
+impl ops::BitOr for SymbolFlags {
+    type Output = SymbolFlags;
+    fn bitor(self, other: SymbolFlags) -> SymbolFlags { SymbolFlags(self.0 | other.0) }
+}
+
+impl ops::BitOrAssign for SymbolFlags {
+    fn bitor_assign(&mut self, other: SymbolFlags) { self.0 |= other.0 }
+}
+
+impl ops::BitAnd for SymbolFlags {
+    type Output = SymbolFlags;
+    fn bitand(self, other: SymbolFlags) -> SymbolFlags { SymbolFlags(self.0 & other.0) }
+}
+
+impl ops::Not for SymbolFlags {
+    type Output = SymbolFlags;
+    fn not(self) -> SymbolFlags { SymbolFlags(!self.0 & MAX_FLAGS) }
+}
+
+impl fmt::Debug for SymbolFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SymbolFlags(")?;
+        if self.is_empty() { write!(f, "empty")? }
+        for (n, (name, _)) in Self::NAMES.iter().filter(|(_, flag)| self.contains(*flag)).enumerate() {
+            write!(f, "{}{}", if n == 0 { "" } else { " | " }, name)?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbols() {
+        for flags in 0..=MAX_FLAGS {
+            for name in ["", "A", "%$.", "ZZZZZZ", "%%%%%%", "LOOP1"] {
+                let w = encode_symbol(name, flags).unwrap();
+                assert!(is_valid_word(w));
+                assert_eq!(decode_symbol(w), (flags, name.into()));
+                assert_eq!(to_parts(w), (flags, pdp10::encode_word(name).unwrap()));
+            }
+        }
+        assert_eq!(encode_symbol("%%%%%%", MAX_FLAGS), Ok(MAX_WORD));
+        assert_eq!(encode_symbol("A", 0o20), encode_symbol("A", 0));
+        assert_eq!(decode_symbol(1 << 36 | 0o210501245750), (0o04, "ABCDEF".into()));
+    }
+
+    #[test]
+    fn strings() {
+        let text = "THIS IS A TEST";
+        let words = encode(text).unwrap();
+        assert_eq!(decode(&words), "THIS IS A TEST    ");
+        let flagged: Vec<u64> = words.iter().map(|&w| w | 0o17 << 32).collect();
+        assert_eq!(decode_checked(&flagged), Ok("THIS IS A TEST    ".into()));
+        assert_eq!(decode_checked([pdp10::MAX_WORD as u64 + 1]), Err(Error::WordOutOfRange { word: pdp10::MAX_WORD as u64 + 1, index: 0 }));
+    }
+
+    #[test]
+    fn symbol_flags() {
+        let request = SymbolFlags::DELETED | SymbolFlags::SUPPRESSED;
+        assert_eq!(request.bits(), 0o14);
+        assert!(request.contains(SymbolFlags::DELETED) && !request.contains(SymbolFlags::GLOBAL | SymbolFlags::DELETED));
+        assert_eq!(!request, SymbolFlags::GLOBAL | SymbolFlags::LOCAL);
+        assert_eq!(request & SymbolFlags::DELETED, SymbolFlags::DELETED);
+        let mut flags = SymbolFlags::empty();
+        flags |= SymbolFlags::LOCAL;
+        assert_eq!(flags, SymbolFlags::LOCAL);
+        assert_eq!(SymbolFlags::from_bits(0o17), Some(SymbolFlags::all()));
+        assert_eq!(SymbolFlags::from_bits(0o20), None);
+        assert_eq!(SymbolFlags::from_bits_truncate(0o22), SymbolFlags::LOCAL);
+        assert_eq!(alloc::format!("{:?}", SymbolFlags::empty()), "SymbolFlags(empty)");
+        assert_eq!(alloc::format!("{:?}", SymbolFlags::all()), "SymbolFlags(GLOBAL | LOCAL | DELETED | SUPPRESSED)");
+
+        for bits in 0..=MAX_FLAGS {
+            let flags = SymbolFlags::from_bits(bits).unwrap();
+            assert_eq!(flags.encode("LOOP1"), encode_symbol("LOOP1", bits));
+            assert_eq!(SymbolFlags::decode(flags.encode("LOOP1").unwrap()), (flags, "LOOP1".into()));
+        }
+    }
+}
@@ -0,0 +1,262 @@
+// SIMH tape images
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Reading and writing SIMH magtape images (`.TAP` files).
+//!
+//! A `.TAP` file stores each tape record as a 4 byte little endian length, the data (padded to an even length),
+//! and the length again. The top 4 bits of the length word are its class: 0 for a good record and 8 for a record
+//! that had a read error. A length of 0 is a tape mark, 0xFFFFFFFF marks the end of the medium, and 0xFFFFFFFE is
+//! an erase gap.
+//!
+//! [`items`] and [`files`] read images. [`write_record`], [`write_tape_mark`], and [`write_file`] make them.
+//!
+//! # Examples
+//! ```
+//! # use radix50::tap::{self, Item};
+//! # let image = [4, 0, 0, 0, 1, 2, 3, 4, 4, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff];
+//! for item in tap::items(&image) {
+//!     match item? {
+//!         Item::Record(data)    => println!("{} byte record", data.len()),
+//!         Item::BadRecord(data) => println!("{} byte record (with errors)", data.len()),
+//!         Item::TapeMark        => println!("tape mark"),
+//!     }
+//! }
+//! # assert_eq!(tap::items(&image).collect::<Result<Vec<_>, _>>()?, [Item::Record(&[1, 2, 3, 4]), Item::TapeMark]);
+//! # Ok::<(), tap::TapError>(())
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+
+/// The length word of a tape mark.
+pub const TAPE_MARK: u32 = 0;
+/// The length word that marks the end of the medium.
+pub const END_OF_MEDIUM: u32 = 0xFFFF_FFFF;
+/// The length word of an erase gap.
+pub const ERASE_GAP: u32 = 0xFFFF_FFFE;
+
+/// The class (top 4 bits of the length word) of a good record.
+pub const CLASS_GOOD: u8 = 0;
+/// The class of a record that had a read error.
+pub const CLASS_BAD: u8 = 8;
+
+/// The biggest record length (the low 28 bits of the length word).
+pub const MAX_RECORD: usize = 0x0FFF_FFFF;
+
+/// One thing on a tape.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum Item<'a> {
+    /// A data record.
+    Record(&'a [u8]),
+    /// A data record that had a read error. The data may or may not be any good.
+    BadRecord(&'a [u8]),
+    /// A tape mark (the end of a file).
+    TapeMark,
+}
+
+/// Errors from reading `.TAP` files.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+#[non_exhaustive]
+pub enum TapError {
+    /// The record at byte `offset` runs past the end of the file.
+    Truncated { offset: usize },
+    /// The record at byte `offset` has a different length after it than before it.
+    LengthMismatch { offset: usize },
+    /// The length word at byte `offset` has a class this doesn't know about.
+    UnknownClass { offset: usize, class: u8 },
+}
+
+impl core::error::Error for TapError {
+}
+
+impl fmt::Display for TapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TapError::Truncated { offset }           => write!(f, "Truncated record at byte {}", offset),
+            TapError::LengthMismatch { offset }      => write!(f, "Mismatched record lengths at byte {}", offset),
+            TapError::UnknownClass { offset, class } => write!(f, "Unknown record class {:X} at byte {}", class, offset),
+        }
+    }
+}
+
+/// Iterate over the records and tape marks in a `.TAP` image. Erase gaps are skipped. Iteration stops at the end
+/// of the medium marker (or the end of `bytes`), or after the first error.
+///
+/// # Examples
+/// ```
+/// # use radix50::tap::{self, Item, TapError};
+/// let image = [3, 0, 0, 0, 1, 2, 3, 0, 3, 0, 0, 0, 0xfe, 0xff, 0xff, 0xff, 1, 0, 0, 0x80, 9, 0, 1, 0, 0, 0x80,
+///              5, 0, 0, 0, 1, 2, 3, 4, 5, 0, 4, 0, 0, 0];
+/// let mut items = tap::items(&image);
+/// assert_eq!(items.next(), Some(Ok(Item::Record(&[1, 2, 3]))));
+/// assert_eq!(items.next(), Some(Ok(Item::BadRecord(&[9]))));
+/// assert_eq!(items.next(), Some(Err(TapError::LengthMismatch { offset: 26 })));
+/// assert_eq!(items.next(), None);
+/// ```
+pub fn items(bytes: &[u8]) -> impl Iterator<Item = Result<Item<'_>, TapError>> {
+    let mut at = 0;
+    let mut done = false;
+    core::iter::from_fn(move || {
+        while !done {
+            let offset = at;
+            let Some(&[a, b, c, d]) = bytes.get(at..at + 4) else {
+                done = true;
+                return (at < bytes.len()).then_some(Err(TapError::Truncated { offset }));
+            };
+            let word = u32::from_le_bytes([a, b, c, d]);
+            at += 4;
+            match word {
+                END_OF_MEDIUM => done = true,
+                ERASE_GAP     => {},
+                TAPE_MARK     => return Some(Ok(Item::TapeMark)),
+                _ => {
+                    let (class, len) = ((word >> 28) as u8, word as usize & MAX_RECORD);
+                    let item = match class {
+                        CLASS_GOOD | CLASS_BAD => read_record(bytes, offset, class, len),
+                        _                      => Err(TapError::UnknownClass { offset, class }),
+                    };
+                    at += len + len % 2 + 4;
+                    done = item.is_err();
+                    return Some(item);
+                },
+            }
+        }
+        None
+    })
+}
+
+fn read_record(bytes: &[u8], offset: usize, class: u8, len: usize) -> Result<Item<'_>, TapError> {
+    let start = offset + 4;
+    let end = start + len + len % 2;
+    let trailer = bytes.get(end..end + 4).ok_or(TapError::Truncated { offset })?;
+    if trailer != &bytes[offset..start] { return Err(TapError::LengthMismatch { offset }) }
+    let data = &bytes[start..start + len];
+    Ok(if class == CLASS_BAD { Item::BadRecord(data) } else { Item::Record(data) })
+}
+
+/// Collect the records of each file on a tape (the records between tape marks), stopping at the logical end of
+/// the tape: 2 tape marks in a row, or the end of the image. Bad records are included.
+///
+/// # Examples
+/// ```
+/// # use radix50::tap;
+/// let image = [2, 0, 0, 0, 1, 2, 2, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 3, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+/// assert_eq!(tap::files(&image).unwrap(), [vec![&[1, 2][..]], vec![&[3][..]]]);
+/// ```
+pub fn files(bytes: &[u8]) -> Result<Vec<Vec<&[u8]>>, TapError> {
+    let mut files = Vec::new();
+    let mut file = Vec::new();
+    let mut marks = 0;
+    for item in items(bytes) {
+        match item? {
+            Item::Record(data) | Item::BadRecord(data) => { file.push(data); marks = 0 },
+            Item::TapeMark => {
+                marks += 1;
+                if marks == 2 { return Ok(files) }
+                files.push(core::mem::take(&mut file));
+            },
+        }
+    }
+    if !file.is_empty() { files.push(file) }
+    Ok(files)
+}
+
+/// Append a good record holding `data` to `out`.
+///
+/// # Panics
+/// If `data` is empty (its length word would be a tape mark), or longer than [`MAX_RECORD`].
+///
+/// # Examples
+/// ```
+/// # use radix50::tap::{self, Item};
+/// let mut image = Vec::new();
+/// tap::write_record(b"ABC", &mut image);
+/// assert_eq!(image, [3, 0, 0, 0, b'A', b'B', b'C', 0, 3, 0, 0, 0]);
+/// ```
+pub fn write_record(data: &[u8], out: &mut Vec<u8>) {
+    assert!((1..=MAX_RECORD).contains(&data.len()), "tape records are 1 to {MAX_RECORD} bytes long");
+    let len = (data.len() as u32).to_le_bytes();
+    out.extend(len);
+    out.extend(data);
+    if data.len() % 2 == 1 { out.push(0) }
+    out.extend(len);
+}
+
+/// Append a tape mark to `out`. Two in a row mark the logical end of the tape.
+pub fn write_tape_mark(out: &mut Vec<u8>) { out.extend(TAPE_MARK.to_le_bytes()) }
+
+/// Append an end of medium marker to `out`. SIMH doesn't need one at the end of an image, but it can stop a tape
+/// short of whatever comes after it.
+pub fn write_end_of_medium(out: &mut Vec<u8>) { out.extend(END_OF_MEDIUM.to_le_bytes()) }
+
+/// Append a file to `out`: each of `records`, then a tape mark. Write one more tape mark after the last file.
+///
+/// # Examples
+/// ```
+/// # use radix50::tap;
+/// let mut image = Vec::new();
+/// tap::write_file([&b"HELLO"[..], b"WORLD"], &mut image);
+/// tap::write_file(b"0123456789".chunks(4), &mut image);
+/// tap::write_tape_mark(&mut image);
+/// assert_eq!(tap::files(&image).unwrap(), [vec![&b"HELLO"[..], b"WORLD"], vec![b"0123", b"4567", b"89"]]);
+/// ```
+pub fn write_file<I>(records: I, out: &mut Vec<u8>) where I: IntoIterator, I::Item: AsRef<[u8]> {
+    for record in records { write_record(record.as_ref(), out) }
+    write_tape_mark(out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn record(class: u8, data: &[u8]) -> Vec<u8> {
+        let len = ((class as u32) << 28 | data.len() as u32).to_le_bytes();
+        let mut r = len.to_vec();
+        r.extend(data);
+        if data.len() % 2 == 1 { r.push(0) }
+        r.extend(len);
+        r
+    }
+
+    #[test]
+    fn tape() {
+        let mut image = record(0, b"HDR1");
+        image.extend(TAPE_MARK.to_le_bytes());
+        image.extend(ERASE_GAP.to_le_bytes());
+        image.extend(record(0, b"odd"));
+        image.extend(record(CLASS_BAD, b""));
+        image.extend(TAPE_MARK.to_le_bytes());
+        image.extend(TAPE_MARK.to_le_bytes());
+        image.extend(record(0, b"after the end"));
+        image.extend(END_OF_MEDIUM.to_le_bytes());
+        image.extend(record(0, b"after the medium"));
+
+        assert_eq!(items(&image).collect::<Result<Vec<_>, _>>().unwrap(),
+                   [Item::Record(b"HDR1"), Item::TapeMark, Item::Record(b"odd"), Item::BadRecord(b""), Item::TapeMark,
+                    Item::TapeMark, Item::Record(b"after the end")]);
+        assert_eq!(files(&image).unwrap(), [vec![&b"HDR1"[..]], vec![&b"odd"[..], &b""[..]]]);
+
+        let good = record(0, b"abc");
+        assert_eq!(items(&good[..7]).collect::<Vec<_>>(), [Err(TapError::Truncated { offset: 0 })]);
+        assert_eq!(items(&good[..2]).collect::<Vec<_>>(), [Err(TapError::Truncated { offset: 0 })]);
+        assert_eq!(items(&record(3, b"abc")).collect::<Vec<_>>(), [Err(TapError::UnknownClass { offset: 0, class: 3 })]);
+        assert_eq!(files(&[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn writing() {
+        let mut image = Vec::new();
+        write_record(b"odd", &mut image);
+        assert_eq!(image, record(0, b"odd"));
+        write_file([b"y", &b"x"[..]], &mut image);
+        write_tape_mark(&mut image);
+        write_end_of_medium(&mut image);
+        write_record(b"lost", &mut image);
+        assert_eq!(items(&image).collect::<Result<Vec<_>, _>>().unwrap(),
+                   [Item::Record(b"odd"), Item::Record(b"y"), Item::Record(b"x"), Item::TapeMark, Item::TapeMark]);
+        assert_eq!(files(&image).unwrap(), [vec![&b"odd"[..], b"y", b"x"]]);
+    }
+}
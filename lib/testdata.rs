@@ -0,0 +1,104 @@
+// Golden RADIX-50 test corpus
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! A canonical corpus of strings and their RADIX-50 encodings.
+//!
+//! This backs the crate's [stability guarantee](crate#stability): the crate's tests assert that every entry
+//! encodes to exactly these words (and decodes back). Entries are only ever added, never changed.
+//!
+//! It's public (behind the `testdata` feature) so that downstream crates can check their own conversions
+//! against the same data.
+//!
+//! # Examples
+//! ```
+//! # use radix50::{pdp10, pdp11, testdata::CORPUS};
+//! for golden in CORPUS {
+//!     assert_eq!(pdp10::encode(golden.text).unwrap(), golden.pdp10);
+//!     assert_eq!(pdp11::encode(golden.text).unwrap(), golden.pdp11);
+//! }
+//! ```
+
+/// A string and its expected encodings.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Golden {
+    /// The input string
+    pub text: &'static str,
+    /// [`text`](Golden::text) encoded with [`pdp10::encode`](crate::pdp10::encode)
+    pub pdp10: &'static [u32],
+    /// [`text`](Golden::text) encoded with [`pdp11::encode`](crate::pdp11::encode)
+    pub pdp11: &'static [u16],
+}
+
+/// The golden corpus.
+pub const CORPUS: &[Golden] = &[
+    Golden { text: "",                            pdp10: &[],
+                                                  pdp11: &[] },
+    Golden { text: " ",                           pdp10: &[0],
+                                                  pdp11: &[0] },
+    Golden { text: "A",                           pdp10: &[1126400000],
+                                                  pdp11: &[1600] },
+    Golden { text: "AB",                          pdp10: &[1157120000],
+                                                  pdp11: &[1680] },
+    Golden { text: "ABC",                         pdp10: &[1157952000],
+                                                  pdp11: &[1683] },
+    Golden { text: "ABCD",                        pdp10: &[1157974400],
+                                                  pdp11: &[1683, 6400] },
+    Golden { text: "ABCDEF",                      pdp10: &[1157975016],
+                                                  pdp11: &[1683, 6606] },
+    Golden { text: "ABCDEFG",                     pdp10: &[1157975016, 1740800000],
+                                                  pdp11: &[1683, 6606, 11200] },
+    Golden { text: "   ",                         pdp10: &[0],
+                                                  pdp11: &[0] },
+    Golden { text: "THIS IS A TEST",              pdp10: &[3119342419, 2970305215, 3046400000],
+                                                  pdp11: &[32329, 30409, 30401, 805, 31200] },
+    Golden { text: "ABCDEFGHIJKLMNOPQRSTUVWXYZ",  pdp10: &[1157975016, 1788128862, 2418282708, 3048436554, 3676160000],
+                                                  pdp11: &[1683, 6606, 11529, 16452, 21375, 26298, 31221, 36144, 41040] },
+    Golden { text: "0123456789",                  pdp10: &[107718606, 737872000],
+                                                  pdp11: &[49272, 54195, 59118, 62400] },
+    Golden { text: ".$%",                         pdp10: &[3888576000],
+                                                  pdp11: &[45909] },
+    Golden { text: "$$$.%%%",                     pdp10: &[3990972799, 3993600000],
+                                                  pdp11: &[44307, 45989, 46400] },
+    Golden { text: "999999",                      pdp10: &[1050256410],
+                                                  pdp11: &[63999, 63999] },
+    Golden { text: "%%%%%%",                      pdp10: &[4095999999],
+                                                  pdp11: &[47589, 47589] },
+    Golden { text: "SYMBOL",                      pdp10: &[3060692222],
+                                                  pdp11: &[31413, 3812] },
+    Golden { text: "FILE05",                      pdp10: &[1688472046],
+                                                  pdp11: &[9972, 9235] },
+    Golden { text: "SWAP.SYS",                    pdp10: &[3054827109, 3658240000],
+                                                  pdp11: &[31321, 26739, 40760] },
+    Golden { text: "DK RT11SJ.SYS",               pdp10: &[1487406002, 280380395, 2969600000],
+                                                  pdp11: &[6840, 29631, 50370, 45585, 30400] },
+    Golden { text: "PIP   SAV",                   pdp10: &[2712704000, 2999808000],
+                                                  pdp11: &[25976, 0, 30462] },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pdp10, pdp11};
+
+    fn padded(s: &str, chars: usize) -> String {
+        format!("{:<1$}", s, s.len().div_ceil(chars) * chars)
+    }
+
+    #[test]
+    fn golden_encode() {
+        for golden in CORPUS {
+            assert_eq!(pdp10::encode(golden.text).as_deref(), Ok(golden.pdp10), "pdp10 {:?}", golden.text);
+            assert_eq!(pdp11::encode(golden.text).as_deref(), Ok(golden.pdp11), "pdp11 {:?}", golden.text);
+        }
+    }
+
+    #[test]
+    fn golden_decode() {
+        for golden in CORPUS {
+            assert_eq!(pdp10::decode(golden.pdp10), padded(golden.text, 6), "pdp10 {:?}", golden.text);
+            assert_eq!(pdp11::decode(golden.pdp11), padded(golden.text, 3), "pdp11 {:?}", golden.text);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+// RSX-11 task image label blocks
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Reading the label block of RSX-11 task images (`.TSK` files).
+//!
+//! The first block of a task image is its label block: the task's name and the partition it runs in (both [PDP-11
+//! RADIX-50][pdp11]), its size and addresses, and a descriptor for each shared library or common it's linked
+//! against (also named in RADIX-50). The next block holds the task's logical unit (LUN) assignments. The task's
+//! global symbols aren't in the image, the task builder writes them to a [symbol table file](crate::stb).
+//!
+//! [pdp11]: crate::pdp11::RADIX50_DECODE
+//!
+//! # Examples
+//! ```
+//! # use radix50::tsk::{self, Label};
+//! # let mut image = [0u8; 1024];
+//! # image[0..8].copy_from_slice(&[0x78, 0x65, 0x40, 0x06, 0x96, 0x2c, 0, 0]);
+//! # image[0o456] = 1;
+//! # image[512..516].copy_from_slice(b"TI\0\0");
+//! let label = Label::parse(image[..512].try_into().unwrap());
+//! println!("Task {} in partition {}", label.task.name, label.partition);
+//! for lun in tsk::luns(image[512..1024].try_into().unwrap(), label.luns) {
+//!     println!("  {lun}");
+//! }
+//! # assert_eq!(label.task.name.to_string(), "PIPA");
+//! # assert_eq!(label.partition.to_string(), "GEN");
+//! # assert_eq!(tsk::luns(image[512..1024].try_into().unwrap(), label.luns).next().unwrap().to_string(), "TI0:");
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::Symbol6;
+
+/// The size of a label block, in bytes.
+pub const BLOCK_SIZE: usize = 512;
+
+// Offsets into the label block
+const L_BPAR: usize = 0o4;
+const L_BSA:  usize = 0o10;
+const L_BLIB: usize = 0o40;
+const L_BPRI: usize = 0o442;
+
+/// The size and layout of the task itself, or of a library it's linked against.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct Descriptor {
+    /// The task or library name.
+    pub name: Symbol6,
+    /// The base (lowest) virtual address.
+    pub base: u16,
+    /// The highest virtual address mapped by the first window.
+    pub high_window: u16,
+    /// The highest virtual address.
+    pub highest: u16,
+    /// The load size, in 64 byte blocks.
+    pub load_size: u16,
+    /// The maximum size, in 64 byte blocks.
+    pub max_size: u16,
+    /// The offset into the partition, in 64 byte blocks.
+    pub offset: u16,
+    /// The number of address windows.
+    pub windows: u16,
+    /// The size of the overlay segment descriptors, in bytes.
+    pub segment_size: u16,
+    /// The flags word.
+    pub flags: u16,
+    /// The raw creation date: years since 1900, month, and day (see [`Descriptor::date`]).
+    pub date_words: [u16; 3],
+}
+
+impl Descriptor {
+    /// The size of a library descriptor in the label block, in bytes.
+    pub const SIZE: usize = 0o34;
+
+    fn parse(block: &[u8], name_at: usize, rest_at: usize) -> Descriptor {
+        let w = |n: usize| word(block, rest_at + n * 2);
+        Descriptor {
+            name: Symbol6::new([word(block, name_at), word(block, name_at + 2)]),
+            base: w(0), high_window: w(1), highest: w(2), load_size: w(3), max_size: w(4), offset: w(5),
+            windows: w(6), segment_size: w(7), flags: w(8), date_words: [w(9), w(10), w(11)],
+        }
+    }
+
+    /// The creation date as `(year, month, day)`. Returns `None` if the month or day is 0.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::tsk::Label;
+    /// let mut block = [0u8; 512];
+    /// block[0o32..0o40].copy_from_slice(&[87, 0, 3, 0, 14, 0]);
+    /// assert_eq!(Label::parse(&block).task.date(), Some((1987, 3, 14)));
+    /// assert_eq!(Label::parse(&[0; 512]).task.date(), None);
+    /// ```
+    pub fn date(&self) -> Option<(u16, u8, u8)> {
+        let [year, month, day] = self.date_words;
+        (month != 0 && day != 0).then_some((1900 + year, month as u8, day as u8))
+    }
+}
+
+/// A task image's label block.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Label {
+    /// The task's name and layout.
+    pub task: Descriptor,
+    /// The name of the partition the task runs in.
+    pub partition: Symbol6,
+    /// The shared libraries and commons the task is linked against.
+    pub libraries: Vec<Descriptor>,
+    /// The task's priority (0 for the system default).
+    pub priority: u16,
+    /// The task's transfer (start) address.
+    pub transfer: u16,
+    /// How much the task gets extended by when it's installed, in 64 byte blocks.
+    pub extension: u16,
+    /// The block number (relative to the label block) of the segment load list.
+    pub segment_load_list: u16,
+    /// The block number (relative to the label block) of the task header.
+    pub header_block: u16,
+    /// How many blocks the label block group takes.
+    pub label_blocks: u16,
+    /// How many LUNs the task has (see [`luns`]).
+    pub luns: u16,
+}
+
+impl Label {
+    /// Parse the first block of a task image. Library descriptors are read until one with a blank name.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::tsk::Label;
+    /// let mut block = [0u8; 512];
+    /// block[0o40..0o44].copy_from_slice(&[0x0b, 0x26, 0x5b, 0x71]);
+    /// block[0o44..0o46].copy_from_slice(&0o160000u16.to_le_bytes());
+    /// block[0o444..0o446].copy_from_slice(&0o1000u16.to_le_bytes());
+    /// let label = Label::parse(&block);
+    /// assert_eq!(label.libraries.len(), 1);
+    /// assert_eq!(label.libraries[0].name.to_string(), "FCSRES");
+    /// assert_eq!(label.libraries[0].base, 0o160000);
+    /// assert_eq!(label.transfer, 0o1000);
+    /// ```
+    pub fn parse(block: &[u8; BLOCK_SIZE]) -> Label {
+        let libraries = (L_BLIB..L_BPRI - Descriptor::SIZE + 1).step_by(Descriptor::SIZE)
+                                                                  .map(|at| Descriptor::parse(block, at, at + 4))
+                                                                  .take_while(|lib| lib.name.words() != [0, 0])
+                                                                  .collect();
+        let w = |n: usize| word(block, L_BPRI + n * 2);
+        Label {
+            task: Descriptor::parse(block, 0, L_BSA),
+            partition: Symbol6::new([word(block, L_BPAR), word(block, L_BPAR + 2)]),
+            libraries,
+            priority: w(0), transfer: w(1), extension: w(2), segment_load_list: w(3), header_block: w(4),
+            label_blocks: w(5), luns: w(6),
+        }
+    }
+}
+
+fn word(block: &[u8], at: usize) -> u16 { u16::from_le_bytes([block[at], block[at + 1]]) }
+
+/// A LUN assignment: a device name and unit number.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct Lun {
+    /// The 2 letter device name, in ASCII.
+    pub device: [u8; 2],
+    pub unit: u16,
+}
+
+/// Displays as `DDn:` with the unit number in octal.
+impl fmt::Display for Lun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{:o}:", self.device[0] as char, self.device[1] as char, self.unit)
+    }
+}
+
+/// Iterate over the first `count` LUN assignments in the second block of a task image (`count` is usually
+/// [`Label::luns`]). Each takes 2 words: the device name and the unit number.
+///
+/// # Examples
+/// ```
+/// # use radix50::tsk;
+/// let mut block = [0u8; 512];
+/// block[..8].copy_from_slice(&[b'S', b'Y', 0, 0, b'D', b'B', 1, 0]);
+/// assert_eq!(tsk::luns(&block, 2).map(|lun| lun.to_string()).collect::<Vec<_>>(), ["SY0:", "DB1:"]);
+/// ```
+pub fn luns(block: &[u8; BLOCK_SIZE], count: u16) -> impl Iterator<Item = Lun> + '_ {
+    block.chunks_exact(4)
+         .take(count as usize)
+         .map(|b| Lun { device: [b[0], b[1]], unit: u16::from_le_bytes([b[2], b[3]]) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn put(block: &mut [u8], at: usize, words: &[u16]) {
+        for (n, w) in words.iter().enumerate() { block[at + n * 2..at + n * 2 + 2].copy_from_slice(&w.to_le_bytes()) }
+    }
+
+    #[test]
+    fn label() {
+        let mut block = [0u8; BLOCK_SIZE];
+        put(&mut block, 0, &"MYTASK".parse::<Symbol6>().unwrap().words());
+        put(&mut block, L_BPAR, &"GEN".parse::<Symbol6>().unwrap().words());
+        put(&mut block, L_BSA, &[0, 0o37776, 0o37776, 0o200, 0o200, 0, 1, 0, 0o100, 124, 12, 25]);
+        let libs = ["SYSRES", "FCSRES", "RMSRES", "LB1", "LB2", "LB3", "LB4", "LB5", "LB6"];
+        for (n, lib) in libs.iter().enumerate() {
+            let at = L_BLIB + n * Descriptor::SIZE;
+            put(&mut block, at, &lib.parse::<Symbol6>().unwrap().words());
+            put(&mut block, at + 4, &[0o140000 + n as u16]);
+        }
+        put(&mut block, L_BPRI, &[50, 0o1000, 0o100, 3, 2, 2, 6]);
+
+        let label = Label::parse(&block);
+        assert_eq!(label.task.name.to_string(), "MYTASK");
+        assert_eq!(label.partition.to_string(), "GEN");
+        assert_eq!((label.task.highest, label.task.load_size, label.task.flags), (0o37776, 0o200, 0o100));
+        assert_eq!(label.task.date(), Some((2024, 12, 25)));
+        assert_eq!(label.libraries.iter().map(|l| (l.name.to_string(), l.base)).collect::<Vec<_>>(),
+                   libs.iter().enumerate().map(|(n, l)| (l.to_string(), 0o140000 + n as u16)).collect::<Vec<_>>());
+        assert_eq!((label.priority, label.transfer, label.extension), (50, 0o1000, 0o100));
+        assert_eq!((label.segment_load_list, label.header_block, label.label_blocks, label.luns), (3, 2, 2, 6));
+
+        put(&mut block, L_BLIB + 2 * Descriptor::SIZE, &[0, 0]);
+        assert_eq!(Label::parse(&block).libraries.len(), 2);
+        assert_eq!(luns(&[0; BLOCK_SIZE], 1000).count(), 128);
+    }
+}
@@ -0,0 +1,244 @@
+// A single RADIX-50 encoded word, typed by its codec
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+use alloc::string::String;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::str::FromStr;
+
+use crate::{Codec, DefaultCodec, Error};
+#[cfg(feature = "pdp10")]
+use crate::pdp10::Pdp10;
+#[cfg(feature = "pdp11")]
+use crate::pdp11::Pdp11;
+
+/// A RADIX-50 encoded machine word that knows which [`Codec`] it was encoded with.
+///
+/// It's a transparent wrapper around the raw word (a `u16` for [`Pdp11`], a `u32` for [`Pdp10`]), so it costs
+/// nothing over passing the raw integers around, but it can't be accidentally mixed up with other integers (or
+/// with words from the other encoding).
+///
+/// - [`Display`](fmt::Display) shows the decoded text.
+/// - [`FromStr`] encodes a string (of up to [`CHARS`](Codec::CHARS) characters).
+/// - [`Ord`] is RADIX-50 collation order (which is also the numeric order of the raw words).
+///
+/// With the `zerocopy` feature, slices of words can be reinterpreted directly from (native endian) bytes with
+/// [`FromBytes`](https://docs.rs/zerocopy/latest/zerocopy/trait.FromBytes.html). With the `bytemuck` feature
+/// it implements [`TransparentWrapper`](https://docs.rs/bytemuck/latest/bytemuck/trait.TransparentWrapper.html),
+/// so a slice of raw words (say, from [`bytemuck::cast_slice`](https://docs.rs/bytemuck/latest/bytemuck/fn.cast_slice.html))
+/// can be wrapped without copying.
+///
+/// # Examples
+/// ```
+/// # #[cfg(all(feature = "pdp10", feature = "pdp11"))] {
+/// # use radix50::{Rad50Word, Error, pdp10::Pdp10};
+/// let w: Rad50Word = "SY".parse().unwrap();
+/// assert_eq!(w.get(), 31400);
+/// assert_eq!(w.to_string(), "SY ");
+/// assert_eq!(u16::from(w), 31400);
+/// assert_eq!(Rad50Word::from(31400), w);
+///
+/// assert!("DK".parse::<Rad50Word>().unwrap() < w);
+/// assert_eq!("a".parse::<Rad50Word>(), Err(Error::IllegalChar { char: 'a', pos: 1 }));
+///
+/// let w: Rad50Word<Pdp10> = "FOO".parse().unwrap();
+/// assert_eq!(u32::from(w), 1704000000);
+/// assert_eq!(format!("{w}"), "FOO   ");
+/// # }
+/// ```
+#[cfg_attr(feature = "bytemuck", derive(bytemuck::TransparentWrapper), transparent(C::Word))]
+#[cfg_attr(feature = "zerocopy", derive(zerocopy::FromBytes, zerocopy::IntoBytes, zerocopy::Immutable, zerocopy::KnownLayout))]
+#[repr(transparent)]
+pub struct Rad50Word<C: Codec = DefaultCodec> {
+    word: C::Word,
+    codec: PhantomData<C>,
+}
+
+impl<C: Codec> Rad50Word<C> {
+    /// Wrap a raw word. The word isn't checked: see [`is_valid`](Rad50Word::is_valid).
+    pub const fn new(word: C::Word) -> Self { Rad50Word { word, codec: PhantomData } }
+
+    /// The raw word.
+    pub const fn get(self) -> C::Word { self.word }
+
+    /// Whether the word is a valid RADIX-50 word (see [`Codec::is_valid_word`]).
+    pub fn is_valid(self) -> bool { C::is_valid_word(self.word) }
+
+    /// Encode up to [`CHARS`](Codec::CHARS) characters into a word. Missing characters are assumed to be spaces.
+    /// This is the same as [`str::parse`].
+    pub fn encode(s: &str) -> Result<Self, Error> { C::encode_word(s).map(Self::new) }
+
+    /// Decode the word into a [`CHARS`](Codec::CHARS) character string. This is the same as
+    /// [`to_string`](ToString::to_string).
+    pub fn decode(self) -> String { C::decode_word(self.word) }
+
+    fn value(self) -> u64 { self.word.into() }
+}
+
+impl<C: Codec> fmt::Display for Rad50Word<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.pad(&self.decode()) }
+}
+
+impl<C: Codec> fmt::Debug for Rad50Word<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "Rad50Word({}, {:?})", self.value(), self.decode()) }
+}
+
+impl<C: Codec> FromStr for Rad50Word<C> {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> { Self::encode(s) }
+}
+
+#[cfg(feature = "defmt")]
+impl<C: Codec> defmt::Format for Rad50Word<C> {
+    fn format(&self, f: defmt::Formatter<'_>) { defmt::write!(f, "Rad50Word({=u64}, {=str})", self.value(), self.decode().as_str()) }
+}
+
+// Not derived because derive would want C (a marker type) to implement these, and not C::Word.
+impl<C: Codec> Clone      for Rad50Word<C> { fn clone(&self) -> Self { *self } }
+impl<C: Codec> Copy       for Rad50Word<C> {}
+impl<C: Codec> PartialEq  for Rad50Word<C> { fn eq(&self, other: &Self) -> bool { self.value() == other.value() } }
+impl<C: Codec> Eq         for Rad50Word<C> {}
+impl<C: Codec> PartialOrd for Rad50Word<C> { fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) } }
+impl<C: Codec> Ord        for Rad50Word<C> { fn cmp(&self, other: &Self) -> Ordering { self.value().cmp(&other.value()) } }
+impl<C: Codec> Hash       for Rad50Word<C> { fn hash<H: Hasher>(&self, state: &mut H) { self.value().hash(state) } }
+
+#[cfg(feature = "pdp11")]
+impl Default for Rad50Word<Pdp11> { fn default() -> Self { Self::new(0) } }
+#[cfg(feature = "pdp10")]
+impl Default for Rad50Word<Pdp10> { fn default() -> Self { Self::new(0) } }
+
+#[cfg(feature = "pdp11")]
+impl From<u16> for Rad50Word<Pdp11> { fn from(word: u16) -> Self { Self::new(word) } }
+#[cfg(feature = "pdp10")]
+impl From<u32> for Rad50Word<Pdp10> { fn from(word: u32) -> Self { Self::new(word) } }
+#[cfg(feature = "pdp11")]
+impl From<Rad50Word<Pdp11>> for u16 { fn from(word: Rad50Word<Pdp11>) -> Self { word.get() } }
+#[cfg(feature = "pdp10")]
+impl From<Rad50Word<Pdp10>> for u32 { fn from(word: Rad50Word<Pdp10>) -> Self { word.get() } }
+
+// Only generates valid words, so fuzzers don't waste their time on words that can't be decoded.
+#[cfg(feature = "arbitrary")]
+impl<'a, C: Codec> arbitrary::Arbitrary<'a> for Rad50Word<C> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let word = u.int_in_range(0..=C::MAX_WORD)?;
+        C::Word::try_from(word).map(Self::new).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+// Read and write as the raw word, so RADIX-50 fields can be embedded in binrw structure definitions.
+#[cfg(feature = "binrw")]
+impl<C: Codec> binrw::BinRead for Rad50Word<C> where for<'a> C::Word: binrw::BinRead<Args<'a> = ()> {
+    type Args<'a> = ();
+    fn read_options<R: std::io::Read + std::io::Seek>(reader: &mut R, endian: binrw::Endian, _: ()) -> binrw::BinResult<Self> {
+        C::Word::read_options(reader, endian, ()).map(Self::new)
+    }
+}
+
+#[cfg(feature = "binrw")]
+impl<C: Codec> binrw::BinWrite for Rad50Word<C> where for<'a> C::Word: binrw::BinWrite<Args<'a> = ()> {
+    type Args<'a> = ();
+    fn write_options<W: std::io::Write + std::io::Seek>(&self, writer: &mut W, endian: binrw::Endian, _: ()) -> binrw::BinResult<()> {
+        self.word.write_options(writer, endian, ())
+    }
+}
+
+#[cfg(all(test, feature = "pdp11"))]
+mod tests {
+    use super::*;
+    #[cfg(feature = "pdp10")]
+    use crate::{pdp10, pdp11};
+
+    #[cfg(feature = "pdp10")]
+    #[test]
+    fn round_trip() {
+        for s in ["", "A", "AB", "ABC", "SY", "999", "$.%"] {
+            let w: Rad50Word = s.parse().unwrap();
+            assert_eq!(w.get(), pdp11::encode_word(s).unwrap());
+            assert_eq!(w.to_string(), format!("{:3}", s));
+            assert_eq!(w.to_string().parse::<Rad50Word>(), Ok(w));
+        }
+        for s in ["", "FOO", "FOOBAR", "$.%123"] {
+            let w: Rad50Word<Pdp10> = s.parse().unwrap();
+            assert_eq!(w.get(), pdp10::encode_word(s).unwrap());
+            assert_eq!(w.to_string(), format!("{:6}", s));
+        }
+    }
+
+    #[test]
+    fn collation() {
+        let mut words: Vec<Rad50Word> = ["Z", "A", "9", " ", "$", "AB", "%"].iter().map(|s| s.parse().unwrap()).collect();
+        words.sort();
+        assert_eq!(words.iter().map(|w| w.decode()).collect::<Vec<_>>(), ["   ", "A  ", "AB ", "Z  ", "$  ", "%  ", "9  "]);
+    }
+
+    #[test]
+    fn formatting() {
+        let w = Rad50Word::<Pdp11>::from(1683);
+        assert_eq!(format!("[{}]", w), "[ABC]");
+        assert_eq!(format!("[{:>5}]", w), "[  ABC]");
+        assert_eq!(format!("{:?}", w), r#"Rad50Word(1683, "ABC")"#);
+        assert_eq!(Rad50Word::<Pdp11>::default().to_string(), "   ");
+    }
+
+    #[cfg(feature = "pdp10")]
+    #[test]
+    fn validity() {
+        assert!(Rad50Word::<Pdp11>::from(pdp11::MAX_WORD).is_valid());
+        assert!(!Rad50Word::<Pdp11>::from(u16::MAX).is_valid());
+        assert!(!Rad50Word::<Pdp10>::from(u32::MAX).is_valid());
+        assert_eq!(core::mem::size_of::<Rad50Word<Pdp11>>(), 2);
+        assert_eq!(core::mem::size_of::<Rad50Word<Pdp10>>(), 4);
+    }
+
+    #[cfg(all(feature = "arbitrary", feature = "pdp10"))]
+    #[test]
+    fn arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+        let bytes = (0..=255).collect::<Vec<u8>>();
+        let mut u = Unstructured::new(&bytes);
+        while !u.is_empty() {
+            assert!(Rad50Word::<Pdp11>::arbitrary(&mut u).unwrap().is_valid());
+            assert!(Rad50Word::<Pdp10>::arbitrary(&mut u).unwrap().is_valid());
+        }
+    }
+
+    #[cfg(all(feature = "binrw", feature = "pdp10"))]
+    #[test]
+    fn binrw() {
+        use binrw::{BinReaderExt, BinWriterExt, io::Cursor};
+        let mut cursor = Cursor::new(vec![0x93, 0x06, 0x7a, 0xa8]);
+        let w: Rad50Word = cursor.read_le().unwrap();
+        assert_eq!(w.to_string(), "ABC");
+        let w: Rad50Word = cursor.read_be().unwrap();
+        assert_eq!(w.get(), 0x7aa8);
+
+        let mut cursor = Cursor::new(vec![]);
+        cursor.write_le(&Rad50Word::<Pdp10>::encode("FOOBAR").unwrap()).unwrap();
+        assert_eq!(cursor.into_inner(), pdp10::encode_word("FOOBAR").unwrap().to_le_bytes());
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn bytemuck() {
+        use bytemuck::TransparentWrapper;
+        let words = [1683_u16, 31400];
+        let bytes: &[u8] = bytemuck::cast_slice(&words);
+        let wrapped = Rad50Word::<Pdp11>::wrap_slice(bytemuck::cast_slice::<u8, u16>(bytes));
+        assert_eq!(wrapped.iter().map(|w| w.to_string()).collect::<Vec<_>>(), ["ABC", "SY "]);
+    }
+
+    #[cfg(all(feature = "zerocopy", feature = "pdp10"))]
+    #[test]
+    fn zerocopy() {
+        use zerocopy::{FromBytes, IntoBytes};
+        let bytes = [1683_u16, 31400].iter().flat_map(|w| w.to_ne_bytes()).collect::<Vec<_>>();
+        let words = <[Rad50Word<Pdp11>]>::ref_from_bytes(&bytes).unwrap();
+        assert_eq!(words.iter().map(|w| w.to_string()).collect::<Vec<_>>(), ["ABC", "SY "]);
+        assert_eq!(words.as_bytes(), bytes);
+        assert!(<[Rad50Word<Pdp10>]>::ref_from_bytes(&bytes[..3]).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+// XXDP directory entries
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! XXDP diagnostic media directories.
+//!
+//! XXDP (and XXDP+) disks and tapes keep their file list in user file directory (UFD) blocks: a link word
+//! pointing at the next UFD block, then 28 entries of 9 words each. An entry's file name is stored the DOS-11
+//! way, 2 [PDP-11 RADIX-50][pdp11] words for the name and 1 for the extension, followed by the date and the
+//! file's location. Unused entries have an all-zero name.
+//!
+//! [pdp11]: crate::pdp11::RADIX50_DECODE
+//!
+//! # Examples
+//! ```
+//! # use radix50::xxdp;
+//! let mut ufd = [0u16; 256];
+//! ufd[1..10].copy_from_slice(&[7457, 20840, 30462, 12045, 0, 230, 20, 249, 0]);
+//! for entry in xxdp::entries(&ufd) {
+//!     println!("{:10} {:?} {:5} {:5}", entry.filename(), entry.date(), entry.start, entry.length);
+//! }
+//! let entry = xxdp::entries(&ufd).next().unwrap();
+//! assert_eq!(entry.filename(), "DZQMA.SAV");
+//! assert_eq!(entry.date(), Some((1982, 45)));
+//! ```
+
+use alloc::string::String;
+
+use crate::{dos11, rt11};
+
+/// How many words each directory entry takes.
+pub const ENTRY_WORDS: usize = 9;
+
+/// How many directory entries fit in a UFD block (after its link word).
+pub const ENTRIES_PER_BLOCK: usize = 28;
+
+/// A used XXDP directory entry.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct Entry {
+    /// The file name: 2 words of name and 1 of extension.
+    pub name: [u16; 3],
+    /// The raw date word (see [`Entry::date`]).
+    pub date_word: u16,
+    /// The file's first block.
+    pub start: u16,
+    /// How many blocks the file takes.
+    pub length: u16,
+    /// The file's last block.
+    pub last: u16,
+}
+
+impl Entry {
+    /// Parse a 9 word directory entry. Returns `None` for an unused entry.
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::xxdp::Entry;
+    /// let entry = Entry::from_words(&[7457, 20840, 30462, 12045, 0, 230, 20, 249, 0]).unwrap();
+    /// assert_eq!(entry.filename(), "DZQMA.SAV");
+    /// assert_eq!((entry.start, entry.length, entry.last), (230, 20, 249));
+    /// assert_eq!(Entry::from_words(&[0; 9]), None);
+    /// ```
+    pub fn from_words(words: &[u16; ENTRY_WORDS]) -> Option<Entry> {
+        if words[..3] == [0; 3] { return None }
+        Some(Entry { name: [words[0], words[1], words[2]], date_word: words[3], start: words[5], length: words[6], last: words[7] })
+    }
+
+    /// The file name as `NAME.EXT`, with the padding trimmed (see [`rt11::decode_filename`]).
+    pub fn filename(&self) -> String { rt11::decode_filename(&self.name) }
+
+    /// The date, as a `(year, day of the year)` pair. XXDP uses the DOS-11 date format (see
+    /// [`dos11::decode_date`]).
+    ///
+    /// # Examples
+    /// ```
+    /// # use radix50::xxdp::Entry;
+    /// let entry = Entry { name: [1600, 0, 0], date_word: 17365, start: 0, length: 0, last: 0 };
+    /// assert_eq!(entry.date(), Some((1987, 365)));
+    /// assert_eq!(Entry { date_word: 0, ..entry }.date(), None);
+    /// ```
+    pub fn date(&self) -> Option<(u16, u16)> { dos11::decode_date(self.date_word) }
+}
+
+/// Iterate over the used entries in a UFD block, skipping its link word.
+///
+/// # Examples
+/// ```
+/// # use radix50::xxdp;
+/// assert_eq!(xxdp::entries(&[0; 256]).count(), 0);
+/// ```
+pub fn entries(block: &[u16; 256]) -> impl Iterator<Item = Entry> + '_ {
+    block[1..].chunks_exact(ENTRY_WORDS)
+              .take(ENTRIES_PER_BLOCK)
+              .filter_map(|words| Entry::from_words(words.try_into().expect("chunks are ENTRY_WORDS long")))
+}
+
+/// The block number of the next UFD block (the first word of `block`), or `None` if this is the last one.
+///
+/// # Examples
+/// ```
+/// # use radix50::xxdp;
+/// let mut ufd = [0u16; 256];
+/// assert_eq!(xxdp::next_block(&ufd), None);
+/// ufd[0] = 4;
+/// assert_eq!(xxdp::next_block(&ufd), Some(4));
+/// ```
+pub fn next_block(block: &[u16; 256]) -> Option<u16> { Some(block[0]).filter(|&b| b != 0) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ufd_block() {
+        let mut ufd = [0u16; 256];
+        let names = ["HELP.TXT", "XXDPSM.SYS", "ZZZZZZ.BIN"];
+        for (n, slot) in [0, 5, 27].into_iter().enumerate() {
+            let at = 1 + slot * ENTRY_WORDS;
+            ufd[at..at + 3].copy_from_slice(&rt11::encode_filename(names[n]).unwrap());
+            ufd[at + 5] = n as u16 + 100;
+        }
+        let found: alloc::vec::Vec<_> = entries(&ufd).map(|e| (e.filename(), e.start)).collect();
+        assert_eq!(found, [("HELP.TXT".into(), 100), ("XXDPSM.SYS".into(), 101), ("ZZZZZZ.BIN".into(), 102)]);
+        ufd[255] = 1; // Past the last entry
+        assert_eq!(entries(&ufd).count(), 3);
+    }
+}
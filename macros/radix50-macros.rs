@@ -0,0 +1,66 @@
+// Compile time RADIX-50 literals
+//
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+// License: MIT (see LICENSE.md file)
+
+//! Macros that RADIX-50 encode string literals at compile time.
+//!
+//! `rad50!` expands to a `[u16; N]` of [PDP-11 words](radix50::pdp11::encode) and `rad50_pdp10!` expands to a
+//! `[u32; N]` of [PDP-10 words](radix50::pdp10::encode). Since they're just arrays, they can initialize `const`s and
+//! `static`s, and a string that can't be encoded is a compile error (pointing at the bad character, where the
+//! compiler allows it) instead of a runtime `unwrap()`.
+//!
+//! # Examples
+//! ```
+//! use radix50_macros::{rad50, rad50_pdp10};
+//!
+//! const NAME: [u16; 5] = rad50!("THIS IS A TEST");
+//! assert_eq!(NAME, [32329, 30409, 30401, 805, 31200]);
+//! assert_eq!(rad50_pdp10!("THIS IS A TEST"), [3119342419, 2970305215, 3046400000]);
+//! assert_eq!(rad50!(""), [0_u16; 0]);
+//! ```
+//!
+//! ```compile_fail
+//! let words = radix50_macros::rad50!("this is a test");
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{ToTokens, quote};
+use radix50::{Codec, Error, pdp10::Pdp10, pdp11::Pdp11};
+use syn::{LitStr, parse_macro_input};
+
+/// Encode a string literal into an array of [PDP-11 RADIX-50 words](radix50::pdp11::encode) at compile time.
+#[proc_macro]
+pub fn rad50(input: TokenStream) -> TokenStream {
+    encode::<Pdp11>(parse_macro_input!(input as LitStr), "PDP-11", quote!(u16))
+}
+
+/// Encode a string literal into an array of [PDP-10 RADIX-50 words](radix50::pdp10::encode) at compile time.
+#[proc_macro]
+pub fn rad50_pdp10(input: TokenStream) -> TokenStream {
+    encode::<Pdp10>(parse_macro_input!(input as LitStr), "PDP-10", quote!(u32))
+}
+
+fn encode<C: Codec>(lit: LitStr, name: &str, word: proc_macro2::TokenStream) -> TokenStream where C::Word: ToTokens {
+    match C::encode(&lit.value()) {
+        // Spell out the type so that an empty string still has one.
+        Ok(words) => { let len = words.len(); quote!({ const WORDS: [#word; #len] = [#(#words),*]; WORDS }).into() },
+        Err(e@Error::IllegalChar { char, pos }) => {
+            let message = format!("{} (not in the {} RADIX-50 character set)", e, name);
+            syn::Error::new(char_span(&lit, char, pos), message).to_compile_error().into()
+        },
+        Err(e) => syn::Error::new(lit.span(), e).to_compile_error().into(),
+    }
+}
+
+// Point at the bad character itself if we can. That needs a plain literal (no escapes or raw string markers,
+// so that the source bytes line up with the value) and a compiler that supports subspans (currently only nightly).
+// Otherwise, point at the whole literal.
+fn char_span(lit: &LitStr, char: char, pos: usize) -> Span {
+    let token = lit.token();
+    let value = lit.value();
+    if token.to_string() != format!("\"{}\"", value) { return lit.span() }
+    // Everything before the first illegal character is legal, and legal characters are all ASCII.
+    token.subspan(pos..pos + char.len_utf8()).unwrap_or_else(|| lit.span())
+}
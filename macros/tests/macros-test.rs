@@ -0,0 +1,21 @@
+// Copyright © 2024 David Caldwell <david@porkrind.org>
+
+use radix50::{pdp10, pdp11};
+use radix50_macros::{rad50, rad50_pdp10};
+
+static TABLE: [[u16; 2]; 2] = [rad50!("$DSW"), rad50!("R0    ")];
+
+#[test]
+fn matches_runtime_encoding() {
+    assert_eq!(rad50!("THIS IS A TEST"), pdp11::encode("THIS IS A TEST").unwrap()[..]);
+    assert_eq!(rad50_pdp10!("THIS IS A TEST"), pdp10::encode("THIS IS A TEST").unwrap()[..]);
+    assert_eq!(rad50!("A"), [1600]);
+    assert_eq!(rad50_pdp10!("$.%0123456789"), pdp10::encode("$.%0123456789").unwrap()[..]);
+    assert_eq!(TABLE.map(pdp11::decode), ["$DSW  ", "R0    "]);
+}
+
+#[test]
+fn escapes() {
+    assert_eq!(rad50!("A\x42C"), rad50!("ABC"));
+    assert_eq!(rad50!(r"ABC"), rad50!("ABC"));
+}